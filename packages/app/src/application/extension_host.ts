@@ -12,7 +12,9 @@ import type {
     RuntimeViewError,
     InvView_PouchList,
     InvView_Gdt,
+    InvView_CrashReport,
     InvView_Overworld,
+    InvView_SaveComparison,
     ItemDragData,
 } from "@pistonite/skybook-api";
 import {
@@ -218,6 +220,24 @@ class ExtensionAppHost implements ExtensionApp {
         return await this.runtime.getCrashInfo(script, taskId, bytePos);
     }
 
+    public async getCrashReport(
+        taskId: string,
+        inputScript: string | undefined,
+        charPos: number | undefined,
+    ): WxPromise<MaybeAborted<InvView_CrashReport>> {
+        const [script, bytePos] = convertScriptAndCharPosArg(inputScript, charPos);
+        const result = await this.runtime.getCrashReport(script, taskId, bytePos);
+        return mapMaybeAbortedResult(result, (report) => {
+            // convert the span of the step from byte positions to character positions
+            const bytePosToCharPos = createBytePosToCharPosArray(script);
+            const [start, end] = report.span;
+            return {
+                ...report,
+                span: [bytePosToCharPos[start], bytePosToCharPos[end]],
+            };
+        });
+    }
+
     public async getSaveNames(
         taskId: string,
         inputScript: string | undefined,
@@ -237,6 +257,15 @@ class ExtensionAppHost implements ExtensionApp {
         return await this.runtime.getSaveInventory(script, taskId, bytePos, name);
     }
 
+    public async getSaveComparison(
+        taskId: string,
+        inputScript: string | undefined,
+        charPos: number | undefined,
+    ): WxPromise<MaybeAborted<Result<InvView_SaveComparison, RuntimeViewError>>> {
+        const [script, bytePos] = convertScriptAndCharPosArg(inputScript, charPos);
+        return await this.runtime.getSaveComparison(script, taskId, bytePos);
+    }
+
     public async handleItemDrag(data: ItemDragData | undefined): WxPromise<void> {
         const { setDragData } = useSessionStore.getState();
         setDragData(data);
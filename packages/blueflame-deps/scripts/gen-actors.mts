@@ -10,6 +10,8 @@ const DEFAULT_USE = PouchItemUse.Item;
 const typeMap = {};
 const useMap = {};
 const lifeMap = {};
+const sellingPriceMap = {};
+const buyingPriceMap = {};
 const canStackActors = new Set<string>();
 const cannotSellActors = new Set<string>();
 const canUseActors = new Set<string>();
@@ -27,6 +29,14 @@ for (const actor in ActorDataMap) {
         const life = getActorParam(actor, "generalLife");
         lifeMap[actor] = life;
     }
+    const sellingPrice = getActorParam(actor, "itemSellingPrice");
+    if (sellingPrice >= 0) {
+        sellingPriceMap[actor] = sellingPrice;
+    }
+    const buyingPrice = getActorParam(actor, "itemBuyingPrice");
+    if (buyingPrice >= 0) {
+        buyingPriceMap[actor] = buyingPrice;
+    }
     const canStack = getActorParam(actor, "canStack");
     if (canStack) {
         canStackActors.add(actor);
@@ -44,6 +54,8 @@ for (const actor in ActorDataMap) {
 console.log(Object.keys(typeMap).length, "actors found with non-default type");
 console.log(Object.keys(useMap).length, "actors found with non-default use");
 console.log(Object.keys(lifeMap).length, "weapon actor life values found");
+console.log(Object.keys(sellingPriceMap).length, "actor selling prices found");
+console.log(Object.keys(buyingPriceMap).length, "actor buying prices found");
 console.log(canStackActors.size, "stackable actors found");
 console.log(cannotSellActors.size, "non-sellable actors found");
 console.log(canUseActors.size, "eatable actors found");
@@ -72,6 +84,20 @@ for (const actor in lifeMap) {
 }
 lines.push("};");
 
+lines.push("#[rustfmt::skip]");
+lines.push("pub static SELLING_PRICE_MAP: phf::Map<&'static str, i32> = phf::phf_map! {");
+for (const actor in sellingPriceMap) {
+    lines.push(`    "${actor}" => ${sellingPriceMap[actor]},`);
+}
+lines.push("};");
+
+lines.push("#[rustfmt::skip]");
+lines.push("pub static BUYING_PRICE_MAP: phf::Map<&'static str, i32> = phf::phf_map! {");
+for (const actor in buyingPriceMap) {
+    lines.push(`    "${actor}" => ${buyingPriceMap[actor]},`);
+}
+lines.push("};");
+
 lines.push("#[rustfmt::skip]");
 lines.push("pub static STACKABLE_ACTORS_SORTED: &[&str] = &[");
 const canStackActorsSorted = [...canStackActors];
@@ -19,6 +19,18 @@ pub fn get_weapon_general_life(actor: &str) -> Option<i32> {
     crate::generated::actor::WEAPON_LIFE_MAP.get(actor).copied()
 }
 
+/// Get the `itemSellingPrice` of the actor from static data,
+/// if the actor has one
+pub fn get_selling_price(actor: &str) -> Option<i32> {
+    crate::generated::actor::SELLING_PRICE_MAP.get(actor).copied()
+}
+
+/// Get the `itemBuyingPrice` of the actor from static data,
+/// if the actor has one
+pub fn get_buying_price(actor: &str) -> Option<i32> {
+    crate::generated::actor::BUYING_PRICE_MAP.get(actor).copied()
+}
+
 /// Get if the actor has `CanStack` tag from static data
 pub fn can_stack(actor: &str) -> bool {
     crate::generated::actor::STACKABLE_ACTORS_SORTED
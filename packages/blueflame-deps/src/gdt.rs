@@ -59,7 +59,7 @@ const _: () = {
 };
 
 /// Trait for flag types that can be used in TriggerParam
-pub trait FlagType: Clone + PartialEq {
+pub trait FlagType: Clone + PartialEq + std::fmt::Debug {
     /// The shared storage type of the flag - each flag stores a shared value for the initial value
     type SharedType: Clone + PartialEq + 'static;
     /// The static storage type of the flag - used for initializing the flag value
@@ -217,6 +217,16 @@ impl<T: FlagType> Flag<T> {
     pub fn reset(&mut self) {
         self.value = T::from_shared(&self.initial_value);
     }
+    /// Replace the initial value, and reset the flag to it
+    pub fn set_initial_value(&mut self, initial_value: T::StaticType) {
+        self.initial_value = T::static_to_shared(initial_value);
+        self.reset();
+    }
+    /// Replace the initial value with a value read at runtime, and reset the flag to it
+    pub fn set_initial_shared_value(&mut self, initial_value: T::SharedType) {
+        self.initial_value = initial_value;
+        self.reset();
+    }
 
     pub fn hash(&self) -> i32 {
         self.hash
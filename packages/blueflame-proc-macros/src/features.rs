@@ -1,5 +1,4 @@
 use pm::pre::*;
-use syn::spanned::Spanned as _;
 
 use crate::util;
 
@@ -61,15 +60,31 @@ fn expand_internal(mut input: syn::DeriveInput) -> pm::Result<TokenStream2> {
 }
 
 pub fn expand_enable_macro(input: TokenStream) -> TokenStream {
-    let input = syn::parse_macro_input!(input as syn::LitStr);
+    let input = syn::parse_macro_input!(input as EnableMacroInput);
 
-    let feature_name = input.value();
-    let feature_ident = syn::Ident::new(&feature_name.replace("-", "_"), feature_name.span());
+    let features = input.features;
+    let feature_name = input.name.value();
+    let feature_ident = syn::Ident::new(&feature_name.replace("-", "_"), input.name.span());
 
     let blueflame = util::crate_ident();
     let expanded = pm::quote! {
-        #blueflame::env::is_feature_enabled(#blueflame::env::Feature::#feature_ident)
+        (#features).contains(#blueflame::env::Feature::#feature_ident)
     };
 
     expanded.into()
 }
+
+/// Input to `enabled!`: `features_expr, "feature-name"`
+struct EnableMacroInput {
+    features: syn::Expr,
+    name: syn::LitStr,
+}
+
+impl syn::parse::Parse for EnableMacroInput {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let features = input.parse()?;
+        input.parse::<syn::Token![,]>()?;
+        let name = input.parse()?;
+        Ok(Self { features, name })
+    }
+}
@@ -9,13 +9,20 @@ pub fn derive_mem_object(input: TokenStream) -> TokenStream {
 }
 mod mem_object;
 
+/// Derive macro for MemInspect, using the same layout attributes as MemObject
+#[proc_macro_derive(MemInspect, attributes(offset, size))]
+pub fn derive_mem_inspect(input: TokenStream) -> TokenStream {
+    mem_inspect::expand(input)
+}
+mod mem_inspect;
+
 #[proc_macro_attribute]
 pub fn derive_feature_set(_attr: TokenStream, input: TokenStream) -> TokenStream {
     features::expand(input)
 }
 mod features;
 
-/// Macro to check if a feature is enabled
+/// Macro to check if a feature is enabled in a feature set, e.g. `enabled!(features, "mem-permission")`
 #[proc_macro]
 pub fn enabled(input: TokenStream) -> TokenStream {
     features::expand_enable_macro(input)
@@ -0,0 +1,65 @@
+use pm::pre::*;
+
+use crate::util;
+
+pub fn expand(input: TokenStream) -> TokenStream {
+    let input = syn::parse_macro_input!(input as syn::DeriveInput);
+    pm::flatten(expand_internal(input))
+}
+
+fn expand_internal(input: syn::DeriveInput) -> pm::Result<TokenStream2> {
+    let blueflame = util::crate_ident();
+
+    let fields = util::get_struct_fields(&input)?;
+    let struct_name = &input.ident;
+    let struct_name_str = struct_name.to_string();
+
+    let mut fields_ordered_by_offset = Vec::with_capacity(fields.named.len());
+    for field in fields.named.iter() {
+        let offset = util::get_field_offset(field)?;
+        let field_size = util::get_field_size(field)?;
+        fields_ordered_by_offset.push((offset, field_size, field));
+    }
+    fields_ordered_by_offset.sort_by_key(|(offset, _, _)| *offset);
+
+    let mut children_impl = TokenStream2::new();
+    for (offset, field_size, field) in fields_ordered_by_offset {
+        let field_name = field.ident.as_ref().unwrap();
+        let field_name_str = field_name.to_string();
+        let field_type = &field.ty;
+        let size_tokens = match field_size {
+            None => pm::quote! { <#field_type as #blueflame::memory::MemObject>::SIZE },
+            Some((size, _)) => pm::quote! { #size },
+        };
+        children_impl.extend(pm::quote! {
+            {
+                let mut child = #blueflame::memory::InspectNode::new(
+                    #field_name_str,
+                    <#field_type as #blueflame::memory::MemInspect>::type_name(),
+                    node.addr + #offset as u64,
+                    #size_tokens,
+                );
+                <#field_type as #blueflame::memory::MemInspect>::inspect_into(&mut child, memory)?;
+                node.children.push(child);
+            }
+        });
+    }
+
+    let expanded = pm::quote! {
+        #[automatically_derived]
+        impl #blueflame::memory::MemInspect for #struct_name {
+            fn type_name() -> ::std::string::String {
+                #struct_name_str.to_string()
+            }
+            fn inspect_into(
+                node: &mut #blueflame::memory::InspectNode,
+                memory: &#blueflame::memory::Memory,
+            ) -> ::std::result::Result<(), #blueflame::memory::Error> {
+                #children_impl
+                Ok(())
+            }
+        }
+    };
+
+    Ok(expanded)
+}
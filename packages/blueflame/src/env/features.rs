@@ -6,10 +6,10 @@ pub use blueflame_proc_macros::enabled;
 #[doc(hidden)]
 pub use enumset;
 
-/// BlueFlame features that can be enabled or disabled at init time.
-/// They should not be changed after init
+/// BlueFlame features that can be enabled or disabled for each process.
 ///
-/// TODO manual
+/// In scripts, features can be toggled with the `!system` command,
+/// for example `!system [enable-feature=mem-track-free]`
 #[rustfmt::skip] // to be more readable
 #[allow(non_camel_case_types)] // to be more readable
 #[derive(Debug, Hash, EnumSetType)]
@@ -28,6 +28,9 @@ pub enum Feature {
     #[on] mem_permission,
     /// If enabled, accessing unallocated location on the heap will not be allowed
     #[on] mem_strict_heap,
+    /// Track live allocations on the heap, so freeing memory is possible.
+    /// Freed memory is poisoned, and accessing it will not be allowed
+    mem_track_free,
 
     /// If enabled, jumping to the middle of a replace-hooked code will not be allowed
     #[on] strict_replace_hook,
@@ -56,36 +59,11 @@ pub enum Feature {
     #[on] check_stack_corruption,
 }
 
-pub type FeatureSet = enumset::EnumSet<Feature>;
-
-static mut FEATURES: FeatureSet = Feature::default_const();
-
-/// Set the features for the BlueFlame core
+/// Set of enabled [`Feature`]s.
 ///
-/// # Safety
-/// The features is simply a static bitset in memory to allow
-/// for efficient reads. There is no locking. Initializing
-/// features flag should be done before using anything in the crate.
-///
-/// Before this is called, the "default" set of features is used.
-pub unsafe fn init_features(features: FeatureSet) {
-    unsafe {
-        FEATURES = features;
-    }
-}
-
-/// Check if a feature is enabled
-///
-/// This is somewhat verbose, so there is a `feature!` macro
-/// for it. `features::is_enabled(features::Feature::mem_strict_region)`
-/// is the same as `enabled!("mem-strict-region")`. Kebab case is used
+/// Features are part of the process. Use [`Process::features`](crate::processor::Process::features)
+/// to get the features of a process, and the `enabled!` macro to check
+/// a feature in the set: `enabled!(features, "mem-strict-section")` is the same as
+/// `features.contains(Feature::mem_strict_section)`. Kebab case is used
 /// to be consistent with the style used in scripts
-#[inline(always)]
-pub fn is_feature_enabled(feature: Feature) -> bool {
-    unsafe {
-        // SAFETY: we are just reading a number
-        // if people read the thing above, it will be safe
-        #[allow(static_mut_refs)]
-        FEATURES.contains(feature)
-    }
-}
+pub type FeatureSet = enumset::EnumSet<Feature>;
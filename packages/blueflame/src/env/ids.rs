@@ -21,12 +21,72 @@ use cu::pre::*;
 pub enum DataId {
     /// Actor/ActorInfo.product.byml (decompressed version of the sbyml)
     ActorInfoByml,
+    /// Function symbols in the CSV format of the decomp project,
+    /// used to resolve addresses in stack traces and crash reports
+    SymbolMap,
+    /// Cooking/CookData.byml (decompressed), the recipes and cooking effects
+    CookDataByml,
+    /// GameData/ShopGameDataInfo.byml (decompressed), the items sold in shops
+    ShopDataByml,
+    /// GameData/gamedata.sarc (decompressed), the bgdata files with the
+    /// definitions and initial values of the game data flags
+    GameDataSarc,
+}
+
+impl DataId {
+    /// All data types, in the order they are added to the image
+    pub const ALL: &[Self] = &[
+        Self::ActorInfoByml,
+        Self::SymbolMap,
+        Self::CookDataByml,
+        Self::ShopDataByml,
+        Self::GameDataSarc,
+    ];
+
+    /// Short name of the data, used in the command line tools
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::ActorInfoByml => "actor-info",
+            Self::SymbolMap => "symbols",
+            Self::CookDataByml => "cook-data",
+            Self::ShopDataByml => "shop-data",
+            Self::GameDataSarc => "gamedata",
+        }
+    }
+
+    /// Path of the file in the romfs of the game, if the data comes from the game.
+    ///
+    /// The files are Yaz0-compressed in the romfs, and are stored decompressed in the image.
+    /// If the file is inside a pack (see [`pack_path`](Self::pack_path)), this is the path
+    /// inside the pack
+    pub const fn romfs_path(self) -> Option<&'static str> {
+        match self {
+            Self::ActorInfoByml => Some("Actor/ActorInfo.product.sbyml"),
+            Self::SymbolMap => None,
+            Self::CookDataByml => Some("Cooking/CookData.sbyml"),
+            Self::ShopDataByml => Some("GameData/ShopGameDataInfo.sbyml"),
+            Self::GameDataSarc => Some("GameData/gamedata.ssarc"),
+        }
+    }
+
+    /// Path of the pack (SARC) in the romfs that contains the file, if the file
+    /// is not directly in the romfs
+    pub const fn pack_path(self) -> Option<&'static str> {
+        match self {
+            Self::GameDataSarc => Some("Pack/Bootup.pack"),
+            _ => None,
+        }
+    }
 }
 
 impl From<ArchivedDataId> for DataId {
     fn from(archived: ArchivedDataId) -> Self {
         match archived {
             ArchivedDataId::ActorInfoByml => DataId::ActorInfoByml,
+            ArchivedDataId::SymbolMap => DataId::SymbolMap,
+            ArchivedDataId::CookDataByml => DataId::CookDataByml,
+            ArchivedDataId::ShopDataByml => DataId::ShopDataByml,
+            ArchivedDataId::GameDataSarc => DataId::GameDataSarc,
         }
     }
 }
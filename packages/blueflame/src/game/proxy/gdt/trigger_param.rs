@@ -2,11 +2,32 @@ use std::sync::Arc;
 
 use crate::game::{gdt, singleton_instance};
 use crate::memory::{self, Memory, ProxyObject, Ptr};
+use crate::program::{GdtDefaultTable, GdtDefaultValue};
 
 pub fn get_hash(name: &str) -> i32 {
     crc32fast::hash(name.as_bytes()) as i32
 }
 
+/// Set the initial values of the flags in the list that are in the table,
+/// returning the number of values that cannot be converted to the flag type
+fn apply_default_list<T: gdt::FlagType>(
+    list: &mut Arc<gdt::FlagList<T>>,
+    defaults: &GdtDefaultTable,
+    convert: impl Fn(&gdt::Flag<T>, &GdtDefaultValue) -> Option<T::SharedType>,
+) -> usize {
+    let mut skipped = 0;
+    for flag in Arc::make_mut(list) {
+        let Some(value) = defaults.get(flag.hash()) else {
+            continue;
+        };
+        match convert(flag, value) {
+            Some(value) => flag.set_initial_shared_value(value),
+            None => skipped += 1,
+        }
+    }
+    skipped
+}
+
 /// Get the trigger param raw pointer from GDTM instance
 pub fn trigger_param_ptr(memory: &Memory) -> Result<u64, memory::Error> {
     let gdt_manager = singleton_instance!(gdtm(memory))?;
@@ -45,6 +66,84 @@ impl TriggerParam {
     pub fn loaded() -> Self {
         Builder.build()
     }
+
+    /// Set the initial values of the flags from the game data in the program image.
+    ///
+    /// Flags not in the table keep the initial values compiled into the simulator.
+    /// Values that cannot be applied (wrong type or array length) are skipped
+    /// and reported
+    pub fn apply_defaults(&mut self, defaults: &GdtDefaultTable) {
+        use GdtDefaultValue as V;
+        let mut skipped = defaults.skipped();
+        skipped += apply_default_list(&mut self.bool_flags, defaults, |_, v| match v {
+            V::Bool(x) => Some(*x),
+            _ => None,
+        });
+        skipped += apply_default_list(&mut self.s32_flags, defaults, |_, v| match v {
+            V::S32(x) => Some(*x),
+            _ => None,
+        });
+        skipped += apply_default_list(&mut self.f32_flags, defaults, |_, v| match v {
+            V::F32(x) => Some(*x),
+            _ => None,
+        });
+        for list in [
+            &mut self.string32_flags,
+            &mut self.string64_flags,
+            &mut self.string256_flags,
+        ] {
+            skipped += apply_default_list(list, defaults, |_, v| match v {
+                V::String(x) => Some(Arc::clone(x)),
+                _ => None,
+            });
+        }
+        skipped += apply_default_list(&mut self.vector2f_flags, defaults, |_, v| match v {
+            V::Vector2f(x) => Some(*x),
+            _ => None,
+        });
+        skipped += apply_default_list(&mut self.vector3f_flags, defaults, |_, v| match v {
+            V::Vector3f(x) => Some(*x),
+            _ => None,
+        });
+        skipped += apply_default_list(&mut self.vector4f_flags, defaults, |_, v| match v {
+            V::Vector4f(x) => Some(*x),
+            _ => None,
+        });
+        // the array length is fixed by the flag definition
+        skipped += apply_default_list(&mut self.bool_array_flags, defaults, |f, v| match v {
+            V::BoolArray(x) if x.len() == f.len() => Some(Arc::clone(x)),
+            _ => None,
+        });
+        skipped += apply_default_list(&mut self.s32_array_flags, defaults, |f, v| match v {
+            V::S32Array(x) if x.len() == f.len() => Some(Arc::clone(x)),
+            _ => None,
+        });
+        skipped += apply_default_list(&mut self.f32_array_flags, defaults, |f, v| match v {
+            V::F32Array(x) if x.len() == f.len() => Some(Arc::clone(x)),
+            _ => None,
+        });
+        for list in [
+            &mut self.string64_array_flags,
+            &mut self.string256_array_flags,
+        ] {
+            skipped += apply_default_list(list, defaults, |f, v| match v {
+                V::StringArray(x) if x.len() == f.len() => Some(Arc::clone(x)),
+                _ => None,
+            });
+        }
+        skipped += apply_default_list(&mut self.vector2f_array_flags, defaults, |f, v| match v {
+            V::Vector2fArray(x) if x.len() == f.len() => Some(Arc::clone(x)),
+            _ => None,
+        });
+        skipped += apply_default_list(&mut self.vector3f_array_flags, defaults, |f, v| match v {
+            V::Vector3fArray(x) if x.len() == f.len() => Some(Arc::clone(x)),
+            _ => None,
+        });
+        if skipped > 0 {
+            cu::warn!("skipped {skipped} GDT flag initial values that cannot be applied");
+        }
+    }
+
     /// Get flag by CRC32 hash of its name
     pub fn by_hash<Fd: gdt::FlagDescriptor>(&self, hash: i32) -> Option<&gdt::Flag<Fd::T>> {
         self.get::<Fd, usize>(self.index_from_hash::<Fd>(hash)?)
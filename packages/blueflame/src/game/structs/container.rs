@@ -1,6 +1,6 @@
-use crate::memory::{self, MemObject, Memory, Ptr, mem};
+use crate::memory::{self, MemInspect, MemObject, Memory, Ptr, mem};
 
-#[derive(MemObject, Default, Clone, Copy)]
+#[derive(MemObject, MemInspect, Default, Clone, Copy)]
 #[size(0x10)]
 pub struct ListNode {
     #[offset(0x0)]
@@ -92,7 +92,7 @@ impl Ptr![ListNode] {
     }
 }
 
-#[derive(MemObject, Default, Clone)]
+#[derive(MemObject, MemInspect, Default, Clone)]
 #[size(0x18)]
 pub struct OffsetList {
     #[offset(0x0)]
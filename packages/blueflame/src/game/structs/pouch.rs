@@ -1,7 +1,7 @@
 use crate::game::{FixedSafeString40, ListNode, OffsetList, gdt};
-use crate::memory::{self, MemObject, Memory, Ptr, mem, offsetof};
+use crate::memory::{self, MemInspect, MemObject, Memory, Ptr, mem, offsetof};
 
-#[derive(MemObject, Clone)]
+#[derive(MemObject, MemInspect, Clone)]
 #[size(0x44808)]
 pub struct PauseMenuDataMgr {
     #[offset(0x0)]
@@ -29,16 +29,16 @@ pub struct PauseMenuDataMgr {
     pub mNumTabs: i32,
     #[offset(0x444a0)]
     pub mGrabbedItems: [GrabbedItemInfo; 5],
-    // #[offset(0x44518)]
-    // mRitoSoulItem: Ptr![PouchItem],
-    // #[offset(0x44520)]
-    // mGoronSoulItem: Ptr![PouchItem],
-    // #[offset(0x44528)]
-    // mZoraSoulItem: Ptr![PouchItem],
-    // #[offset(0x44530)]
-    // mGerudoSoulItem: Ptr![PouchItem],
-    // #[offset(0x44540)]
-    // mNewlyAddedItem: PouchItem,
+    #[offset(0x44518)]
+    pub mRitoSoulItem: Ptr![PouchItem],
+    #[offset(0x44520)]
+    pub mGoronSoulItem: Ptr![PouchItem],
+    #[offset(0x44528)]
+    pub mZoraSoulItem: Ptr![PouchItem],
+    #[offset(0x44530)]
+    pub mGerudoSoulItem: Ptr![PouchItem],
+    #[offset(0x44540)]
+    pub mNewlyAddedItem: PouchItem,
     #[offset(0x447d8)]
     pub mIsPouchForQuest: bool,
     #[offset(0x447e0)]
@@ -109,9 +109,45 @@ impl Ptr![PauseMenuDataMgr] {
     pub fn tabs(self) -> Ptr![Ptr![PouchItem][50]] {
         Ptr!(&self->mTabs).reinterpret_array()
     }
+
+    /// Get the addresses of all pointer fields (including vtables) in the PMDM,
+    /// for example, to relocate a memory dump
+    pub fn pointer_fields(self) -> Vec<u64> {
+        let mut out = vec![
+            Ptr!(&self->vtable).to_raw(),
+            Ptr!(&self->mList1.mStartEnd.mPrev).to_raw(),
+            Ptr!(&self->mList1.mStartEnd.mNext).to_raw(),
+            Ptr!(&self->mList2.mStartEnd.mPrev).to_raw(),
+            Ptr!(&self->mList2.mStartEnd.mNext).to_raw(),
+            Ptr!(&self->mLastAddedItem).to_raw(),
+        ];
+        let item_buffer = self.item_buffer();
+        for i in 0..420 {
+            item_buffer.ith(i).push_pointer_fields(&mut out);
+        }
+        let list_heads = Ptr!(&self->mListHeads).to_raw();
+        out.extend((0..7).map(|i| list_heads + i * 8));
+        let tabs = self.tabs();
+        out.extend((0..50).map(|i| tabs.ith(i).to_raw()));
+        let grabbed_items = self.grabbed_items();
+        for i in 0..5 {
+            let grabbed_item = grabbed_items.ith(i);
+            out.push(Ptr!(&grabbed_item->mItem).to_raw());
+        }
+        out.extend([
+            Ptr!(&self->mRitoSoulItem).to_raw(),
+            Ptr!(&self->mGoronSoulItem).to_raw(),
+            Ptr!(&self->mZoraSoulItem).to_raw(),
+            Ptr!(&self->mGerudoSoulItem).to_raw(),
+        ]);
+        Ptr!(&self->mNewlyAddedItem).push_pointer_fields(&mut out);
+        let equipped_weapons = self.equipped_weapons();
+        out.extend((0..4).map(|i| equipped_weapons.ith(i).to_raw()));
+        out
+    }
 }
 
-#[derive(MemObject, Default, Copy, Clone)]
+#[derive(MemObject, MemInspect, Default, Copy, Clone)]
 #[size(0x10)]
 pub struct GrabbedItemInfo {
     #[offset(0x0)]
@@ -122,7 +158,7 @@ pub struct GrabbedItemInfo {
     // _9: bool,
 }
 
-#[derive(MemObject, Default, Clone)]
+#[derive(MemObject, MemInspect, Default, Clone)]
 #[size(0x298)]
 pub struct PouchItem {
     #[offset(0x0)]
@@ -222,6 +258,24 @@ impl Ptr![PouchItem] {
         Ok(WeaponModifierInfo::default())
     }
 
+    /// Add the addresses of all pointer fields (including vtables) in the item
+    fn push_pointer_fields(self, out: &mut Vec<u64>) {
+        out.push(Ptr!(&self->vtable).to_raw());
+        out.push(Ptr!(&self->mListNode.mPrev).to_raw());
+        out.push(Ptr!(&self->mListNode.mNext).to_raw());
+        push_string_pointer_fields(Ptr!(&self->mName), out);
+        out.push(Ptr!(&self->mIngredients.mPtrs).to_raw());
+        out.push(Ptr!(&self->mIngredients.mNextFree).to_raw());
+        out.push(Ptr!(&self->mIngredients.mFirst).to_raw());
+        push_string_pointer_fields(Ptr!(&self->mIngredients.mStringOrNode_0), out);
+        push_string_pointer_fields(Ptr!(&self->mIngredients.mStringOrNode_1), out);
+        push_string_pointer_fields(Ptr!(&self->mIngredients.mStringOrNode_2), out);
+        push_string_pointer_fields(Ptr!(&self->mIngredients.mStringOrNode_3), out);
+        push_string_pointer_fields(Ptr!(&self->mIngredients.mStringOrNode_4), out);
+        let ptr_buffer = Ptr!(&self->mIngredients.mPtrBuffer).to_raw();
+        out.extend((0..5).map(|i| ptr_buffer + i * 8));
+    }
+
     pub fn ith_ingredient(
         self,
         i: u64,
@@ -235,8 +289,13 @@ impl Ptr![PouchItem] {
     }
 }
 
+fn push_string_pointer_fields(string: Ptr![FixedSafeString40], out: &mut Vec<u64>) {
+    out.push(Ptr!(&string->base.vtable).to_raw());
+    out.push(Ptr!(&string->base.mStringTop).to_raw());
+}
+
 #[allow(non_camel_case_types)]
-#[derive(MemObject, Default, Clone)]
+#[derive(MemObject, MemInspect, Default, Clone)]
 #[size(0x200)]
 pub struct FixedObjArray5_FixedSafeString40 {
     #[offset(0x0)]
@@ -272,7 +331,7 @@ pub struct WeaponModifierInfo {
     pub value: i32,
 }
 
-#[derive(MemObject, Clone)]
+#[derive(MemObject, MemInspect, Clone)]
 #[size(0x228)]
 pub struct CookItem {
     #[offset(0x0)]
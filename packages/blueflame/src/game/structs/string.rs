@@ -1,7 +1,7 @@
 #![allow(non_snake_case)]
-use crate::memory::{self, MemObject, Memory, Ptr};
+use crate::memory::{self, InspectNode, InspectValue, MemInspect, MemObject, Memory, Ptr};
 
-#[derive(MemObject, Default, Clone, Copy)]
+#[derive(MemObject, MemInspect, Default, Clone, Copy)]
 #[size(0x10)]
 pub struct SafeString {
     #[offset(0x0)]
@@ -120,6 +120,25 @@ impl std::fmt::Display for FixedSafeString40 {
     }
 }
 
+// the buffer is inspected as a string instead of 64 bytes
+impl MemInspect for FixedSafeString40 {
+    fn type_name() -> String {
+        "FixedSafeString40".to_string()
+    }
+    fn inspect_into(node: &mut InspectNode, memory: &Memory) -> Result<(), memory::Error> {
+        let value = Ptr!(<Self>(node.addr)).load(memory)?.to_string();
+        let base = SafeString::inspect("base", node.addr, memory)?;
+        let buffer_size = i32::inspect("mBufferSize", node.addr + 0x10, memory)?;
+        node.children.push(base);
+        node.children.push(buffer_size);
+        let mut buffer = InspectNode::new("mBuffer", "char[64]".to_string(), node.addr + 0x14, 64);
+        buffer.value = InspectValue::String(value.clone());
+        node.children.push(buffer);
+        node.value = InspectValue::String(value);
+        Ok(())
+    }
+}
+
 impl Default for FixedSafeString40 {
     // note this does NOT create a valid FixedSafeString40 in emulated memory
     // this is only to construct temporary default instance in Rust
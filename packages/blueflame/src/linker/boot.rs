@@ -2,12 +2,12 @@ use std::sync::Arc;
 
 use rkyv::rancor;
 
-use crate::env::{DlcVer, Environment, GameVer};
+use crate::env::{DlcVer, Environment, FeatureSet, GameVer};
 use crate::game::{Proxies, singleton};
 use crate::linker::{GameHooks, patch_memory};
 use crate::memory::{self, Memory, PAGE_SIZE, REGION_ALIGN, SimpleHeap, align_down, align_up};
 use crate::processor::{Cpu1, Cpu3, CrashReport, Process};
-use crate::program::ArchivedProgram;
+use crate::program::{ArchivedProgram, GameData, SymbolMap};
 
 /// Error that only happens during boot
 #[derive(Debug, Clone, thiserror::Error)]
@@ -16,6 +16,10 @@ pub enum Error {
     BadImage(String),
     #[error("PMDM address is impossible to satisfy: 0x{0:016x}")]
     InvalidPmdmAddress(u64),
+    #[error("memory dump is not valid: {0}")]
+    BadDump(String),
+    #[error("address is not in the program image: main+0x{0:08x}")]
+    NotInImage(u32),
     #[error("region overlap: {0} and {1}")]
     RegionOverlap(String, String),
     #[error("memory error: {0}")]
@@ -26,10 +30,12 @@ pub enum Error {
 
 /// Initialize memory for the process
 ///
-/// Return the memory state after all singletons are created and initialized
+/// Return the memory state after all singletons are created and initialized.
+/// The `features` are enabled for the process, including while initializing
 pub fn init_process(
     image: &ArchivedProgram,
     dlc_version: DlcVer,
+    features: FeatureSet,
     stack_start: u64,
     stack_size: u32,
     pmdm_address: u64,
@@ -123,13 +129,17 @@ pub fn init_process(
     // patch the memory
     cu::debug!("patching memory");
     patch_memory(&mut memory, env)?;
+    memory.set_features(features);
 
     cu::debug!("creating process");
+    let symbols = SymbolMap::from_program(image).map(Arc::new);
     let mut proc = Process::new(
         Arc::new(memory),
         Arc::new(Proxies::default()),
-        Arc::new(GameHooks),
+        Arc::new(GameHooks::new(symbols.as_deref())),
+        symbols,
     );
+    proc.set_game_data(Arc::new(GameData::from_program(image)));
 
     // create a temporary processor to initialize the singletons
     cu::debug!("creating cpu3");
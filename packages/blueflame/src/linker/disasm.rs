@@ -0,0 +1,221 @@
+use rkyv::rancor;
+
+use crate::env::{DlcVer, Environment, GameVer};
+use crate::linker::{Error, GameHooks, patched_instructions};
+use crate::program::{ArchivedProgram, SymbolMap};
+
+/// One instruction in the disassembly
+#[derive(Debug, Clone, PartialEq)]
+pub struct DisasmLine {
+    /// Offset of the instruction in the main module
+    pub main_offset: u32,
+    /// Bits of the instruction the emulator will run (after patching)
+    pub bits: u32,
+    /// Bits of the instruction in the program image, if it's patched by
+    /// [`patch_memory`](crate::linker::patch_memory)
+    pub original: Option<u32>,
+    /// Disassembled instruction, empty if it cannot be decoded
+    pub text: String,
+    /// Main offset of the target, if the instruction is a branch
+    /// with an immediate target
+    pub branch_target: Option<u32>,
+    /// The range `(main_offset, byte_size)` of the hook that replaces the instruction,
+    /// if the instruction is replaced by [`GameHooks`]
+    pub hook: Option<(u32, u32)>,
+}
+
+/// Disassembler for the main module in the program image.
+///
+/// The disassembly shows the code the emulator will actually run, i.e.
+/// with the instructions patched by the linker, and which ranges are
+/// replaced by hooks
+pub struct Disassembler<'a> {
+    image: &'a ArchivedProgram,
+    env: Environment,
+    symbols: Option<SymbolMap>,
+}
+
+impl<'a> Disassembler<'a> {
+    /// Create a disassembler for the image, using the symbols embedded
+    /// in the image, if any
+    pub fn new(image: &'a ArchivedProgram) -> Result<Self, Error> {
+        let ver = rkyv::deserialize::<GameVer, rancor::Error>(&image.ver)
+            .map_err(|e| Error::BadImage(e.to_string()))?;
+        // the code doesn't depend on the DLC version
+        let env = Environment::new(ver, DlcVer::None);
+        Ok(Self {
+            image,
+            env,
+            symbols: SymbolMap::from_program(image),
+        })
+    }
+
+    /// Set the symbols used for resolving addresses.
+    ///
+    /// This can be used to load symbols that are not embedded in the program image
+    pub fn set_symbols(&mut self, symbols: Option<SymbolMap>) {
+        self.symbols = symbols;
+    }
+
+    /// Get the symbols used for resolving addresses, if available
+    pub fn symbols(&self) -> Option<&SymbolMap> {
+        self.symbols.as_ref()
+    }
+
+    /// Disassemble `size` bytes of the main module starting at `main_offset`.
+    ///
+    /// The start is aligned down to the instruction boundary. Error if any part of the
+    /// range is not in the program image
+    pub fn disassemble(&self, main_offset: u32, size: u32) -> Result<Vec<DisasmLine>, Error> {
+        let start = main_offset & !3;
+        let end = main_offset.saturating_add(size);
+        let hooks = GameHooks::replaced_ranges(self.env);
+        let patches = patched_instructions(self.env);
+
+        let mut lines = Vec::with_capacity(((end - start) / 4) as usize);
+        for offset in (start..end).step_by(4) {
+            let Some(original) = self.read_u32(offset) else {
+                return Err(Error::NotInImage(offset));
+            };
+            let patched = patches
+                .iter()
+                .find(|(x, _)| *x == offset)
+                .map(|(_, bits)| *bits);
+            let bits = patched.unwrap_or(original);
+            let text = disarm64::decoder::decode(bits)
+                .map(|x| x.to_string())
+                .unwrap_or_default();
+            let hook = hooks
+                .iter()
+                .find(|(x, size)| offset >= *x && offset - *x < *size)
+                .copied();
+            lines.push(DisasmLine {
+                main_offset: offset,
+                bits,
+                original: patched.map(|_| original),
+                text,
+                branch_target: branch_target(offset, bits),
+                hook,
+            });
+        }
+
+        Ok(lines)
+    }
+
+    /// Format the disassembly as text, one instruction per line, with labels
+    /// at the start of the symbols
+    pub fn format(&self, lines: &[DisasmLine]) -> String {
+        let mut out = String::new();
+        for line in lines {
+            if let Some(symbols) = &self.symbols
+                && let Some((symbol, 0)) = symbols.resolve(line.main_offset)
+            {
+                out.push_str(&format!("\n{}:\n", symbol.name));
+            }
+            let text = if line.text.is_empty() {
+                "<cannot decode>"
+            } else {
+                line.text.as_str()
+            };
+            let mut s = format!(
+                "main+0x{:08x}  {:08x}  {text:<40}",
+                line.main_offset, line.bits
+            );
+            if let Some(target) = line.branch_target {
+                s.push_str(&format!(" ; -> {}", self.format_main_offset(target)));
+            }
+            if let Some(original) = line.original {
+                s.push_str(&format!(" ; patched, was {original:08x}"));
+            }
+            if let Some((hook_start, _)) = line.hook {
+                s.push_str(&format!(
+                    " ; replaced by hook at {}",
+                    self.format_main_offset(hook_start)
+                ));
+            }
+            out.push_str(s.trim_end());
+            out.push('\n');
+        }
+        out
+    }
+
+    fn format_main_offset(&self, main_offset: u32) -> String {
+        match self
+            .symbols
+            .as_ref()
+            .and_then(|x| x.format_main_offset(main_offset))
+        {
+            Some(name) => format!("main+0x{main_offset:08x} <{name}>"),
+            None => format!("main+0x{main_offset:08x}"),
+        }
+    }
+
+    /// Read an instruction from the program image (before patching)
+    fn read_u32(&self, main_offset: u32) -> Option<u32> {
+        let rel = self.env.main_offset() as u64 + main_offset as u64;
+        for module in self.image.modules.iter() {
+            for section in module.sections.iter() {
+                for segment in section.segments.iter() {
+                    let seg_start = segment.rel_start.to_native() as u64;
+                    let data = segment.data.as_slice();
+                    if rel < seg_start || rel + 4 > seg_start + data.len() as u64 {
+                        continue;
+                    }
+                    let i = (rel - seg_start) as usize;
+                    let bytes = data[i..i + 4].try_into().ok()?;
+                    return Some(u32::from_le_bytes(bytes));
+                }
+            }
+        }
+        None
+    }
+}
+
+/// Decode the target of a branch instruction with an immediate offset
+/// (B, BL, B.cond, CBZ, CBNZ, TBZ and TBNZ)
+fn branch_target(main_offset: u32, bits: u32) -> Option<u32> {
+    // sign extend the immediate at bit 5 (or 0 for B/BL) and multiply by 4
+    let imm = |shift: u32, width: u32| {
+        let x = (bits >> shift) & ((1 << width) - 1);
+        (((x << (32 - width)) as i32) >> (32 - width)) * 4
+    };
+    let offset = if bits & 0x7c00_0000 == 0x1400_0000 {
+        // B, BL
+        imm(0, 26)
+    } else if bits & 0xff00_0010 == 0x5400_0000 {
+        // B.cond
+        imm(5, 19)
+    } else if bits & 0x7e00_0000 == 0x3400_0000 {
+        // CBZ, CBNZ
+        imm(5, 19)
+    } else if bits & 0x7e00_0000 == 0x3600_0000 {
+        // TBZ, TBNZ
+        imm(5, 14)
+    } else {
+        return None;
+    };
+    main_offset.checked_add_signed(offset)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_branch_target() {
+        // bl #0x10
+        assert_eq!(branch_target(0x100, 0x94000004), Some(0x110));
+        // b #-4
+        assert_eq!(branch_target(0x100, 0x17ffffff), Some(0xfc));
+        // b.eq #8
+        assert_eq!(branch_target(0x100, 0x54000040), Some(0x108));
+        // cbz x0, #0xc
+        assert_eq!(branch_target(0x100, 0xb4000060), Some(0x10c));
+        // tbz w0, #0, #0x10
+        assert_eq!(branch_target(0x100, 0x36000080), Some(0x110));
+        // nop
+        assert_eq!(branch_target(0x100, 0xd503201f), None);
+        // b #-0x200 from 0x100
+        assert_eq!(branch_target(0x100, 0x17ffff80), None);
+    }
+}
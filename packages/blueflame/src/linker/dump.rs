@@ -0,0 +1,126 @@
+use crate::game::{PauseMenuDataMgr, singleton_instance};
+use crate::linker::Error;
+use crate::memory::{MemObject, Ptr};
+use crate::processor::Process;
+
+/// A raw memory dump of the PauseMenuDataMgr singleton and the heap around it,
+/// as captured from a console
+#[derive(Debug, Clone, Copy)]
+pub struct PmdmDump<'a> {
+    /// The dumped bytes
+    pub bytes: &'a [u8],
+    /// Address of the first byte of the dump on the console
+    pub dump_start: u64,
+    /// Address of the PMDM singleton on the console. The whole
+    /// PMDM must be inside the dump
+    pub pmdm_addr: u64,
+    /// Start of the main module on the console, used to relocate
+    /// vtables and other pointers into the program
+    pub main_start: u64,
+}
+
+/// Load a PMDM memory dump into the process, replacing the state of the PMDM singleton.
+///
+/// The dump is copied into the PMDM singleton and a copy of the dump allocated
+/// on the heap. Pointers are relocated when the addresses in the process are
+/// different from the ones on the console:
+/// - Pointers into the dumped PMDM are relocated to the PMDM singleton in the process
+/// - Pointers into the rest of the dump are relocated into the copy on the heap
+/// - Pointers into the program on the console are relocated into the program
+///
+/// In the PMDM, only the known pointer fields (including vtables) are relocated.
+/// The layout of the rest of the dump is unknown, so every 8-byte aligned value
+/// in it that points into one of the ranges above is treated as a pointer.
+/// Other pointers, such as ones to the console heap outside of the dump,
+/// are kept as-is.
+pub fn load_pmdm_dump(proc: &mut Process, dump: &PmdmDump) -> Result<(), Error> {
+    cu::info!("loading PMDM dump");
+    let pmdm_size = PauseMenuDataMgr::SIZE as u64;
+    let dump_len = dump.bytes.len() as u64;
+    if !dump.dump_start.is_multiple_of(8) {
+        return Err(Error::BadDump(format!(
+            "dump start is not aligned: 0x{:016x}",
+            dump.dump_start
+        )));
+    }
+    let pmdm_off = dump.pmdm_addr.checked_sub(dump.dump_start).filter(|off| {
+        off.checked_add(pmdm_size)
+            .is_some_and(|end| end <= dump_len)
+    });
+    let Some(pmdm_off) = pmdm_off else {
+        return Err(Error::BadDump(format!(
+            "PMDM at 0x{:016x} is not fully inside the dump (0x{:016x}, size 0x{dump_len:x})",
+            dump.pmdm_addr, dump.dump_start
+        )));
+    };
+    if !pmdm_off.is_multiple_of(8) {
+        return Err(Error::BadDump(format!(
+            "PMDM address is not aligned: 0x{:016x}",
+            dump.pmdm_addr
+        )));
+    }
+    let Ok(copy_size) = u32::try_from(dump_len) else {
+        return Err(Error::BadDump(format!("dump is too big: 0x{dump_len:x}")));
+    };
+
+    let pmdm = singleton_instance!(pmdm(proc.memory()))?.to_raw();
+    let memory = proc.memory_mut();
+    let copy_start = memory.alloc(copy_size)?;
+    let main_start = memory.main_start();
+    cu::debug!("PMDM is at 0x{pmdm:016x}, copy of dump is at 0x{copy_start:016x}");
+
+    let relocate = |value: u64| -> Option<u64> {
+        if value == 0 {
+            return None;
+        }
+        if let Some(off) = value.checked_sub(dump.pmdm_addr)
+            && off < pmdm_size
+        {
+            return Some(pmdm + off);
+        }
+        if let Some(off) = value.checked_sub(dump.dump_start)
+            && off < dump_len
+        {
+            return Some(copy_start + off);
+        }
+        if let Some(off) = value.checked_sub(dump.main_start)
+            && let Some(program_value) = main_start.checked_add(off)
+            && memory.is_program_addr(program_value)
+        {
+            return Some(program_value);
+        }
+        None
+    };
+
+    let pmdm_off = pmdm_off as usize;
+    let pmdm_end = pmdm_off + pmdm_size as usize;
+    let mut bytes = dump.bytes.to_vec();
+    let mut relocated = 0;
+    // offsets of the pointer fields in the PMDM, relative to the start of the dump
+    let pmdm_fields = Ptr!(<PauseMenuDataMgr>(pmdm_off as u64)).pointer_fields();
+    // every aligned value in the dump outside of the PMDM
+    let other_values = (0..bytes.len().saturating_sub(7))
+        .step_by(8)
+        .filter(|off| *off + 8 <= pmdm_off || *off >= pmdm_end)
+        .map(|off| off as u64);
+    for off in pmdm_fields.into_iter().chain(other_values) {
+        let chunk = &mut bytes[off as usize..off as usize + 8];
+        let mut value = [0u8; 8];
+        value.copy_from_slice(chunk);
+        let value = u64::from_le_bytes(value);
+        let Some(new_value) = relocate(value) else {
+            continue;
+        };
+        if new_value != value {
+            chunk.copy_from_slice(&new_value.to_le_bytes());
+            relocated += 1;
+        }
+    }
+    cu::debug!("relocated {relocated} pointers in PMDM dump");
+
+    Ptr!(<u8>(copy_start)).store_slice(&bytes, memory)?;
+    Ptr!(<u8>(pmdm)).store_slice(&bytes[pmdm_off..pmdm_end], memory)?;
+
+    cu::info!("PMDM dump loaded");
+    Ok(())
+}
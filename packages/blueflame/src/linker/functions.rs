@@ -392,6 +392,29 @@ pub fn unequip(cpu: &mut Cpu2, item: Ptr![PouchItem]) -> Result<(), processor::E
     save_to_game_data(cpu)
 }
 
+/// Upgrade an armor to `next_name`, like at a Great Fairy
+///
+/// The armor is removed with `uking::ui::PauseMenuDataMgr::sellItem`, then
+/// the next star is added with `uking::ui::PauseMenuDataMgr::itemGet` with the same
+/// value (i.e. the dye color). If the armor was equipped, the new one is equipped
+/// with `uking::ui::PauseMenuDataMgr::autoEquipLastAddedItem`
+pub fn upgrade_armor(
+    cpu: &mut Cpu2,
+    item: Ptr![PouchItem],
+    next_name: &str,
+) -> Result<(), processor::Error> {
+    mem! { (cpu.proc.memory()):
+        let value = *(&item->mValue);
+        let is_equipped = *(&item->mEquipped);
+    }
+    sell_item(cpu, item, 1)?;
+    get_item_with_value(cpu, next_name, value, None)?;
+    if is_equipped {
+        equip_last_added_item(cpu)?;
+    }
+    Ok(())
+}
+
 /// Wrapper that calls `uking::ui::PauseMenuDataMgr::getWeaponsForDpad`
 ///
 /// The returned Vec has at most 20 elements, and is guaranteed to not have nullptrs
@@ -357,15 +357,21 @@ fn set_flag_by_index_impl<
     };
     let value = reader(cpu, proc)?;
 
-    proxy! { let mut params = *this_ptr as trigger_param in proc };
-    let Some(flag) = params.get_mut::<Fd, _>(idx) else {
-        reg! { cpu: x[0] = false, return };
+    let tracing = proc.memory().is_tracing_writes();
+    let change = {
+        proxy! { let mut params = *this_ptr as trigger_param in proc };
+        let Some(flag) = params.get_mut::<Fd, _>(idx) else {
+            reg! { cpu: x[0] = false, return };
+        };
+        if check_perms && !flag.writable() {
+            reg! { cpu: x[0] = false, return };
+        }
+        // we ignore one_trigger and initial value check
+        let old = tracing.then(|| flag.get().clone());
+        flag.set(value);
+        old.map(|old| FlagChange::new(None, flag.hash(), None, &old, flag.get()))
     };
-    if check_perms && !flag.writable() {
-        reg! { cpu: x[0] = false, return };
-    }
-    // we ignore one_trigger and initial value check
-    flag.set(value);
+    FlagChange::record(change, proc);
     reg! { cpu: x[0] = true, return };
 }
 
@@ -398,15 +404,21 @@ fn set_flag_by_name_impl<
         reg! { cpu: x[0] = false, return };
     }
 
-    proxy! { let mut params = *this_ptr as trigger_param in proc };
-    let Some(flag) = params.by_name_mut::<Fd>(name) else {
-        reg! { cpu: x[0] = false, return };
+    let tracing = proc.memory().is_tracing_writes();
+    let change = {
+        proxy! { let mut params = *this_ptr as trigger_param in proc };
+        let Some(flag) = params.by_name_mut::<Fd>(&name) else {
+            reg! { cpu: x[0] = false, return };
+        };
+        if check_perms && !flag.writable() {
+            reg! { cpu: x[0] = false, return };
+        }
+        // we ignore one_trigger and initial value check
+        let old = tracing.then(|| flag.get().clone());
+        flag.set(value);
+        old.map(|old| FlagChange::new(Some(&name), flag.hash(), None, &old, flag.get()))
     };
-    if check_perms && !flag.writable() {
-        reg! { cpu: x[0] = false, return };
-    }
-    // we ignore one_trigger and initial value check
-    flag.set(value);
+    FlagChange::record(change, proc);
     reg! { cpu: x[0] = true, return };
 }
 
@@ -434,15 +446,22 @@ fn set_flag_array_by_index_impl<
     };
     let value = reader(cpu, proc)?;
 
-    proxy! { let mut params = *this_ptr as trigger_param in proc };
-    let Some(flag) = params.get_mut::<Fd, _>(idx) else {
-        reg! { cpu: x[0] = false, return };
-    };
-    if check_perms && !flag.writable() {
-        reg! { cpu: x[0] = false, return };
-    }
-    // we ignore one_trigger and initial value check
-    let result = flag.set_at(array_idx, value);
+    let tracing = proc.memory().is_tracing_writes();
+    let (result, change) = {
+        proxy! { let mut params = *this_ptr as trigger_param in proc };
+        let Some(flag) = params.get_mut::<Fd, _>(idx) else {
+            reg! { cpu: x[0] = false, return };
+        };
+        if check_perms && !flag.writable() {
+            reg! { cpu: x[0] = false, return };
+        }
+        // we ignore one_trigger and initial value check
+        let old = tracing.then(|| flag.get_at(array_idx).cloned()).flatten();
+        let result = flag.set_at(array_idx, value);
+        let change = FlagChange::new_at(None, flag, array_idx, old);
+        (result, change)
+    };
+    FlagChange::record(change, proc);
     reg! { cpu: x[0] = result, return };
 }
 
@@ -476,15 +495,22 @@ fn set_flag_array_by_name_impl<
         reg! { cpu: x[0] = false, return };
     }
 
-    proxy! { let mut params = *this_ptr as trigger_param in proc };
-    let Some(flag) = params.by_name_mut::<Fd>(name) else {
-        reg! { cpu: x[0] = false, return };
-    };
-    if check_perms && !flag.writable() {
-        reg! { cpu: x[0] = false, return };
-    }
-    // we ignore one_trigger and initial value check
-    let result = flag.set_at(array_idx, value);
+    let tracing = proc.memory().is_tracing_writes();
+    let (result, change) = {
+        proxy! { let mut params = *this_ptr as trigger_param in proc };
+        let Some(flag) = params.by_name_mut::<Fd>(&name) else {
+            reg! { cpu: x[0] = false, return };
+        };
+        if check_perms && !flag.writable() {
+            reg! { cpu: x[0] = false, return };
+        }
+        // we ignore one_trigger and initial value check
+        let old = tracing.then(|| flag.get_at(array_idx).cloned()).flatten();
+        let result = flag.set_at(array_idx, value);
+        let change = FlagChange::new_at(Some(&name), flag, array_idx, old);
+        (result, change)
+    };
+    FlagChange::record(change, proc);
     reg! { cpu: x[0] = result, return };
 }
 
@@ -499,14 +525,20 @@ pub fn reset<Fd: gdt::FlagDescriptor>(
         w[2] => let check_perms: bool,
     }
 
-    proxy! { let mut params = *this_ptr as trigger_param in proc };
-    let Some(flag) = params.get_mut::<Fd, _>(idx) else {
-        reg! { cpu: x[0] = false, return };
+    let tracing = proc.memory().is_tracing_writes();
+    let change = {
+        proxy! { let mut params = *this_ptr as trigger_param in proc };
+        let Some(flag) = params.get_mut::<Fd, _>(idx) else {
+            reg! { cpu: x[0] = false, return };
+        };
+        if check_perms && !flag.writable() {
+            reg! { cpu: x[0] = false, return };
+        }
+        let old = tracing.then(|| flag.get().clone());
+        flag.reset();
+        old.map(|old| FlagChange::new(None, flag.hash(), None, &old, flag.get()))
     };
-    if check_perms && !flag.writable() {
-        reg! { cpu: x[0] = false, return };
-    }
-    flag.reset();
+    FlagChange::record(change, proc);
     reg! { cpu: x[0] = true, return }
 }
 
@@ -527,14 +559,20 @@ pub fn reset_by_name<Fd: gdt::FlagDescriptor>(
         reg! { cpu: x[0] = false, return };
     }
 
-    proxy! { let mut params = *this_ptr as trigger_param in proc };
-    let Some(flag) = params.by_name_mut::<Fd>(name) else {
-        reg! { cpu: x[0] = false, return };
+    let tracing = proc.memory().is_tracing_writes();
+    let change = {
+        proxy! { let mut params = *this_ptr as trigger_param in proc };
+        let Some(flag) = params.by_name_mut::<Fd>(&name) else {
+            reg! { cpu: x[0] = false, return };
+        };
+        if check_perms && !flag.writable() {
+            reg! { cpu: x[0] = false, return };
+        }
+        let old = tracing.then(|| flag.get().clone());
+        flag.reset();
+        old.map(|old| FlagChange::new(Some(&name), flag.hash(), None, &old, flag.get()))
     };
-    if check_perms && !flag.writable() {
-        reg! { cpu: x[0] = false, return };
-    }
-    flag.reset();
+    FlagChange::record(change, proc);
     reg! { cpu: x[0] = true, return }
 }
 
@@ -550,14 +588,21 @@ pub fn reset_array<Fd: gdt::ArrayFlagDescriptor + 'static>(
         w[3] => let check_perms: bool,
     }
 
-    proxy! { let mut params = *this_ptr as trigger_param in proc };
-    let Some(flag) = params.get_mut::<Fd, _>(idx) else {
-        reg! { cpu: x[0] = false, return };
+    let tracing = proc.memory().is_tracing_writes();
+    let (result, change) = {
+        proxy! { let mut params = *this_ptr as trigger_param in proc };
+        let Some(flag) = params.get_mut::<Fd, _>(idx) else {
+            reg! { cpu: x[0] = false, return };
+        };
+        if check_perms && !flag.writable() {
+            reg! { cpu: x[0] = false, return };
+        }
+        let old = tracing.then(|| flag.get_at(array_idx).cloned()).flatten();
+        let result = flag.reset_at(array_idx);
+        let change = FlagChange::new_at(None, flag, array_idx, old);
+        (result, change)
     };
-    if check_perms && !flag.writable() {
-        reg! { cpu: x[0] = false, return };
-    }
-    let result = flag.reset_at(array_idx);
+    FlagChange::record(change, proc);
     reg! { cpu: x[0] = result, return }
 }
 
@@ -570,6 +615,58 @@ pub fn reset_all(cpu: &mut Cpu0, proc: &mut Process) -> Result<(), processor::Er
     Ok(())
 }
 
+/// Change to a flag made by a hook, to be recorded with the write tracer
+/// after the TriggerParam proxy is released
+struct FlagChange {
+    flag: String,
+    array_idx: Option<i32>,
+    old: String,
+    new: String,
+}
+
+impl FlagChange {
+    fn new<T: std::fmt::Debug>(
+        name: Option<&str>,
+        hash: i32,
+        array_idx: Option<i32>,
+        old: &T,
+        new: &T,
+    ) -> Self {
+        let flag = match name {
+            Some(name) => name.to_string(),
+            None => format!("0x{hash:08x}"),
+        };
+        Self {
+            flag,
+            array_idx,
+            old: format!("{old:?}"),
+            new: format!("{new:?}"),
+        }
+    }
+
+    /// Create the change for an array element, `old` is `None` if
+    /// the writes are not traced or the index is out of bounds
+    fn new_at<T: gdt::FlagType + 'static>(
+        name: Option<&str>,
+        flag: &gdt::Flag<gdt::ArrayFlagType<T>>,
+        array_idx: i32,
+        old: Option<T>,
+    ) -> Option<Self> {
+        let old = old?;
+        let new = flag.get_at(array_idx)?;
+        Some(Self::new(name, flag.hash(), Some(array_idx), &old, new))
+    }
+
+    fn record(change: Option<Self>, proc: &mut Process) {
+        let Some(change) = change else {
+            return;
+        };
+        if let Some(tracer) = proc.memory_mut().write_tracer_mut() {
+            tracer.record_gdt(change.flag, change.array_idx, change.old, change.new);
+        }
+    }
+}
+
 /// ksys::gdt::TriggerParam::getXXXIdx(this, i32 hash) -> int
 pub fn idx_from_hash<Fd: gdt::FlagDescriptor>(
     cpu: &mut Cpu0,
@@ -1,12 +1,13 @@
 use super::gdt_hooks;
 use crate::env::Environment;
 use crate::game::gdt;
-use crate::memory::{self, Memory, access, proxy};
+use crate::memory::{self, Memory, access, proxy, region};
 use crate::processor::insn::paste_insn;
 use crate::processor::{self, Cpu0, Hook, HookProvider, Process, reg};
+use crate::program::SymbolMap;
 
 macro_rules! replace_hooks {
-    ($main_offset:ident size fn $( $offset:literal $size:literal $function:expr ),* $(,)?) => {
+    ($main_offset:ident; $( $offset:literal $size:literal $function:expr ),* $(,)?) => {
         match $main_offset {
         $(
             #[allow(clippy::zero_prefixed_literal)]
@@ -19,40 +20,49 @@ macro_rules! replace_hooks {
     };
 }
 
-/// Patch the (instruction) memory before running
-pub fn patch_memory(memory: &mut Memory, env: Environment) -> Result<(), memory::Error> {
-    let main_start = memory.program_start() + env.main_offset() as u64;
+macro_rules! replaced_ranges {
+    ($( $offset:literal $size:literal $function:expr ),* $(,)?) => {
+        &[ $( ($offset, $size) ),* ]
+    };
+}
 
+/// Instructions patched by [`patch_memory`], as `(main_offset, instruction)`
+const PATCHES: &[(u32, u32)] = &[
+    // in uking::ui::PauseMenuDataMgr::createPlayerEquipment and doCreateEquipmentFromItem
+    // skip the check for if CreatePlayerEquipActorMgr is null
+    (0x971540, paste_insn!(1F 20 03 D5)), // nop
+    (0xaa81ec, paste_insn!(1F 20 03 D5)), // nop
+];
+
+/// Get the instructions patched by [`patch_memory`] for the environment,
+/// as `(main_offset, instruction)`
+pub fn patched_instructions(env: Environment) -> &'static [(u32, u32)] {
     if env.is160() {
         // TODO --160: patch for 160
-        return Ok(());
+        return &[];
     }
+    PATCHES
+}
 
+/// Patch the (instruction) memory before running
+pub fn patch_memory(memory: &mut Memory, env: Environment) -> Result<(), memory::Error> {
+    let main_start = memory.program_start() + env.main_offset() as u64;
     let f = access!(force);
 
-    // in uking::ui::PauseMenuDataMgr::createPlayerEquipment and doCreateEquipmentFromItem
-    // skip the check for if CreatePlayerEquipActorMgr is null
-    memory
-        .write(main_start + 0x971540, f)?
-        .write_u32(paste_insn!(1F 20 03 D5))?; // nop
-    memory
-        .write(main_start + 0xaa81ec, f)?
-        .write_u32(paste_insn!(1F 20 03 D5))?; // nop
+    for (main_offset, insn) in patched_instructions(env) {
+        memory
+            .write(main_start + *main_offset as u64, f)?
+            .write_u32(*insn)?;
+    }
 
     Ok(())
 }
 
-pub struct GameHooks;
-impl HookProvider for GameHooks {
-    fn fetch(&self, main_offset: u32, env: Environment) -> Result<Option<Hook>, processor::Error> {
-        if env.is160() {
-            // TODO --160: hooks for 160
-            return Ok(None);
-        }
-
-        replace_hooks! {
-
-        main_offset size   fn
+/// Table of the functions replaced by [`GameHooks`], passed to the macro `$callback`
+macro_rules! game_hooks_table {
+    ($callback:ident $(, $arg:ident)?) => {
+        $callback! { $($arg;)?
+        // main_offset size   fn
         0x006669f8  000408 return_void, // uking::act::CreatePlayerEquipActorMgr::doRequestCreateWeapon
         0x00666cf8  000688 return_void, // uking::act::CreatePlayerEquipActorMgr::doRequestCreateArmor
         0x00674830  000612 is_true_form_master_sword, // uking::dmg::DamageInfoMgr::isTrueFormMasterSword
@@ -192,7 +202,72 @@ impl HookProvider for GameHooks {
         0x01800a20  000016 return_void, // nn::os::UnlockMutex
         0x01800bf0  000016 vsnprintf,   // nn::util::VSNPrintf
 
-        };
+        }
+    };
+}
+
+/// Mangled names of `operator delete` and `operator delete[]`
+const DELETE_SYMBOLS: &[&str] = &["_ZdlPv", "_ZdaPv"];
+
+/// Hooks for running the game code in the simulator
+///
+/// Most of the hooks are at fixed offsets (see `game_hooks_table`). `operator delete`
+/// is found in the symbol map, and frees the memory on the simulated heap,
+/// so use-after-free can be detected with the `mem-track-free` feature.
+/// Without the symbol map, memory is never freed
+#[derive(Debug, Default)]
+pub struct GameHooks {
+    /// `(main_offset, byte_size)` of `operator delete` functions
+    delete_functions: Vec<(u32, u32)>,
+}
+
+impl GameHooks {
+    /// Create the hooks, finding the functions to hook by name in the symbol map
+    pub fn new(symbols: Option<&SymbolMap>) -> Self {
+        let delete_functions = symbols
+            .map(|symbols| {
+                DELETE_SYMBOLS
+                    .iter()
+                    .filter_map(|name| symbols.find_by_name(name))
+                    .map(|x| (x.main_offset, x.size.max(4)))
+                    .collect()
+            })
+            .unwrap_or_default();
+        Self { delete_functions }
+    }
+
+    /// Get the ranges of code replaced by the hooks for the environment,
+    /// as `(main_offset, byte_size)`
+    pub fn replaced_ranges(env: Environment) -> &'static [(u32, u32)] {
+        if env.is160() {
+            // TODO --160: hooks for 160
+            return &[];
+        }
+        #[allow(clippy::zero_prefixed_literal)]
+        const RANGES: &[(u32, u32)] = game_hooks_table!(replaced_ranges);
+        RANGES
+    }
+}
+
+impl HookProvider for GameHooks {
+    fn fetch(&self, main_offset: u32, env: Environment) -> Result<Option<Hook>, processor::Error> {
+        if env.is160() {
+            // TODO --160: hooks for 160
+            return Ok(None);
+        }
+
+        game_hooks_table!(replace_hooks, main_offset);
+
+        if let Some((_, size)) = self
+            .delete_functions
+            .iter()
+            .find(|(offset, _)| *offset == main_offset)
+        {
+            return Ok(Some(Hook::Replace(
+                processor::box_execute(operator_delete),
+                *size,
+            )));
+        }
 
         Ok(None)
     }
@@ -203,6 +278,24 @@ fn get_player(cpu: &mut Cpu0, _: &mut Process) -> Result<(), processor::Error> {
     reg! { cpu: x[0] = player_ptr, return };
 }
 
+/// operator delete(ptr)
+///
+/// Only memory on the simulated heap is freed. Objects elsewhere (for example,
+/// in the program's static memory), and heap allocations made before
+/// `mem-track-free` was enabled, are ignored
+fn operator_delete(cpu: &mut Cpu0, proc: &mut Process) -> Result<(), processor::Error> {
+    reg! { cpu: x[0] => let ptr: u64 };
+    let memory = proc.memory();
+    if ptr != 0
+        && memory
+            .section_flags(ptr)
+            .is_some_and(|flags| flags.any(region!(heap)))
+    {
+        proc.memory_mut().free(ptr)?;
+    }
+    reg! { cpu: return };
+}
+
 /// memcpy(dest, src, size)
 fn memcpy(cpu: &mut Cpu0, proc: &mut Process) -> Result<(), processor::Error> {
     reg! { cpu:
@@ -297,3 +390,90 @@ fn is_true_form_master_sword(cpu: &mut Cpu0, proc: &mut Process) -> Result<(), p
     let is_true_form = *param.get();
     reg! { cpu: x[0] = is_true_form, return }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::env::Feature;
+    use crate::game::Proxies;
+    use crate::memory::Ptr;
+    use crate::processor::{Cpu1, Cpu2};
+    use crate::program::Symbol;
+
+    const DELETE_OFFSET: u32 = 0x100;
+
+    fn new_process() -> Process {
+        let symbols = SymbolMap::new(vec![Symbol {
+            main_offset: DELETE_OFFSET,
+            size: 4,
+            name: "_ZdlPv".to_string(),
+        }]);
+        let mut memory = Memory::new_for_test();
+        memory.set_features(memory.features() | Feature::mem_track_free);
+        Process::new(
+            Arc::new(memory),
+            Arc::new(Proxies::default()),
+            Arc::new(GameHooks::new(Some(&symbols))),
+            None,
+        )
+    }
+
+    #[test]
+    fn test_operator_delete_use_after_free() -> cu::Result<()> {
+        let mut proc = new_process();
+        let ptr = proc.memory_mut().alloc(0x10)?;
+        // allocate after it so the freed memory is not reclaimed
+        proc.memory_mut().alloc(0x10)?;
+        Ptr!(<u32>(ptr)).store(&1, proc.memory_mut())?;
+
+        let mut cpu1 = Cpu1::default();
+        let mut cpu = Cpu2::new(&mut cpu1, &mut proc);
+        reg! { cpu: x[0] = ptr };
+        cpu.native_jump_to_main_offset(DELETE_OFFSET)?;
+
+        let result = Ptr!(<u32>(ptr)).load(proc.memory());
+        assert!(
+            matches!(result, Err(memory::Error::UseAfterFree(addr, start, _)) if addr == ptr && start == ptr)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_operator_delete_nullptr() -> cu::Result<()> {
+        let mut proc = new_process();
+        let mut cpu1 = Cpu1::default();
+        let mut cpu = Cpu2::new(&mut cpu1, &mut proc);
+        reg! { cpu: x[0] = 0u64 };
+        cpu.native_jump_to_main_offset(DELETE_OFFSET)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_operator_delete_before_tracking() -> cu::Result<()> {
+        let mut proc = new_process();
+        let features = proc.memory().features();
+        proc.memory_mut()
+            .set_features(features - Feature::mem_track_free);
+        let ptr = proc.memory_mut().alloc(0x10)?;
+        proc.memory_mut().set_features(features);
+        Ptr!(<u32>(ptr)).store(&1, proc.memory_mut())?;
+
+        // allocated before tracking was enabled, so deleting it is ignored
+        let mut cpu1 = Cpu1::default();
+        let mut cpu = Cpu2::new(&mut cpu1, &mut proc);
+        reg! { cpu: x[0] = ptr };
+        cpu.native_jump_to_main_offset(DELETE_OFFSET)?;
+
+        assert_eq!(Ptr!(<u32>(ptr)).load(proc.memory())?, 1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_no_delete_hook_without_symbols() {
+        let hooks = GameHooks::new(None);
+        let env = crate::env::Environment::new_for_test();
+        assert!(matches!(hooks.fetch(DELETE_OFFSET, env), Ok(None)));
+    }
+}
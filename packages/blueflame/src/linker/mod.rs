@@ -1,6 +1,12 @@
 mod boot;
 pub use boot::*;
 
+mod dump;
+pub use dump::*;
+
+mod disasm;
+pub use disasm::*;
+
 mod hooks;
 pub use hooks::*;
 
@@ -33,6 +33,14 @@ pub enum Error {
 
     #[error("heap out of memory")]
     HeapOutOfMemory,
+    #[error(
+        "[mem-track-free] attempt to access freed heap memory: 0x{0:016x} (allocation at 0x{1:016x}), flags: {2}"
+    )]
+    UseAfterFree(u64, u64, AccessFlags),
+    #[error("[mem-track-free] double free: 0x{0:016x}")]
+    DoubleFree(u64),
+    #[error("[mem-track-free] attempt to free a pointer that is not allocated: 0x{0:016x}")]
+    InvalidFree(u64),
 
     #[error("proxy object is too small: {0} bytes, need at least 4 bytes")]
     InvalidProxyObjectSize(u32),
@@ -43,3 +51,18 @@ pub enum Error {
     #[error("too many proxy objects")]
     ProxyOutOfMemory,
 }
+
+impl Error {
+    /// Get the address and flags of the memory access that caused the error,
+    /// if the error is from an access
+    pub fn access(&self) -> Option<(u64, AccessFlags)> {
+        match self {
+            Self::HeapUnallocated(addr, flags)
+            | Self::InvalidSection(addr, flags)
+            | Self::PermissionDenied(addr, flags)
+            | Self::Boundary(addr, flags)
+            | Self::UseAfterFree(addr, _, flags) => Some((*addr, *flags)),
+            _ => None,
+        }
+    }
+}
@@ -1,5 +1,10 @@
+use std::collections::BTreeMap;
+
 use crate::memory::{Error, PAGE_SIZE, REGION_ALIGN, Section, align_down, align_up, perm, region};
 
+/// Value written to every byte of freed heap memory
+pub const HEAP_FREE_POISON: u8 = 0xdd;
+
 #[cfg(feature = "trace-memory")]
 static MAX_ALLOC_SIZE: std::sync::LazyLock<std::sync::Arc<std::sync::atomic::AtomicU64>> =
     std::sync::LazyLock::new(|| std::sync::Arc::new(std::sync::atomic::AtomicU64::new(0)));
@@ -24,12 +29,15 @@ pub fn get_max_heap_alloc() -> u64 {
 /// A simple heap region implementation
 ///
 /// Since the simulator doesn't make much heap allocation (usually),
-/// freed memory are never reclaimed. This is fine, because
+/// freed memory are never reclaimed by default. This is fine, because
 /// each re-run of the simulation will have a fresh heap.
 ///
-/// It doesn't track which regions are freed, so UAF won't be detected
-/// and is completely safe in the simulation since there is essentially
-/// no free.
+/// With the `mem-track-free` feature, the heap tracks live allocations,
+/// so they can be freed. Freeing works like a stack: if the freed allocation
+/// is the last one, the space is reclaimed (along with freed allocations right below it).
+/// Otherwise, the region is remembered as freed, and accessing it is reported
+/// as use-after-free. Note that after the space is reclaimed and allocated again,
+/// dangling pointers to it can no longer be detected.
 ///
 /// The heap structure only tracks the pointers. The actual
 /// data is still stored in the memory object
@@ -41,6 +49,14 @@ pub struct SimpleHeap {
     size: u32,
     /// Address of the next allocation
     next_alloc: u64,
+    /// If live allocations are tracked so they can be freed
+    track_free: bool,
+    /// Allocations below this address were made before tracking was enabled
+    track_start: u64,
+    /// Start address -> size of live allocations, if tracking is enabled
+    allocations: BTreeMap<u64, u32>,
+    /// Start address -> size of freed allocations that are not reclaimed
+    freed: BTreeMap<u64, u32>,
 }
 
 impl SimpleHeap {
@@ -53,7 +69,40 @@ impl SimpleHeap {
             start,
             size,
             next_alloc: start_alloc,
+            track_free: false,
+            track_start: start_alloc,
+            allocations: BTreeMap::new(),
+            freed: BTreeMap::new(),
+        }
+    }
+
+    /// Set if live allocations are tracked so they can be freed.
+    ///
+    /// This is set by the memory according to the `mem-track-free` feature.
+    /// Allocations made before tracking is enabled cannot be freed,
+    /// and freeing them is ignored. Turning tracking off forgets
+    /// all tracked and freed allocations
+    pub fn set_track_free(&mut self, track_free: bool) {
+        if self.track_free == track_free {
+            return;
         }
+        self.track_free = track_free;
+        self.track_start = self.next_alloc;
+        self.allocations.clear();
+        self.freed.clear();
+    }
+
+    /// Check if live allocations are tracked so they can be freed
+    #[inline]
+    pub fn is_tracking_free(&self) -> bool {
+        self.track_free
+    }
+
+    /// Check if freeing the address should be ignored, because tracking is off
+    /// or the address was allocated before tracking was enabled
+    #[inline]
+    pub fn is_untracked(&self, addr: u64) -> bool {
+        !self.track_free || addr < self.track_start
     }
 
     /// Create a section of memory that corresponds to this heap region
@@ -95,6 +144,9 @@ impl SimpleHeap {
             return Err(Error::HeapOutOfMemory);
         }
         self.next_alloc = end;
+        if self.track_free {
+            self.allocations.insert(start, size);
+        }
         #[cfg(feature = "trace-memory")]
         {
             record_max_alloc(self.next_alloc - self.start)
@@ -102,6 +154,55 @@ impl SimpleHeap {
         Ok(start)
     }
 
+    /// Get the size of the live allocation starting at `addr`
+    ///
+    /// Returns an error if the address is not the start of a live allocation
+    pub fn allocation_size(&self, addr: u64) -> Result<u32, Error> {
+        if let Some(size) = self.allocations.get(&addr) {
+            return Ok(*size);
+        }
+        if self.freed.contains_key(&addr) {
+            return Err(Error::DoubleFree(addr));
+        }
+        Err(Error::InvalidFree(addr))
+    }
+
+    /// Free the live allocation starting at `addr`, and return the size of it.
+    ///
+    /// If the allocation is the last one on the heap, the space is reclaimed.
+    /// Otherwise, it's marked as freed. Does nothing if the address is not tracked
+    /// (see [`is_untracked`](Self::is_untracked)).
+    pub fn free(&mut self, addr: u64) -> Result<u32, Error> {
+        if self.is_untracked(addr) {
+            return Ok(0);
+        }
+        let size = self.allocation_size(addr)?;
+        self.allocations.remove(&addr);
+        self.freed.insert(addr, size);
+        // reclaim freed allocations at the top of the heap.
+        // the next allocation after a freed one starts at its aligned end
+        while let Some((start, size)) = self.freed.last_key_value() {
+            let end = start + *size as u64;
+            if end != self.next_alloc && align_up!(end, 8) != self.next_alloc {
+                break;
+            }
+            self.next_alloc = *start;
+            self.freed.pop_last();
+        }
+        Ok(size)
+    }
+
+    /// If the address is in a freed (but not reclaimed) allocation,
+    /// return the start of the allocation
+    pub fn check_freed(&self, addr: u64) -> Option<u64> {
+        let (start, size) = self.freed.range(..=addr).next_back()?;
+        if addr - start < *size as u64 {
+            Some(*start)
+        } else {
+            None
+        }
+    }
+
     /// Return false if:
     /// - The address is in the heap region
     /// - The address is in the unallocated part of the heap
@@ -112,3 +213,77 @@ impl SimpleHeap {
         addr < self.next_alloc
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn test_free_reclaims_top() -> Result<(), Error> {
+        let mut heap = SimpleHeap::new(0x10000, 0x10000, 0x10000);
+        heap.set_track_free(true);
+        let a = heap.alloc(0x10)?;
+        let b = heap.alloc(0x20)?;
+        let c = heap.alloc(0x8)?;
+
+        // freeing in the middle does not reclaim
+        assert_eq!(heap.free(b)?, 0x20);
+        assert_eq!(heap.check_freed(b + 0x1f), Some(b));
+        assert_eq!(heap.check_freed(c), None);
+        assert!(heap.check_allocated(c));
+        assert_eq!(heap.free(b), Err(Error::DoubleFree(b)));
+
+        // freeing the top reclaims the freed regions below it as well
+        heap.free(c)?;
+        assert_eq!(heap.check_freed(b), None);
+        assert!(!heap.check_allocated(b));
+        assert!(heap.check_allocated(a));
+        assert_eq!(heap.alloc(0x8)?, b);
+
+        assert_eq!(heap.free(a + 8), Err(Error::InvalidFree(a + 8)));
+        Ok(())
+    }
+
+    #[test]
+    pub fn test_free_reclaims_odd_size() -> Result<(), Error> {
+        let mut heap = SimpleHeap::new(0x10000, 0x10000, 0x10000);
+        heap.set_track_free(true);
+        let a = heap.alloc(0x10)?;
+        let b = heap.alloc(0x13)?;
+        let c = heap.alloc(0x5)?;
+        assert_eq!(c, b + 0x18);
+
+        heap.free(b)?;
+        heap.free(c)?;
+        // b is reclaimed even though it's padded before c
+        assert_eq!(heap.check_freed(b), None);
+        assert!(!heap.check_allocated(b));
+        assert_eq!(heap.alloc(0x8)?, b);
+        assert!(heap.check_allocated(a));
+        Ok(())
+    }
+
+    #[test]
+    pub fn test_free_untracked() -> Result<(), Error> {
+        let mut heap = SimpleHeap::new(0x10000, 0x10000, 0x10000);
+        let a = heap.alloc(0x10)?;
+        heap.set_track_free(true);
+        let b = heap.alloc(0x10)?;
+
+        // allocated before tracking, ignored
+        assert_eq!(heap.free(a)?, 0);
+        assert!(heap.is_untracked(a));
+        assert!(!heap.is_untracked(b));
+        heap.free(b)?;
+        assert_eq!(heap.alloc(0x10)?, b);
+
+        // turning tracking off forgets freed allocations
+        let c = heap.alloc(0x10)?;
+        heap.alloc(0x10)?;
+        heap.free(c)?;
+        assert_eq!(heap.check_freed(c), Some(c));
+        heap.set_track_free(false);
+        assert_eq!(heap.check_freed(c), None);
+        Ok(())
+    }
+}
@@ -0,0 +1,310 @@
+use std::collections::HashMap;
+
+use crate::memory::{Error, MemObject, Memory, Ptr, PtrToArray, PtrToSized};
+
+/// A memory object that can be inspected as a tree of fields.
+///
+/// Automatically derived on a struct with the MemInspect macro, using the same
+/// `#[offset]` and `#[size]` attributes as the MemObject derive. Each field
+/// is read from memory separately, so large objects don't need to be loaded at once
+pub trait MemInspect: MemObject {
+    /// Name of the type displayed in the tree, in C-like syntax
+    fn type_name() -> String;
+
+    /// Read the object at the address of the node and fill in the value and children
+    /// of the node. The node is created by the parent with the name, type, address and size
+    fn inspect_into(node: &mut InspectNode, memory: &Memory) -> Result<(), Error>;
+
+    /// Inspect the object located at `addr` as the root of a tree
+    fn inspect(name: &str, addr: u64, memory: &Memory) -> Result<InspectNode, Error> {
+        let mut node = InspectNode::new(name, Self::type_name(), addr, Self::SIZE);
+        Self::inspect_into(&mut node, memory)?;
+        Ok(node)
+    }
+}
+
+/// One field in an inspected memory object
+#[derive(Debug, Clone, PartialEq)]
+pub struct InspectNode {
+    /// Name of the field, or `[i]` for array elements
+    pub name: String,
+    /// Name of the type of the field
+    pub type_name: String,
+    /// Physical address of the field
+    pub addr: u64,
+    /// Size of the field in memory
+    pub size: u32,
+    /// Value of the field, `None` for structs and arrays
+    pub value: InspectValue,
+    /// Extra information about the value, for example
+    /// what a pointer points to
+    pub annotation: String,
+    /// Fields of a struct or elements of an array
+    pub children: Vec<InspectNode>,
+}
+
+/// Value of a leaf node in the inspected tree
+#[derive(Debug, Clone, PartialEq)]
+pub enum InspectValue {
+    None,
+    Bool(bool),
+    Int(i64),
+    UInt(u64),
+    F32(f32),
+    F64(f64),
+    String(String),
+    /// A pointer, with the type name of the pointee
+    Pointer(u64, String),
+}
+
+impl std::fmt::Display for InspectValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::None => Ok(()),
+            Self::Bool(x) => write!(f, "{x}"),
+            Self::Int(x) => write!(f, "{x}"),
+            Self::UInt(x) => write!(f, "0x{x:x}"),
+            Self::F32(x) => write!(f, "{x}"),
+            Self::F64(x) => write!(f, "{x}"),
+            Self::String(x) => write!(f, "{x:?}"),
+            Self::Pointer(x, _) => write!(f, "0x{x:016x}"),
+        }
+    }
+}
+
+impl InspectNode {
+    pub fn new(name: impl Into<String>, type_name: String, addr: u64, size: u32) -> Self {
+        Self {
+            name: name.into(),
+            type_name,
+            addr,
+            size,
+            value: InspectValue::None,
+            annotation: String::new(),
+            children: Vec::new(),
+        }
+    }
+
+    /// Find the path of the field that contains `addr`, relative to this node.
+    ///
+    /// The search stops at the first field that starts at `addr` and has the type
+    /// `pointee`, otherwise the deepest field containing the address is used, with the
+    /// offset into the field appended. Returns `None` if the address is outside this node,
+    /// and an empty string if it is this node itself
+    pub fn find_path(&self, addr: u64, pointee: &str) -> Option<String> {
+        if addr < self.addr || addr - self.addr >= self.size as u64 {
+            return None;
+        }
+        let mut path = String::new();
+        let mut node = self;
+        loop {
+            if node.addr == addr && node.type_name == pointee {
+                return Some(path);
+            }
+            let Some(child) = node
+                .children
+                .iter()
+                .find(|x| addr >= x.addr && addr - x.addr < x.size as u64)
+            else {
+                if node.addr != addr {
+                    path.push_str(&format!("+0x{:x}", addr - node.addr));
+                }
+                return Some(path);
+            };
+            if !path.is_empty() && !child.name.starts_with('[') {
+                path.push('.');
+            }
+            path.push_str(&child.name);
+            node = child;
+        }
+    }
+
+    /// Visit this node and all its descendants (pre-order) with the path of the node
+    pub fn visit_mut(&mut self, f: &mut impl FnMut(&str, &mut InspectNode)) {
+        self.visit_mut_internal(&mut String::new(), f)
+    }
+
+    fn visit_mut_internal(
+        &mut self,
+        path: &mut String,
+        f: &mut impl FnMut(&str, &mut InspectNode),
+    ) {
+        f(path, self);
+        for child in &mut self.children {
+            let len = path.len();
+            if len != 0 && !child.name.starts_with('[') {
+                path.push('.');
+            }
+            path.push_str(&child.name);
+            child.visit_mut_internal(path, f);
+            path.truncate(len);
+        }
+    }
+
+    /// Annotate all pointers in the tree that point inside this node
+    /// with the path of the field they point to. Pointers that already
+    /// have an annotation are not changed
+    pub fn annotate_internal_pointers(&mut self) {
+        let mut annotations = HashMap::new();
+        let root: &InspectNode = self;
+        let mut stack = vec![root];
+        while let Some(node) = stack.pop() {
+            if let InspectValue::Pointer(ptr, pointee) = &node.value
+                && node.annotation.is_empty()
+                && let Some(path) = root.find_path(*ptr, pointee)
+            {
+                annotations.insert(node.addr, path);
+            }
+            stack.extend(node.children.iter());
+        }
+        if annotations.is_empty() {
+            return;
+        }
+        self.visit_mut(&mut |_, node| {
+            if !matches!(node.value, InspectValue::Pointer(..)) {
+                return;
+            }
+            if let Some(path) = annotations.remove(&node.addr) {
+                node.annotation = path;
+            }
+        });
+    }
+}
+
+macro_rules! primitive_type_mem_inspect_impl {
+    ($type:ty, $variant:ident, $value_type:ty) => {
+        impl MemInspect for $type {
+            fn type_name() -> String {
+                stringify!($type).to_string()
+            }
+            #[allow(clippy::unnecessary_cast)]
+            fn inspect_into(node: &mut InspectNode, memory: &Memory) -> Result<(), Error> {
+                let value = Ptr!(<$type>(node.addr)).load(memory)?;
+                node.value = InspectValue::$variant(value as $value_type);
+                Ok(())
+            }
+        }
+    };
+}
+primitive_type_mem_inspect_impl!(u8, UInt, u64);
+primitive_type_mem_inspect_impl!(u16, UInt, u64);
+primitive_type_mem_inspect_impl!(u32, UInt, u64);
+primitive_type_mem_inspect_impl!(u64, UInt, u64);
+primitive_type_mem_inspect_impl!(i8, Int, i64);
+primitive_type_mem_inspect_impl!(i16, Int, i64);
+primitive_type_mem_inspect_impl!(i32, Int, i64);
+primitive_type_mem_inspect_impl!(i64, Int, i64);
+primitive_type_mem_inspect_impl!(bool, Bool, bool);
+primitive_type_mem_inspect_impl!(f32, F32, f32);
+primitive_type_mem_inspect_impl!(f64, F64, f64);
+
+impl<T: MemInspect + Default, const N: usize> MemInspect for [T; N] {
+    fn type_name() -> String {
+        format!("{}[{N}]", T::type_name())
+    }
+    fn inspect_into(node: &mut InspectNode, memory: &Memory) -> Result<(), Error> {
+        let type_name = T::type_name();
+        node.children.reserve(N);
+        for i in 0..N {
+            let addr = node.addr + (i as u64) * (T::SIZE as u64);
+            let mut child = InspectNode::new(format!("[{i}]"), type_name.clone(), addr, T::SIZE);
+            T::inspect_into(&mut child, memory)?;
+            node.children.push(child);
+        }
+        Ok(())
+    }
+}
+
+impl<T: MemInspect, const S: u32> MemInspect for PtrToSized<T, S> {
+    fn type_name() -> String {
+        format!("{}*", T::type_name())
+    }
+    fn inspect_into(node: &mut InspectNode, memory: &Memory) -> Result<(), Error> {
+        let ptr = Ptr!(<Self>(node.addr)).load(memory)?;
+        node.value = InspectValue::Pointer(ptr.to_raw(), T::type_name());
+        if ptr.is_nullptr() {
+            node.annotation = "nullptr".to_string();
+        }
+        Ok(())
+    }
+}
+
+impl<T: MemInspect + Default, const S: u32, const L: usize> MemInspect for PtrToArray<T, S, L> {
+    fn type_name() -> String {
+        format!("{}(*)[{L}]", T::type_name())
+    }
+    fn inspect_into(node: &mut InspectNode, memory: &Memory) -> Result<(), Error> {
+        let ptr = Ptr!(<Self>(node.addr)).load(memory)?;
+        node.value = InspectValue::Pointer(ptr.to_raw(), <[T; L]>::type_name());
+        if ptr.is_nullptr() {
+            node.annotation = "nullptr".to_string();
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::MemInspect;
+
+    #[derive(MemObject, MemInspect, Default)]
+    #[size(0x20)]
+    struct TestNode {
+        #[offset(0x0)]
+        next: Ptr![TestNode],
+        #[offset(0x8)]
+        value: i32,
+        #[offset(0x10)]
+        flags: [u8; 2],
+    }
+
+    #[derive(MemObject, MemInspect)]
+    #[size(0x48)]
+    struct Test {
+        #[offset(0x0)]
+        nodes: [TestNode; 2],
+        #[offset(0x40)]
+        head: Ptr![TestNode],
+    }
+
+    #[test]
+    pub fn test_inspect_tree() -> cu::Result<()> {
+        let mut mem = Memory::new_for_test();
+        let base = 0x500;
+        let test = Test {
+            nodes: [
+                TestNode {
+                    next: Ptr!(<TestNode>(base + 0x20)),
+                    value: -3,
+                    flags: [1, 2],
+                },
+                TestNode {
+                    next: Ptr!(<TestNode>(base + 0x28)),
+                    value: 4,
+                    flags: [0, 0],
+                },
+            ],
+            head: Ptr!(<TestNode>(0)),
+        };
+        Ptr!(<Test>(base)).store(&test, &mut mem)?;
+        let mut tree = Test::inspect("test", base, &mem)?;
+        tree.annotate_internal_pointers();
+
+        assert_eq!(tree.type_name, "Test");
+        assert_eq!(tree.children.len(), 2);
+        let nodes = &tree.children[0];
+        assert_eq!(nodes.type_name, "TestNode[2]");
+        let node0 = &nodes.children[0];
+        assert_eq!(node0.name, "[0]");
+        assert_eq!(node0.children[0].type_name, "TestNode*");
+        assert_eq!(node0.children[0].annotation, "nodes[1]");
+        assert_eq!(node0.children[1].value, InspectValue::Int(-3));
+        assert_eq!(node0.children[2].children[1].addr, base + 0x11);
+        assert_eq!(node0.children[2].children[1].value, InspectValue::UInt(2));
+        let node1 = &nodes.children[1];
+        assert_eq!(node1.children[0].annotation, "nodes[1].value");
+        assert_eq!(tree.children[1].annotation, "nullptr");
+        Ok(())
+    }
+}
@@ -1,9 +1,9 @@
 use std::sync::Arc;
 
-use crate::env::{Environment, enabled};
+use crate::env::{Environment, Feature, FeatureSet, enabled};
 use crate::memory::{
-    AccessFlag, AccessFlags, Error, PAGE_SIZE, Page, Ptr, REGION_ALIGN, Reader, Section,
-    SimpleHeap, Writer, align_up, perm, region,
+    AccessFlag, AccessFlags, Error, HEAP_FREE_POISON, PAGE_SIZE, Page, Ptr, REGION_ALIGN, Reader,
+    Section, SimpleHeap, WriteTrace, WriteTracer, Writer, align_up, perm, region,
 };
 use crate::program::ArchivedModule;
 
@@ -15,6 +15,10 @@ pub struct Memory {
     heap: SimpleHeap,
     program_start: u64,
     stack_end: u64,
+    /// Features enabled for the process
+    features: FeatureSet,
+    /// Tracer for writes, if enabled
+    write_tracer: Option<Box<WriteTracer>>,
 }
 
 impl Memory {
@@ -35,6 +39,8 @@ impl Memory {
             heap,
             program_start: 0,
             stack_end,
+            features: Feature::default_const(),
+            write_tracer: None,
         }
     }
 
@@ -84,6 +90,8 @@ impl Memory {
             heap,
             program_start,
             stack_end,
+            features: Feature::default_const(),
+            write_tracer: None,
         })
     }
 
@@ -97,6 +105,19 @@ impl Memory {
         self.env = env;
     }
 
+    /// Get the features enabled for the process
+    #[inline(always)]
+    pub fn features(&self) -> FeatureSet {
+        self.features
+    }
+
+    /// Set the features enabled for the process
+    pub fn set_features(&mut self, features: FeatureSet) {
+        self.features = features;
+        let track_free = features.contains(Feature::mem_track_free);
+        self.heap.set_track_free(track_free);
+    }
+
     /// Get the physical starting address of the program region
     pub fn program_start(&self) -> u64 {
         self.program_start
@@ -140,7 +161,7 @@ impl Memory {
     /// also needs to have execute permission. Region permissions are still checked,
     /// of course.
     pub fn read(&'_ self, addr: u64, flags: AccessFlags) -> Result<Reader<'_>, Error> {
-        let flags = perm!(r) | convert_region_flags(flags);
+        let flags = perm!(r) | self.convert_region_flags(flags);
         let (section_idx, page_idx, page_off, max_page_off) = self.calculate(addr, flags)?;
         let page = self.page_by_indices_unchecked(section_idx, page_idx);
 
@@ -153,7 +174,7 @@ impl Memory {
     /// If any region bit is specified, then only those regions are allowed to
     /// be accessed. Otherwise all regions are allowed (permissions are still checked, of course)
     pub fn write(&'_ mut self, addr: u64, flags: AccessFlags) -> Result<Writer<'_>, Error> {
-        let flags = perm!(w) | convert_region_flags(flags);
+        let flags = perm!(w) | self.convert_region_flags(flags);
         let (section_idx, page_idx, page_off, max_page_off) = self.calculate(addr, flags)?;
 
         Ok(Writer::new(
@@ -183,7 +204,7 @@ impl Memory {
     pub fn calculate(&self, addr: u64, flags: AccessFlags) -> Result<(u32, u32, u32, u32), Error> {
         // return section index, page index, page offset, max page offset
         if !self.heap.check_allocated(addr) {
-            if enabled!("mem-strict-heap") {
+            if enabled!(self.features, "mem-strict-heap") {
                 cu::error!(
                     "accessing unallocated heap address: 0x{addr:016x} ({})",
                     self.format_addr(addr)
@@ -195,8 +216,15 @@ impl Memory {
                 self.format_addr(addr)
             );
         }
+        if let Some(alloc_start) = self.heap.check_freed(addr) {
+            cu::error!(
+                "accessing freed heap address: 0x{addr:016x} ({})",
+                self.format_addr(addr)
+            );
+            return Err(Error::UseAfterFree(addr, alloc_start, flags));
+        }
         let Some(section_idx) = self.find_section_idx(addr) else {
-            if enabled!("mem-strict-section") {
+            if enabled!(self.features, "mem-strict-section") {
                 cu::error!(
                     "accessing invalid section: 0x{addr:016x} ({})",
                     self.format_addr(addr)
@@ -212,7 +240,7 @@ impl Memory {
         let section = &self.sections[section_idx];
         // permission check
         if !flags.all(AccessFlag::Force) && !section.flags.all(flags.perms()) {
-            if enabled!("mem-permission") {
+            if enabled!(self.features, "mem-permission") {
                 cu::error!(
                     "permission denied: 0x{addr:016x} ({})",
                     self.format_addr(addr)
@@ -231,6 +259,17 @@ impl Memory {
         Ok((section_idx as u32, page_idx, page_off, max_page_off))
     }
 
+    /// Check if the address is in one of the sections of the program
+    pub fn is_program_addr(&self, addr: u64) -> bool {
+        self.find_section_idx(addr)
+            .is_some_and(|i| self.sections[i].flags.any(region!(program)))
+    }
+
+    /// Get the flags (permissions and region) of the section the address is in
+    pub fn section_flags(&self, addr: u64) -> Option<AccessFlags> {
+        self.find_section_idx(addr).map(|i| self.sections[i].flags)
+    }
+
     fn find_section_idx(&self, address: u64) -> Option<usize> {
         let i = match self.sections.binary_search_by_key(&address, |s| s.start()) {
             Ok(i) => i,
@@ -244,11 +283,67 @@ impl Memory {
         }
     }
 
+    /// Start recording writes to the memory, discarding writes recorded
+    /// previously, if any
+    pub fn start_write_trace(&mut self) {
+        self.write_tracer = Some(Box::default());
+    }
+
+    /// Stop recording writes to the memory, and return the writes recorded
+    pub fn stop_write_trace(&mut self) -> WriteTrace {
+        self.write_tracer
+            .take()
+            .map(|x| x.into_trace())
+            .unwrap_or_default()
+    }
+
+    /// Check if writes to the memory are being recorded
+    #[inline]
+    pub fn is_tracing_writes(&self) -> bool {
+        self.write_tracer.is_some()
+    }
+
+    /// Get the write tracer, if writes are being recorded
+    pub fn write_tracer(&self) -> Option<&WriteTracer> {
+        self.write_tracer.as_deref()
+    }
+
+    /// Get the write tracer, if writes are being recorded
+    pub fn write_tracer_mut(&mut self) -> Option<&mut WriteTracer> {
+        self.write_tracer.as_deref_mut()
+    }
+
     /// Allocate `size` bytes on the heap.
     pub fn alloc(&mut self, size: u32) -> Result<u64, Error> {
         self.heap.alloc(size)
     }
 
+    /// Free the allocation on the heap starting at `addr`
+    ///
+    /// This does nothing unless the heap is tracking allocations
+    /// (see the `mem-track-free` feature), or if the allocation was made
+    /// before tracking was enabled. The freed memory is poisoned
+    /// with [`HEAP_FREE_POISON`], and any access to it is an error
+    /// until the space is reclaimed
+    pub fn free(&mut self, addr: u64) -> Result<(), Error> {
+        if self.heap.is_untracked(addr) {
+            return Ok(());
+        }
+        let size = self.heap.allocation_size(addr)?;
+        let poison = vec![HEAP_FREE_POISON; size as usize];
+        Ptr!(<u8>(addr)).store_slice(&poison, self)?;
+        self.heap.free(addr)?;
+        Ok(())
+    }
+
+    fn convert_region_flags(&self, flags: AccessFlags) -> AccessFlags {
+        if flags.any(region!(all)) && enabled!(self.features, "mem-strict-section") {
+            flags
+        } else {
+            flags | region!(all) // allow all regions
+        }
+    }
+
     /// Allocate space on the heap for the given byte slice,
     /// and copy the slice to the allocated space.
     ///
@@ -259,11 +354,3 @@ impl Memory {
         Ok(ptr)
     }
 }
-
-fn convert_region_flags(flags: AccessFlags) -> AccessFlags {
-    if flags.any(region!(all)) && enabled!("mem-strict-section") {
-        flags
-    } else {
-        flags | region!(all) // allow all regions
-    }
-}
@@ -6,6 +6,8 @@ mod error;
 pub use error::*;
 mod heap;
 pub use heap::*;
+mod inspect;
+pub use inspect::*;
 #[allow(clippy::module_inception)]
 mod memory;
 pub use memory::*;
@@ -19,6 +21,8 @@ mod read;
 pub use read::*;
 mod write;
 pub use write::*;
+mod write_trace;
+pub use write_trace::*;
 
 mod section;
 pub use section::*;
@@ -29,7 +33,7 @@ pub mod traits;
 pub use traits::{MemLayout, MemObject};
 
 pub use blueflame_deps::{align_down, align_up};
-pub use blueflame_proc_macros::MemObject;
+pub use blueflame_proc_macros::{MemInspect, MemObject};
 
 pub const PAGE_SIZE: u32 = 0x1000;
 pub const REGION_ALIGN: u64 = 0x10000;
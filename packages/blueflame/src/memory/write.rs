@@ -62,6 +62,7 @@ impl<'m> Writer<'m> {
         };
         let val: bool = val.into();
         trace!(bool, self.memory.format_addr(self.addr), val);
+        self.trace_write(1, val as u64);
         let page = self
             .memory
             .page_by_indices_mut_unchecked(self.section_idx, self.page_idx);
@@ -79,6 +80,7 @@ impl<'m> Writer<'m> {
         };
         let val: u8 = val.into();
         trace!(1, self.memory.format_addr(self.addr), val, 2);
+        self.trace_write(1, val as u64);
         let page = self
             .memory
             .page_by_indices_mut_unchecked(self.section_idx, self.page_idx);
@@ -101,6 +103,7 @@ impl<'m> Writer<'m> {
         };
         let val: u16 = val.into();
         trace!(2, self.memory.format_addr(self.addr), val, 4);
+        self.trace_write(2, val as u64);
         let page = self
             .memory
             .page_by_indices_mut_unchecked(self.section_idx, self.page_idx);
@@ -123,6 +126,7 @@ impl<'m> Writer<'m> {
         };
         let val: u32 = val.into();
         trace!(4, self.memory.format_addr(self.addr), val, 8);
+        self.trace_write(4, val as u64);
         let page = self
             .memory
             .page_by_indices_mut_unchecked(self.section_idx, self.page_idx);
@@ -145,6 +149,7 @@ impl<'m> Writer<'m> {
         };
         let val: u64 = val.into();
         trace!(8, self.memory.format_addr(self.addr), val, 16);
+        self.trace_write(8, val as u64);
         let page = self
             .memory
             .page_by_indices_mut_unchecked(self.section_idx, self.page_idx);
@@ -170,6 +175,28 @@ impl<'m> Writer<'m> {
         self.write_u64(val)
     }
 
+    /// Record the write with the write tracer, if enabled.
+    ///
+    /// Must be called after `prep_write` and before the value is written
+    fn trace_write(&mut self, len: u32, new: u64) {
+        if !self.memory.is_tracing_writes() {
+            return;
+        }
+        let page = self
+            .memory
+            .page_by_indices_unchecked(self.section_idx, self.page_idx);
+        let old = match len {
+            1 => page.read_u8(self.page_off) as u64,
+            2 => page.read_u16(self.page_off) as u64,
+            4 => page.read_u32(self.page_off) as u64,
+            _ => page.read_u64(self.page_off),
+        };
+        let addr = self.addr;
+        if let Some(tracer) = self.memory.write_tracer_mut() {
+            tracer.record(addr, len, old, new);
+        }
+    }
+
     /// Prepare a write, then operate on the page
     ///
     /// This must be done through a FnOnce closure because of borrowing rules
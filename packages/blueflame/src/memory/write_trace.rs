@@ -0,0 +1,101 @@
+/// A write to memory recorded by the [`WriteTracer`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WriteRecord {
+    /// Address written to
+    pub addr: u64,
+    /// Number of bytes written (1, 2, 4 or 8)
+    pub size: u32,
+    /// Value before the write
+    pub old: u64,
+    /// Value after the write
+    pub new: u64,
+    /// PC of the last instruction executed before the write
+    pub pc: u64,
+    /// Jump target of the current stack frame (i.e. the start of the
+    /// function being executed), 0 if there is no stack frame
+    pub function: u64,
+}
+
+/// A change to a GDT flag recorded by the [`WriteTracer`].
+///
+/// The flag values are stored in the TriggerParam proxy instead of the memory,
+/// so the changes are recorded where the flags are set
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GdtWriteRecord {
+    /// Name of the flag if it's accessed by name, otherwise the hash of the name
+    pub flag: String,
+    /// Index of the element, if the flag is an array
+    pub array_idx: Option<i32>,
+    /// Value before the change, formatted for display
+    pub old: String,
+    /// Value after the change, formatted for display
+    pub new: String,
+    /// PC of the last instruction executed before the change
+    pub pc: u64,
+    /// Jump target of the current stack frame, 0 if there is no stack frame
+    pub function: u64,
+}
+
+/// Writes recorded by the [`WriteTracer`]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WriteTrace {
+    /// Writes to the memory, in the order they happened
+    pub writes: Vec<WriteRecord>,
+    /// Changes to GDT flags, in the order they happened
+    pub gdt_writes: Vec<GdtWriteRecord>,
+}
+
+/// Records writes to the memory and changes to GDT flags.
+///
+/// Unlike the `trace-memory` feature, the tracer is selected at runtime with
+/// [`Memory::start_write_trace`](crate::memory::Memory::start_write_trace).
+/// Since the memory doesn't know where the write is from, the processor
+/// updates the location with [`set_location`](Self::set_location) as it executes
+#[derive(Debug, Clone, Default)]
+pub struct WriteTracer {
+    trace: WriteTrace,
+    pc: u64,
+    function: u64,
+}
+
+impl WriteTracer {
+    /// Set the location of the code currently executing
+    #[inline]
+    pub fn set_location(&mut self, pc: u64, function: u64) {
+        self.pc = pc;
+        self.function = function;
+    }
+
+    /// Record a write at the current location
+    pub fn record(&mut self, addr: u64, size: u32, old: u64, new: u64) {
+        self.trace.writes.push(WriteRecord {
+            addr,
+            size,
+            old,
+            new,
+            pc: self.pc,
+            function: self.function,
+        });
+    }
+
+    /// Record a change to a GDT flag at the current location
+    pub fn record_gdt(&mut self, flag: String, array_idx: Option<i32>, old: String, new: String) {
+        self.trace.gdt_writes.push(GdtWriteRecord {
+            flag,
+            array_idx,
+            old,
+            new,
+            pc: self.pc,
+            function: self.function,
+        });
+    }
+
+    /// Get the writes to the memory recorded so far
+    pub fn records(&self) -> &[WriteRecord] {
+        &self.trace.writes
+    }
+
+    pub fn into_trace(self) -> WriteTrace {
+        self.trace
+    }
+}
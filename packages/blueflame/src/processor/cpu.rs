@@ -5,7 +5,7 @@ use enum_map::EnumMap;
 
 use blueflame_deps::trace_call;
 
-use crate::env::{DataId, GameVer, ProxyId, enabled};
+use crate::env::{DataId, FeatureSet, GameVer, ProxyId, enabled};
 use crate::game::gdt;
 use crate::memory::{MemObject, Ptr};
 use crate::processor::{
@@ -139,7 +139,7 @@ impl VirtualMachine for Cpu3<'_, '_, '_> {
     fn v_execute_until(&mut self, target: u32) -> Result<(), Self::Error> {
         trace_call!("v_execute_until 0x{target:08x}");
         let target_abs = target as u64 + self.proc.main_start();
-        let has_limit = enabled!("limited-block-iteration");
+        let has_limit = enabled!(self.proc.features(), "limited-block-iteration");
 
         for count in 0.. {
             if has_limit && count > BLOCK_ITERATION_LIMIT {
@@ -169,8 +169,12 @@ impl VirtualMachine for Cpu3<'_, '_, '_> {
     fn v_proxy_alloc(&mut self, proxy: ProxyId) -> Result<(), Self::Error> {
         let ptr = match proxy {
             ProxyId::TriggerParam => {
+                let mut trigger_param = gdt::TriggerParam::loaded();
+                if let Some(defaults) = self.proc.game_data().gdt_defaults() {
+                    trigger_param.apply_defaults(defaults);
+                }
                 let mut proxy_list = self.proc.proxies_mut(|p| &mut p.trigger_param);
-                proxy_list.alloc(gdt::TriggerParam::loaded())?
+                proxy_list.alloc(trigger_param)?
             }
         };
         self.write(reg!(x[0]), ptr);
@@ -199,7 +203,7 @@ impl VirtualMachine for Cpu3<'_, '_, '_> {
     fn v_execute_to_complete(&mut self) -> Result<(), Self::Error> {
         trace_call!("v_execute_to_complete");
         let target_abs = INTERNAL_RETURN_ADDRESS;
-        let has_limit = enabled!("limited-block-iteration");
+        let has_limit = enabled!(self.proc.features(), "limited-block-iteration");
 
         for count in 0.. {
             if has_limit && count > BLOCK_ITERATION_LIMIT {
@@ -250,7 +254,7 @@ impl Cpu2<'_, '_> {
         self.stack_trace.push_native(pc);
         self.pc = pc;
 
-        let has_limit = enabled!("limited-block-count");
+        let has_limit = enabled!(self.proc.features(), "limited-block-count");
 
         for count in 0.. {
             if has_limit && count > BLOCK_COUNT_LIMIT {
@@ -275,6 +279,12 @@ impl Cpu2<'_, '_> {
     /// Run one block of execution
     pub fn execute_once(&mut self) -> Result<(), Error> {
         let ver = self.proc.env().game_ver;
+        // hooks are not executed as instructions, so the location
+        // is also updated at the start of each block
+        if self.proc.memory().is_tracing_writes() {
+            let function = self.stack_trace.current_function();
+            self.proc.set_write_trace_location(self.pc, function);
+        }
 
         let (fetch_max_bytes, is_hook) = match self.cpu1.cache[ver].get(self.pc) {
             Ok((exe, step)) => {
@@ -286,7 +296,7 @@ impl Cpu2<'_, '_> {
                             return Err(e);
                         }
                         // executing the middle of replacement code
-                        if enabled!("strict-replace-hook") {
+                        if enabled!(self.proc.features(), "strict-replace-hook") {
                             return Err(e);
                         }
                         // probably doesn't need to fetch that much
@@ -344,13 +354,30 @@ impl Cpu2<'_, '_> {
     }
 
     /// Execute the function, and turn any error that happened into a [`CrashReport`]
+    ///
+    /// The traces being recorded by the process are stopped on crash and kept in the report,
+    /// since the process is usually discarded after a crash
     pub fn with_crash_report<T, F: FnOnce(&mut Self) -> Result<T, Error>>(
         &mut self,
         f: F,
     ) -> Result<T, CrashReport> {
         match f(self) {
             Ok(result) => Ok(result),
-            Err(e) => Err(self.make_crash_report(e)),
+            Err(e) => {
+                let mut report = self.make_crash_report(e);
+                // stop the execution trace first, since it may own the write trace
+                if self.proc.is_tracing_exec() {
+                    report.exec_trace = Some(Box::new(self.proc.stop_exec_trace()));
+                }
+                if self.proc.is_profiling() {
+                    report.profile = Some(Box::new(self.proc.stop_profile()));
+                }
+                if self.proc.memory().is_tracing_writes() {
+                    let trace = self.proc.memory_mut().stop_write_trace();
+                    report.write_trace = Some(Box::new(trace));
+                }
+                Err(report)
+            }
         }
     }
 
@@ -361,7 +388,10 @@ impl Cpu2<'_, '_> {
     pub fn make_crash_report(&self, error: Error) -> CrashReport {
         let main_start = self.proc.main_start();
         let cpu0 = self.cpu1.cpu0.clone();
-        CrashReport::new(Box::new(cpu0), main_start, error)
+        let symbols = self.proc.symbols().cloned();
+        let hooked = self.proc.is_hooked(cpu0.pc);
+        CrashReport::new(Box::new(cpu0), main_start, symbols, error)
+            .with_memory_context(self.proc.memory(), hooked)
     }
 
     /// Allocate memory object on the stack
@@ -403,7 +433,7 @@ impl Cpu2<'_, '_> {
 
     /// Check if the stack-allocated object is corrupted
     pub fn stack_free_size(&mut self, addr: u64, size: u32) -> Result<(), Error> {
-        if !enabled!("check-stack-corruption") {
+        if !enabled!(self.proc.features(), "check-stack-corruption") {
             return Ok(());
         }
         if addr == 0 {
@@ -464,9 +494,9 @@ impl Cpu0 {
     /// Note that this is different from the implementation of the `ret`
     /// instruction, because `ret` instruction sets the PC to `LR - 4`,
     /// and then it's increment by the executor
-    pub fn return_to_lr(&mut self) -> Result<(), Error> {
+    pub fn return_to_lr(&mut self, features: FeatureSet) -> Result<(), Error> {
         let lr = self.read(reg!(lr));
-        self.stack_trace.pop_checked(lr)?;
+        self.stack_trace.pop_checked(lr, features)?;
         self.pc = lr;
         Ok(())
     }
@@ -1,8 +1,9 @@
-use cu::pre::*;
+use std::sync::Arc;
 
 use crate::env::DataId;
-use crate::memory;
-use crate::processor::{Cpu0, reg};
+use crate::memory::{self, AccessFlags, Memory, WriteTrace, access};
+use crate::processor::{Cpu0, ExecTrace, Profile, reg};
+use crate::program::SymbolMap;
 
 #[derive(Debug, Clone, PartialEq, thiserror::Error)]
 pub enum Error {
@@ -22,6 +23,24 @@ pub enum Error {
     BlockCountLimitReached,
     #[error("[limited-block-iteration] block iteration limit reached")]
     BlockIterationLimitReached,
+    #[error(
+        "[watchdog] limit of {limit} instructions reached while executing 0x{hot_start:016x}-0x{hot_end:016x}"
+    )]
+    InstructionLimitReached {
+        limit: u64,
+        hot_start: u64,
+        hot_end: u64,
+    },
+    #[error(
+        "[watchdog] limit of {limit_ms}ms reached while executing 0x{hot_start:016x}-0x{hot_end:016x}"
+    )]
+    TimeLimitReached {
+        limit_ms: u64,
+        hot_start: u64,
+        hot_end: u64,
+    },
+    #[error("execution aborted")]
+    Aborted,
     #[error("[check-stack-frames] stack frames are corrupted")]
     StackFrameCorrupted,
     #[error("[check-return-address] return address 0x{0:016x} does not match expected 0x{1:016x}")]
@@ -43,11 +62,130 @@ pub enum Error {
     Unexpected(String),
 }
 
-#[derive(Constructor, PartialEq, Clone)]
+impl Error {
+    /// Get the main offset in the program where the error is about, if any
+    pub fn main_offset(&self) -> Option<u32> {
+        match self {
+            Self::ExecuteCacheOverlap { new_start, .. } => Some(*new_start),
+            Self::TooBigHook(main_offset) => Some(*main_offset),
+            Self::StrictReplacement { main_offset } => Some(*main_offset),
+            _ => None,
+        }
+    }
+}
+
+#[derive(PartialEq, Clone)]
 pub struct CrashReport {
     pub cpu: Box<Cpu0>,
     pub main_start: u64,
+    /// Symbols for resolving addresses, if available
+    pub symbols: Option<Arc<SymbolMap>>,
     pub error: Error,
+    /// The memory access that caused the crash, if the crash is from a memory error
+    pub fault: Option<MemoryFault>,
+    /// Instructions around PC, empty if the code is not in the program
+    pub disassembly: Vec<CrashDisasmLine>,
+    /// Writes recorded up to the crash, if writes were being traced
+    pub write_trace: Option<Box<WriteTrace>>,
+    /// Instructions counted up to the crash, if the process was being profiled
+    pub profile: Option<Box<Profile>>,
+    /// Blocks executed up to the crash, if the execution was being traced
+    pub exec_trace: Option<Box<ExecTrace>>,
+}
+
+/// A memory access that caused a crash
+#[derive(Debug, PartialEq, Clone)]
+pub struct MemoryFault {
+    /// Address being accessed
+    pub addr: u64,
+    /// Number of bytes accessed, if it can be decoded from the instruction at PC
+    pub size: Option<u32>,
+    /// Flags of the access (permissions and regions allowed)
+    pub flags: AccessFlags,
+    /// Flags of the section the address is in, `None` if the address is not in any section
+    pub section: Option<AccessFlags>,
+    /// The address formatted relative to the section it's in
+    pub location: String,
+}
+
+/// One instruction in the disassembly of a [`CrashReport`]
+#[derive(Debug, PartialEq, Clone)]
+pub struct CrashDisasmLine {
+    pub addr: u64,
+    pub bits: u32,
+    /// Disassembled instruction, empty if it cannot be decoded
+    pub text: String,
+}
+
+impl CrashReport {
+    /// Number of instructions before and after PC to disassemble
+    pub const DISASM_WINDOW: u64 = 8;
+
+    pub fn new(
+        cpu: Box<Cpu0>,
+        main_start: u64,
+        symbols: Option<Arc<SymbolMap>>,
+        error: Error,
+    ) -> Self {
+        Self {
+            cpu,
+            main_start,
+            symbols,
+            error,
+            fault: None,
+            disassembly: Vec::new(),
+            write_trace: None,
+            profile: None,
+            exec_trace: None,
+        }
+    }
+
+    /// Add the context from the memory at the time of the crash, which
+    /// includes the faulting access and the instructions around PC.
+    ///
+    /// `hooked` should be true if the code at PC is replaced by a hook, in which
+    /// case the access size is not decoded from the instruction
+    pub fn with_memory_context(mut self, memory: &Memory, hooked: bool) -> Self {
+        let pc = self.cpu.pc & !3;
+        let read_insn = |addr: u64| {
+            if !memory.is_program_addr(addr) {
+                return None;
+            }
+            memory
+                .read(addr, access!(force))
+                .ok()?
+                .read_u32::<u32>()
+                .ok()
+        };
+        let start = pc.saturating_sub(Self::DISASM_WINDOW * 4);
+        let end = pc.saturating_add(Self::DISASM_WINDOW * 4);
+        for addr in (start..=end).step_by(4) {
+            let Some(bits) = read_insn(addr) else {
+                continue;
+            };
+            let text = disarm64::decoder::decode(bits)
+                .map(|x| x.to_string())
+                .unwrap_or_default();
+            self.disassembly.push(CrashDisasmLine { addr, bits, text });
+        }
+
+        if let Error::Memory(e) = &self.error
+            && let Some((addr, flags)) = e.access()
+        {
+            let size = match hooked {
+                true => None,
+                false => read_insn(pc).and_then(load_store_size),
+            };
+            self.fault = Some(MemoryFault {
+                addr,
+                size,
+                flags,
+                section: memory.section_flags(addr),
+                location: memory.format_addr(addr),
+            });
+        }
+        self
+    }
 }
 
 impl std::fmt::Display for CrashReport {
@@ -62,7 +200,30 @@ impl std::error::Error for CrashReport {}
 impl std::fmt::Debug for CrashReport {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         writeln!(f, "=== BLUEFLAME CRASH REPORT ===")?;
+        let symbols = self.symbols.as_deref();
         writeln!(f, "Cause: {}", self.error)?;
+        let location = self
+            .error
+            .main_offset()
+            .and_then(|x| symbols?.format_main_offset(x));
+        if let Some(location) = location {
+            writeln!(f, "In: {location}")?;
+        }
+        if let Some(fault) = &self.fault {
+            let size = match fault.size {
+                Some(size) => format!("{size} bytes"),
+                None => "unknown size".to_string(),
+            };
+            let section = match fault.section {
+                Some(flags) => flags.to_string(),
+                None => "not in any section".to_string(),
+            };
+            writeln!(
+                f,
+                "Access: 0x{:016x} ({}), {size}, flags: {}, section: {section}",
+                fault.addr, fault.location, fault.flags
+            )?;
+        }
         writeln!(f)?;
         writeln!(f, "Registers:")?;
         for i in 0..16 {
@@ -85,30 +246,139 @@ impl std::fmt::Debug for CrashReport {
                 "  {reg1}= 0x{x:016x}  {reg3}= 0x{x2:016x}  {reg2}= 0x{v:016x}  {reg4}= 0x{v2:016x}"
             )?;
         }
+        let flags = &self.cpu.flags;
+        writeln!(
+            f,
+            "  NZCV= {}{}{}{}",
+            flags.n as u8, flags.z as u8, flags.c as u8, flags.v as u8
+        )?;
 
         writeln!(f)?;
         writeln!(f, "Main Start: 0x{:016x}", self.main_start)?;
-        writeln!(f, "PC: {}", format_address(self.cpu.pc, self.main_start))?;
+        writeln!(
+            f,
+            "PC: {}",
+            format_address_with_symbols(self.cpu.pc, self.main_start, symbols)
+        )?;
         writeln!(
             f,
             "LR: {}",
-            format_address(self.cpu.read::<u64>(reg!(lr)), self.main_start)
+            format_address_with_symbols(self.cpu.read::<u64>(reg!(lr)), self.main_start, symbols)
         )?;
+        if !self.disassembly.is_empty() {
+            writeln!(f, "Disassembly:")?;
+            for line in &self.disassembly {
+                let marker = if line.addr == self.cpu.pc & !3 {
+                    "->"
+                } else {
+                    "  "
+                };
+                let text = if line.text.is_empty() {
+                    "<cannot decode>"
+                } else {
+                    line.text.as_str()
+                };
+                writeln!(
+                    f,
+                    "{marker}{}  {:08x}  {text}",
+                    format_address_with_symbols(line.addr, self.main_start, None),
+                    line.bits
+                )?;
+            }
+        }
         writeln!(f, "Stack Trace: (top is most recent)")?;
         writeln!(
             f,
             "{}",
-            self.cpu.stack_trace.format_with_main_start(self.main_start)
+            self.cpu
+                .stack_trace
+                .format_with_symbols(self.main_start, symbols)
         )?;
 
         Ok(())
     }
 }
 
+/// Decode the number of bytes accessed by a load or store instruction.
+///
+/// Only the common forms (single register, pair, literal and exclusive) are decoded
+fn load_store_size(bits: u32) -> Option<u32> {
+    // op0 = x1x0 for loads and stores
+    if (bits >> 25) & 0b0101 != 0b0100 {
+        return None;
+    }
+    let size = bits >> 30;
+    let vector = (bits >> 26) & 1 == 1;
+    match (bits >> 27) & 0b111 {
+        // single register
+        0b111 => {
+            let opc_hi = (bits >> 23) & 1 == 1;
+            if vector && size == 0 && opc_hi {
+                Some(16)
+            } else {
+                Some(1 << size)
+            }
+        }
+        // pair
+        0b101 => match (vector, size) {
+            (_, 0) | (false, 1) => Some(8),
+            (false, 2) | (true, 1) => Some(16),
+            (true, 2) => Some(32),
+            _ => None,
+        },
+        // literal
+        0b011 => match (vector, size) {
+            (_, 0) | (false, 2) => Some(4),
+            (_, 1) => Some(8),
+            (true, 2) => Some(16),
+            _ => None,
+        },
+        // exclusive
+        0b001 if (bits >> 24) & 0b111 == 0 => Some(1 << size),
+        _ => None,
+    }
+}
+
 pub fn format_address(addr: u64, main_start: u64) -> String {
-    if main_start <= addr {
-        format!("0x{addr:016x} (main+0x{:08x})", addr - main_start)
-    } else {
-        format!("0x{addr:016x}                ")
+    format_address_with_symbols(addr, main_start, None)
+}
+
+/// Format the address, and append the function it's in if it can be resolved by the symbols
+pub fn format_address_with_symbols(
+    addr: u64,
+    main_start: u64,
+    symbols: Option<&SymbolMap>,
+) -> String {
+    if main_start > addr {
+        return format!("0x{addr:016x}                ");
+    }
+    let main_offset = addr - main_start;
+    let name = u32::try_from(main_offset)
+        .ok()
+        .and_then(|x| symbols?.format_main_offset(x));
+    match name {
+        Some(name) => format!("0x{addr:016x} (main+0x{main_offset:08x}) {name}"),
+        None => format!("0x{addr:016x} (main+0x{main_offset:08x})"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_load_store_size() {
+        // ldr x0, [x1]
+        assert_eq!(load_store_size(0xf9400020), Some(8));
+        // ldrb w0, [x1]
+        assert_eq!(load_store_size(0x39400020), Some(1));
+        // str w0, [x1]
+        assert_eq!(load_store_size(0xb9000020), Some(4));
+        // stp x29, x30, [sp, #-0x10]!
+        assert_eq!(load_store_size(0xa9bf7bfd), Some(16));
+        // ldr q0, [x0]
+        assert_eq!(load_store_size(0x3dc00000), Some(16));
+        // nop
+        assert_eq!(load_store_size(0xd503201f), None);
     }
 }
@@ -0,0 +1,272 @@
+use std::io::{Read, Write};
+
+use flate2::Compression;
+use flate2::write::GzEncoder;
+use rkyv::rancor;
+
+use crate::memory::WriteRecord;
+use crate::processor::Cpu0;
+use crate::program::SymbolMap;
+
+/// An event in the [`ExecTrace`]
+#[derive(Debug, Clone, PartialEq, Eq, rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)]
+pub enum TraceEvent {
+    /// A block of instructions starts executing at `pc`.
+    ///
+    /// `regs` are the general purpose registers (index, value) that changed since
+    /// the previous block or hook. Index 31 is SP
+    Block { pc: u64, regs: Vec<(u8, u64)> },
+    /// A native hook is executed at `pc`, with the registers changed like [`Block`](Self::Block)
+    Hook { pc: u64, regs: Vec<(u8, u64)> },
+    /// Memory is written by the previous block or hook
+    Write { addr: u64, size: u32, value: u64 },
+}
+
+impl TraceEvent {
+    /// Format the event for display, resolving code addresses with the symbols
+    pub fn format(&self, main_start: u64, symbols: Option<&SymbolMap>) -> String {
+        let format_pc = |pc: u64| {
+            let Some(main_offset) = pc
+                .checked_sub(main_start)
+                .and_then(|x| u32::try_from(x).ok())
+            else {
+                return format!("0x{pc:016x}");
+            };
+            match symbols.and_then(|x| x.format_main_offset(main_offset)) {
+                Some(name) => format!("main+0x{main_offset:08x} <{name}>"),
+                None => format!("main+0x{main_offset:08x}"),
+            }
+        };
+        let format_regs = |regs: &[(u8, u64)]| {
+            regs.iter()
+                .map(|(i, value)| match i {
+                    31 => format!("sp=0x{value:x}"),
+                    i => format!("x{i}=0x{value:x}"),
+                })
+                .collect::<Vec<_>>()
+                .join(" ")
+        };
+        match self {
+            Self::Block { pc, regs } => format!("block {} {}", format_pc(*pc), format_regs(regs)),
+            Self::Hook { pc, regs } => format!("hook  {} {}", format_pc(*pc), format_regs(regs)),
+            Self::Write { addr, size, value } => {
+                format!("write 0x{addr:016x} ({size} bytes) = 0x{value:x}")
+            }
+        }
+        .trim_end()
+        .to_string()
+    }
+}
+
+/// Trace of the execution of the processor, for finding where
+/// two builds of the simulator start to behave differently.
+///
+/// The trace is recorded by the process with
+/// [`Process::start_exec_trace`](crate::processor::Process::start_exec_trace)
+#[derive(
+    Debug, Clone, Default, PartialEq, Eq, rkyv::Archive, rkyv::Serialize, rkyv::Deserialize,
+)]
+pub struct ExecTrace {
+    pub events: Vec<TraceEvent>,
+}
+
+/// The first difference between 2 traces
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TraceDivergence {
+    /// Index of the first event that is different
+    pub index: usize,
+    /// The event in the expected trace, `None` if the expected trace ended
+    pub expected: Option<TraceEvent>,
+    /// The event in the actual trace, `None` if the actual trace ended
+    pub actual: Option<TraceEvent>,
+    /// Events before the divergence (which are the same in both traces)
+    pub context: Vec<TraceEvent>,
+}
+
+impl ExecTrace {
+    /// Find the first event where `actual` is different from this trace.
+    /// `context` is the max number of events before the divergence to include
+    pub fn first_divergence(&self, actual: &ExecTrace, context: usize) -> Option<TraceDivergence> {
+        let len = self.events.len().max(actual.events.len());
+        let index = (0..len).find(|i| self.events.get(*i) != actual.events.get(*i))?;
+        let context_start = index.saturating_sub(context);
+        Some(TraceDivergence {
+            index,
+            expected: self.events.get(index).cloned(),
+            actual: actual.events.get(index).cloned(),
+            context: self.events[context_start..index].to_vec(),
+        })
+    }
+}
+
+impl TraceDivergence {
+    /// Format the divergence for display
+    pub fn format(&self, main_start: u64, symbols: Option<&SymbolMap>) -> String {
+        let mut out = String::new();
+        let context_start = self.index - self.context.len();
+        for (i, event) in self.context.iter().enumerate() {
+            out.push_str(&format!(
+                "  {:>8}  {}\n",
+                context_start + i,
+                event.format(main_start, symbols)
+            ));
+        }
+        let format_event = |event: &Option<TraceEvent>| match event {
+            Some(x) => x.format(main_start, symbols),
+            None => "<end of trace>".to_string(),
+        };
+        out.push_str(&format!(
+            "- {:>8}  {}\n",
+            self.index,
+            format_event(&self.expected)
+        ));
+        out.push_str(&format!(
+            "+ {:>8}  {}\n",
+            self.index,
+            format_event(&self.actual)
+        ));
+        out
+    }
+}
+
+/// Errors encoding or decoding traces
+#[derive(Debug, thiserror::Error)]
+pub enum TraceError {
+    #[error("fail to encode the trace: {0}")]
+    Encode(String),
+    #[error("fail to decode the trace: {0}")]
+    Decode(String),
+}
+
+/// Encode the traces (e.g. one per simulation step) into compressed bytes
+pub fn encode_exec_traces(traces: &[ExecTrace]) -> Result<Vec<u8>, TraceError> {
+    let data = rkyv::to_bytes::<rancor::Error>(&traces.to_vec())
+        .map_err(|e| TraceError::Encode(e.to_string()))?;
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder
+        .write_all(&data)
+        .map_err(|e| TraceError::Encode(e.to_string()))?;
+    encoder
+        .finish()
+        .map_err(|e| TraceError::Encode(e.to_string()))
+}
+
+/// Decode the traces encoded with [`encode_exec_traces`]
+pub fn decode_exec_traces(data: &[u8]) -> Result<Vec<ExecTrace>, TraceError> {
+    let mut decoder = flate2::read::GzDecoder::new(data);
+    let mut decoded = Vec::new();
+    decoder
+        .read_to_end(&mut decoded)
+        .map_err(|e| TraceError::Decode(e.to_string()))?;
+    rkyv::from_bytes::<Vec<ExecTrace>, rancor::Error>(&decoded)
+        .map_err(|e| TraceError::Decode(e.to_string()))
+}
+
+/// State for recording the trace in the process
+#[derive(Debug, Clone)]
+pub(crate) struct ExecTracer {
+    trace: ExecTrace,
+    /// Registers at the previous event
+    regs: [u64; 32],
+    /// Number of write records already added to the trace
+    write_index: usize,
+    /// If the write trace of the memory is started by the tracer
+    pub owns_write_trace: bool,
+}
+
+impl ExecTracer {
+    pub fn new(owns_write_trace: bool, write_index: usize) -> Self {
+        Self {
+            trace: ExecTrace::default(),
+            regs: [0; 32],
+            write_index,
+            owns_write_trace,
+        }
+    }
+
+    /// Record a block or hook, and the writes since the previous event
+    pub fn record(&mut self, cpu: &Cpu0, hook: bool, writes: &[WriteRecord]) {
+        self.record_writes(writes);
+        let regs = cpu.gp_snapshot();
+        let changed = (0..32u8)
+            .filter(|i| regs[*i as usize] != self.regs[*i as usize])
+            .map(|i| (i, regs[i as usize]))
+            .collect();
+        self.regs = regs;
+        let pc = cpu.pc;
+        self.trace.events.push(if hook {
+            TraceEvent::Hook { pc, regs: changed }
+        } else {
+            TraceEvent::Block { pc, regs: changed }
+        });
+    }
+
+    /// Add the new writes to the trace
+    pub fn record_writes(&mut self, writes: &[WriteRecord]) {
+        for write in writes.iter().skip(self.write_index) {
+            self.trace.events.push(TraceEvent::Write {
+                addr: write.addr,
+                size: write.size,
+                value: write.new,
+            });
+        }
+        self.write_index = self.write_index.max(writes.len());
+    }
+
+    pub fn into_trace(self) -> ExecTrace {
+        self.trace
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_first_divergence() {
+        let block = |pc: u64| TraceEvent::Block {
+            pc,
+            regs: vec![(0, pc)],
+        };
+        let expected = ExecTrace {
+            events: vec![block(0x10), block(0x20), block(0x30), block(0x40)],
+        };
+        let mut actual = expected.clone();
+        assert_eq!(expected.first_divergence(&actual, 2), None);
+
+        actual.events[2] = block(0x34);
+        let divergence = expected.first_divergence(&actual, 1).unwrap();
+        assert_eq!(divergence.index, 2);
+        assert_eq!(divergence.expected, Some(block(0x30)));
+        assert_eq!(divergence.actual, Some(block(0x34)));
+        assert_eq!(divergence.context, vec![block(0x20)]);
+
+        actual.events.truncate(2);
+        let divergence = expected.first_divergence(&actual, 5).unwrap();
+        assert_eq!(divergence.index, 2);
+        assert_eq!(divergence.actual, None);
+        assert_eq!(divergence.context.len(), 2);
+    }
+
+    #[test]
+    fn test_encode_decode() {
+        let traces = vec![
+            ExecTrace {
+                events: vec![
+                    TraceEvent::Hook {
+                        pc: 0x100,
+                        regs: vec![(31, 0x8000)],
+                    },
+                    TraceEvent::Write {
+                        addr: 0x2000,
+                        size: 4,
+                        value: 1,
+                    },
+                ],
+            },
+            ExecTrace::default(),
+        ];
+        let data = encode_exec_traces(&traces).unwrap();
+        assert_eq!(decode_exec_traces(&data).unwrap(), traces);
+    }
+}
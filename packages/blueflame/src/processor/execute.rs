@@ -20,6 +20,13 @@ where
                 main_offset: (cpu.pc - proc.main_start()) as u32,
             });
         }
+        proc.tick_watchdog(cpu.pc, 1)?;
+        if proc.is_profiling() {
+            proc.record_profile(&cpu.stack_trace, 1, true);
+        }
+        if proc.is_tracing_exec() {
+            proc.record_exec_trace(cpu, true);
+        }
         self(cpu, proc)
     }
 }
@@ -88,11 +88,19 @@ impl InsnVec {
 
 impl Execute for InsnVec {
     fn execute_from(&self, cpu: &mut Cpu0, proc: &mut Process, step: u32) -> Result<(), Error> {
-        let limit = if enabled!("limited-block-iteration") {
+        let limit = if enabled!(proc.features(), "limited-block-iteration") {
             BLOCK_ITERATION_LIMIT
         } else {
             usize::MAX
         };
+        let count = self.insns.len().saturating_sub(step as usize) as u64;
+        proc.tick_watchdog(cpu.pc, count)?;
+        if proc.is_profiling() {
+            proc.record_profile(&cpu.stack_trace, count, false);
+        }
+        if proc.is_tracing_exec() {
+            proc.record_exec_trace(cpu, false);
+        }
         for (i, x) in self.insns.iter().skip(step as usize).enumerate() {
             if i >= limit {
                 return Err(Error::BlockIterationLimitReached);
@@ -106,6 +114,9 @@ impl Execute for InsnVec {
                 Entry::Opcode(opcode) => (opcode, None),
                 Entry::LegacyParse(opcode, legacy_insn) => (opcode, legacy_insn.as_ref()),
             };
+            if proc.memory().is_tracing_writes() {
+                proc.set_write_trace_location(cpu.pc, cpu.stack_trace.current_function());
+            }
 
             match op::execute(cpu, proc, *opcode) {
                 op::ExecResult::Handled => {
@@ -39,7 +39,8 @@ impl ExecutableInstruction for RetArgsInstruction {
         );
         // instruction executor will increment PC later
         let new_pc = xn_val - 4;
-        core.cpu.stack_trace.pop_checked(xn_val)?;
+        let features = core.proc.features();
+        core.cpu.stack_trace.pop_checked(xn_val, features)?;
         core.cpu.pc = new_pc;
         Ok(())
     }
@@ -12,6 +12,12 @@ mod hook;
 pub use hook::*;
 mod stack_trace;
 pub use stack_trace::*;
+mod profiler;
+pub use profiler::*;
+mod exec_trace;
+pub use exec_trace::*;
+mod watchdog;
+pub use watchdog::*;
 
 pub mod insn;
 
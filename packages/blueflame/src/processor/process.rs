@@ -1,20 +1,22 @@
 use std::ops::ControlFlow;
 use std::sync::Arc;
 
-use cu::pre::*;
-
-use crate::env::Environment;
+use crate::env::{Environment, FeatureSet};
 use crate::game::Proxies;
 use crate::memory::{Memory, ProxyGuardMut, ProxyList, ProxyObject, access};
 use crate::processor::insn::{HookedInsnVec, InsnVec};
-use crate::processor::{Error, Execute, Hook, HookProvider};
+use crate::processor::{
+    ABORT_CHECK_INTERVAL, AbortSignal, Clock, Cpu0, Error, ExecTrace, ExecTracer, Execute, Hook,
+    HookProvider, Profile, StackTrace, Watchdog,
+};
+use crate::program::{ActorInfoTable, GameData, SymbolMap};
 
 /// The Process is the container for everything the core tracks
 /// that is not in the Processor.
 ///
 /// Cloning the process will `fork` the process, and the memory will
 /// be shared (clone on write)
-#[derive(Clone, Constructor)]
+#[derive(Clone)]
 pub struct Process {
     /// Main memory of the game
     memory: Arc<Memory>,
@@ -22,6 +24,20 @@ pub struct Process {
     proxies: Arc<Proxies>,
     /// Hooks for this process
     hook_provider: Arc<dyn HookProvider>,
+    /// Symbols for resolving addresses in crash reports, if available
+    symbols: Option<Arc<SymbolMap>>,
+    /// Game data files from the program image
+    game_data: Arc<GameData>,
+    /// Instructions executed, if the process is being profiled
+    profile: Option<Box<Profile>>,
+    /// Blocks and hooks executed, if the execution is being traced
+    exec_trace: Option<Box<ExecTracer>>,
+    /// Instruction and time limits, if set
+    watchdog: Option<Box<Watchdog>>,
+    /// Signal to stop the execution, if set
+    abort_signal: Option<Arc<dyn AbortSignal>>,
+    /// Number of blocks to execute before checking the abort signal
+    blocks_until_abort_check: u32,
 }
 static_assertions::assert_impl_all!(Process: Send, Sync);
 
@@ -32,11 +48,45 @@ impl std::fmt::Debug for Process {
 }
 
 impl Process {
+    pub fn new(
+        memory: Arc<Memory>,
+        proxies: Arc<Proxies>,
+        hook_provider: Arc<dyn HookProvider>,
+        symbols: Option<Arc<SymbolMap>>,
+    ) -> Self {
+        Self {
+            memory,
+            proxies,
+            hook_provider,
+            symbols,
+            game_data: Arc::default(),
+            profile: None,
+            exec_trace: None,
+            watchdog: None,
+            abort_signal: None,
+            blocks_until_abort_check: ABORT_CHECK_INTERVAL,
+        }
+    }
+
     /// Get the environment configuration
     pub fn env(&self) -> Environment {
         self.memory.env()
     }
 
+    /// Get the features enabled for the process
+    #[inline(always)]
+    pub fn features(&self) -> FeatureSet {
+        self.memory.features()
+    }
+
+    /// Set the features enabled for the process. Since features
+    /// are part of the process, forks of the process are not affected
+    pub fn set_features(&mut self, features: FeatureSet) {
+        if self.memory.features() != features {
+            self.memory_mut().set_features(features);
+        }
+    }
+
     /// Access the main memory of the process
     pub fn memory(&self) -> &Memory {
         &self.memory
@@ -69,11 +119,211 @@ impl Process {
         self.memory.main_start()
     }
 
+    /// Update the location of the code being executed for the write tracer,
+    /// if writes are being traced
+    pub fn set_write_trace_location(&mut self, pc: u64, function: u64) {
+        if !self.memory.is_tracing_writes() {
+            return;
+        }
+        if let Some(tracer) = self.memory_mut().write_tracer_mut() {
+            tracer.set_location(pc, function);
+        }
+    }
+
+    /// Start counting the instructions executed in each call stack,
+    /// discarding the counts recorded previously, if any
+    pub fn start_profile(&mut self) {
+        self.profile = Some(Box::default());
+    }
+
+    /// Stop counting the instructions executed, and return the profile
+    pub fn stop_profile(&mut self) -> Profile {
+        self.profile.take().map(|x| *x).unwrap_or_default()
+    }
+
+    /// Check if the instructions executed are being counted
+    #[inline]
+    pub fn is_profiling(&self) -> bool {
+        self.profile.is_some()
+    }
+
+    /// Record `count` instructions executed in the stack, if the process
+    /// is being profiled. `native` should be true if the code executed
+    /// is a native hook
+    pub fn record_profile(&mut self, stack: &StackTrace, count: u64, native: bool) {
+        if let Some(profile) = self.profile.as_deref_mut() {
+            profile.record(stack, count, native);
+        }
+    }
+
+    /// Start recording the blocks and hooks executed, along with the
+    /// registers changed and memory written, discarding the previous trace, if any.
+    ///
+    /// The memory write trace is started if it's not started already
+    pub fn start_exec_trace(&mut self) {
+        let owns_write_trace = match self.exec_trace.take() {
+            Some(tracer) => tracer.owns_write_trace,
+            None => !self.memory.is_tracing_writes(),
+        };
+        if owns_write_trace {
+            self.memory_mut().start_write_trace();
+        }
+        let write_index = self
+            .memory
+            .write_tracer()
+            .map(|x| x.records().len())
+            .unwrap_or_default();
+        self.exec_trace = Some(Box::new(ExecTracer::new(owns_write_trace, write_index)));
+    }
+
+    /// Stop recording the execution, and return the trace.
+    ///
+    /// The memory write trace is stopped if it's started by [`start_exec_trace`](Self::start_exec_trace)
+    pub fn stop_exec_trace(&mut self) -> ExecTrace {
+        let Some(mut tracer) = self.exec_trace.take() else {
+            return ExecTrace::default();
+        };
+        if let Some(writes) = self.memory.write_tracer() {
+            tracer.record_writes(writes.records());
+        }
+        if tracer.owns_write_trace {
+            self.memory_mut().stop_write_trace();
+        }
+        tracer.into_trace()
+    }
+
+    /// Check if the execution is being traced
+    #[inline]
+    pub fn is_tracing_exec(&self) -> bool {
+        self.exec_trace.is_some()
+    }
+
+    /// Record a block or a native hook (if `hook` is true) starting to
+    /// execute, if the execution is being traced
+    pub fn record_exec_trace(&mut self, cpu: &Cpu0, hook: bool) {
+        let Some(tracer) = self.exec_trace.as_deref_mut() else {
+            return;
+        };
+        let writes = match self.memory.write_tracer() {
+            Some(x) => x.records(),
+            None => &[],
+        };
+        tracer.record(cpu, hook, writes);
+    }
+
     /// Get the hook provider
     pub fn hook_provider_mut(&mut self) -> &mut Arc<dyn HookProvider> {
         &mut self.hook_provider
     }
 
+    /// Set the maximum number of instructions that can be executed
+    /// until the next [`reset_watchdog`](Self::reset_watchdog), or `None` for no limit.
+    ///
+    /// When the limit is exceeded, execution stops with [`Error::InstructionLimitReached`],
+    /// which has the range of code recently executed
+    pub fn set_instruction_limit(&mut self, limit: Option<u64>) {
+        self.update_watchdog(|x| x.set_instruction_limit(limit));
+    }
+
+    /// Get the maximum number of instructions that can be executed, if set
+    pub fn instruction_limit(&self) -> Option<u64> {
+        self.watchdog.as_ref().and_then(|x| x.instruction_limit())
+    }
+
+    /// Set the maximum time in milliseconds that can be spent executing
+    /// until the next [`reset_watchdog`](Self::reset_watchdog), or `None` for no limit.
+    /// The time is read from `clock`.
+    ///
+    /// When the limit is exceeded, execution stops with [`Error::TimeLimitReached`],
+    /// which has the range of code recently executed
+    pub fn set_time_limit(&mut self, clock: Arc<dyn Clock>, limit_ms: Option<u64>) {
+        self.update_watchdog(|x| x.set_time_limit(clock, limit_ms));
+    }
+
+    /// Get the maximum time in milliseconds that can be spent executing, if set
+    pub fn time_limit_ms(&self) -> Option<u64> {
+        self.watchdog.as_ref().and_then(|x| x.time_limit_ms())
+    }
+
+    fn update_watchdog(&mut self, f: impl FnOnce(&mut Watchdog)) {
+        let watchdog = self
+            .watchdog
+            .get_or_insert_with(|| Box::new(Watchdog::new()));
+        f(watchdog);
+        if !watchdog.is_enabled() {
+            self.watchdog = None;
+        }
+    }
+
+    /// Reset the number of instructions executed and the start time
+    /// for the instruction and time limits
+    pub fn reset_watchdog(&mut self) {
+        if let Some(watchdog) = self.watchdog.as_deref_mut() {
+            watchdog.reset();
+        }
+    }
+
+    /// Set the signal to check periodically during execution, or `None` to
+    /// stop checking. When the signal is set, execution stops with [`Error::Aborted`]
+    pub fn set_abort_signal(&mut self, signal: Option<Arc<dyn AbortSignal>>) {
+        self.abort_signal = signal;
+        self.blocks_until_abort_check = ABORT_CHECK_INTERVAL;
+    }
+
+    /// Count `count` instructions starting at `pc` to be executed,
+    /// and return an error if the instruction or time limit is exceeded,
+    /// or if the execution is aborted
+    #[inline]
+    pub fn tick_watchdog(&mut self, pc: u64, count: u64) -> Result<(), Error> {
+        if let Some(watchdog) = self.watchdog.as_deref_mut() {
+            watchdog.tick(pc, count)?;
+        }
+        if let Some(signal) = &self.abort_signal {
+            self.blocks_until_abort_check -= 1;
+            if self.blocks_until_abort_check == 0 {
+                self.blocks_until_abort_check = ABORT_CHECK_INTERVAL;
+                if signal.is_aborted() {
+                    return Err(Error::Aborted);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Get the symbol map, if available
+    pub fn symbols(&self) -> Option<&Arc<SymbolMap>> {
+        self.symbols.as_ref()
+    }
+
+    /// Get the game data files from the program image
+    pub fn game_data(&self) -> &GameData {
+        &self.game_data
+    }
+
+    /// Get the actor properties from the ActorInfo in the program image, if available
+    pub fn actor_info(&self) -> Option<&ActorInfoTable> {
+        self.game_data.actor_info()
+    }
+
+    /// Set the game data files. This is set from the program image when booting
+    pub fn set_game_data(&mut self, game_data: Arc<GameData>) {
+        self.game_data = game_data;
+    }
+
+    /// Check if the code at PC is replaced or extended by a hook
+    pub fn is_hooked(&self, pc: u64) -> bool {
+        let Some(main_offset) = pc
+            .checked_sub(self.main_start())
+            .and_then(|x| u32::try_from(x).ok())
+        else {
+            return false;
+        };
+        matches!(
+            self.hook_provider.fetch(main_offset, self.memory.env()),
+            Ok(Some(_))
+        )
+    }
+
     /// Fetch a block of code for execution
     ///
     /// If `max_bytes` is `Some(n)`, then the function will
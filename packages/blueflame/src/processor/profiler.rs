@@ -0,0 +1,149 @@
+use std::collections::BTreeMap;
+
+use crate::processor::StackTrace;
+use crate::program::SymbolMap;
+
+/// Number of instructions executed in each call stack.
+///
+/// The profile is recorded by the process with
+/// [`Process::start_profile`](crate::processor::Process::start_profile).
+/// Each block of instructions is counted under the stack frames at the
+/// start of the block, so the counts are exact unless the block errors
+/// in the middle. Native code (hooks) is counted as 1 per call, under an extra
+/// `[native]` frame
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Profile {
+    samples: BTreeMap<ProfileStack, u64>,
+}
+
+/// A call stack in the profile
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct ProfileStack {
+    /// Jump targets of the stack frames (i.e. start of the functions),
+    /// from the outermost frame
+    pub frames: Vec<u64>,
+    /// If the count is from native code executed in the innermost frame
+    pub native: bool,
+}
+
+impl Profile {
+    /// Record `count` instructions executed in the stack
+    pub fn record(&mut self, stack: &StackTrace, count: u64, native: bool) {
+        let key = ProfileStack {
+            frames: stack.frames.iter().map(|x| x.jump_target).collect(),
+            native,
+        };
+        *self.samples.entry(key).or_default() += count;
+    }
+
+    /// Add the counts from another profile to this profile
+    pub fn merge(&mut self, other: &Profile) {
+        for (stack, count) in &other.samples {
+            match self.samples.get_mut(stack) {
+                Some(x) => *x += count,
+                None => {
+                    self.samples.insert(stack.clone(), *count);
+                }
+            }
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+
+    /// Get the total count of all stacks
+    pub fn total(&self) -> u64 {
+        self.samples.values().sum()
+    }
+
+    /// Get the counts of all stacks
+    pub fn samples(&self) -> impl Iterator<Item = (&ProfileStack, u64)> {
+        self.samples.iter().map(|(stack, count)| (stack, *count))
+    }
+
+    /// Write the profile in the folded stack format, one line per stack,
+    /// which can be consumed by flamegraph tools (e.g. `flamegraph.pl` or `inferno`).
+    ///
+    /// `prefix` are frames added before the frames in the stack, for example
+    /// to group the stacks by simulation step. Addresses are resolved
+    /// with the symbols if available
+    pub fn write_folded(
+        &self,
+        out: &mut String,
+        prefix: &[&str],
+        main_start: u64,
+        symbols: Option<&SymbolMap>,
+    ) {
+        for (stack, count) in &self.samples {
+            let mut names = prefix
+                .iter()
+                .map(|x| x.replace(';', ":"))
+                .collect::<Vec<_>>();
+            names.extend(
+                stack
+                    .frames
+                    .iter()
+                    .map(|x| format_frame(*x, main_start, symbols)),
+            );
+            if stack.native {
+                names.push("[native]".to_string());
+            }
+            if names.is_empty() {
+                names.push("[unknown]".to_string());
+            }
+            out.push_str(&names.join(";"));
+            out.push_str(&format!(" {count}\n"));
+        }
+    }
+}
+
+fn format_frame(addr: u64, main_start: u64, symbols: Option<&SymbolMap>) -> String {
+    let Some(main_offset) = addr
+        .checked_sub(main_start)
+        .and_then(|x| u32::try_from(x).ok())
+    else {
+        return format!("0x{addr:016x}");
+    };
+    match symbols.and_then(|x| x.resolve(main_offset)) {
+        Some((symbol, 0)) => symbol.name.replace(';', ":"),
+        Some((symbol, offset)) => format!("{}+0x{offset:x}", symbol.name.replace(';', ":")),
+        None => format!("main+0x{main_offset:08x}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_folded_output() {
+        let main_start = 0x1000;
+        let symbols = SymbolMap::parse_csv(
+            "Address,Quality,Size,Name\n\
+            0x0000007100000100,O,000032,foo\n",
+        );
+        let mut stack = StackTrace::default();
+        stack.push_native(0x1100);
+        let mut profile = Profile::default();
+        profile.record(&stack, 3, false);
+        stack.push_bl(0x1200, 0x1108);
+        profile.record(&stack, 5, false);
+        profile.record(&stack, 1, true);
+        profile.record(&stack, 2, false);
+
+        let mut other = Profile::default();
+        other.record(&stack, 1, true);
+        profile.merge(&other);
+        assert_eq!(profile.total(), 12);
+
+        let mut out = String::new();
+        profile.write_folded(&mut out, &["step"], main_start, Some(&symbols));
+        assert_eq!(
+            out,
+            "step;foo 3\n\
+            step;foo;main+0x00000200 7\n\
+            step;foo;main+0x00000200;[native] 2\n"
+        );
+    }
+}
@@ -344,6 +344,11 @@ impl RegValue for (u64, u64) {
 // no other implementation for 128 bit values yet
 
 impl Registers {
+    /// Get the values of all general purpose registers. Index 31 is SP
+    pub fn gp_snapshot(&self) -> [u64; 32] {
+        self.x
+    }
+
     /// Read a register
     ///
     /// For performance, there is no error checking here in release mode,
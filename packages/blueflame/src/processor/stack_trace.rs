@@ -1,5 +1,6 @@
-use crate::env::enabled;
-use crate::processor::{Error, RegName, format_address};
+use crate::env::{FeatureSet, enabled};
+use crate::processor::{Error, RegName, format_address_with_symbols};
+use crate::program::SymbolMap;
 
 #[derive(Debug, Clone, PartialEq, Default)]
 pub struct StackTrace {
@@ -35,11 +36,21 @@ impl StackTrace {
         });
     }
 
-    /// Pop the stack frame
-    pub fn pop_checked(&mut self, lr: u64) -> Result<(), Error> {
+    /// Get the jump target of the most recent frame (i.e. the start of the function
+    /// being executed), or 0 if there are no frames
+    pub fn current_function(&self) -> u64 {
+        self.frames
+            .last()
+            .map(|x| x.jump_target)
+            .unwrap_or_default()
+    }
+
+    /// Pop the stack frame, checking the frames and return address
+    /// if the features are enabled
+    pub fn pop_checked(&mut self, lr: u64, features: FeatureSet) -> Result<(), Error> {
         match self.frames.pop() {
             Some(frame) => {
-                if enabled!("check-return-address") {
+                if enabled!(features, "check-return-address") {
                     let source = match frame.jump_type {
                         FrameType::Bl(source) => source,
                         FrameType::Blr(source, _) => source,
@@ -52,7 +63,7 @@ impl StackTrace {
             }
             None => {
                 cu::error!("stack frames popped while empty");
-                if enabled!("check-stack-frames") {
+                if enabled!(features, "check-stack-frames") {
                     return Err(Error::StackFrameCorrupted);
                 }
             }
@@ -61,9 +72,14 @@ impl StackTrace {
     }
 
     pub fn format_with_main_start(&self, main_start: u64) -> String {
+        self.format_with_symbols(main_start, None)
+    }
+
+    /// Format the stack trace, resolving the addresses with the symbols if available
+    pub fn format_with_symbols(&self, main_start: u64, symbols: Option<&SymbolMap>) -> String {
         let mut result = String::new();
         for frame in self.frames.iter().rev() {
-            result.push_str(&frame.format_with_main_start(main_start));
+            result.push_str(&frame.format_with_symbols(main_start, symbols));
             result.push('\n');
         }
         result
@@ -88,27 +104,33 @@ pub enum FrameType {
 
 impl Frame {
     pub fn format_with_main_start(&self, main_start: u64) -> String {
+        self.format_with_symbols(main_start, None)
+    }
+
+    /// Format the frame, resolving the addresses with the symbols if available
+    pub fn format_with_symbols(&self, main_start: u64, symbols: Option<&SymbolMap>) -> String {
+        let format_address = |addr| format_address_with_symbols(addr, main_start, symbols);
         match self.jump_type {
             FrameType::Bl(from) => {
                 format!(
                     "  {} BL      -> {}",
-                    format_address(from, main_start),
-                    format_address(self.jump_target, main_start)
+                    format_address(from),
+                    format_address(self.jump_target)
                 )
             }
             FrameType::Blr(from, reg_name) => {
                 let reg = format!("{:4}", reg_name.to_string());
                 format!(
                     "  {} BLR{} -> {}",
-                    format_address(from, main_start),
+                    format_address(from),
                     reg,
-                    format_address(self.jump_target, main_start)
+                    format_address(self.jump_target)
                 )
             }
             FrameType::Native => {
                 format!(
                     "                                   native jump -> {}",
-                    format_address(self.jump_target, main_start)
+                    format_address(self.jump_target)
                 )
             }
         }
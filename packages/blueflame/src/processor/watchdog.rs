@@ -0,0 +1,266 @@
+use std::panic::{RefUnwindSafe, UnwindSafe};
+use std::sync::Arc;
+
+use crate::processor::Error;
+
+/// Number of recent blocks to keep for finding where the code is stuck
+const RECENT_BLOCKS: usize = 64;
+
+/// Number of blocks executed between checks of the [`AbortSignal`]
+pub(crate) const ABORT_CHECK_INTERVAL: u32 = 0x1000;
+
+/// Number of blocks executed between checks of the time limit
+const TIME_CHECK_INTERVAL: u32 = 0x1000;
+
+/// Signal for stopping the execution from outside, for example
+/// when the result is no longer needed.
+///
+/// The signal is checked periodically when starting a block. When it is set,
+/// execution stops with [`Error::Aborted`]
+pub trait AbortSignal: Send + Sync + UnwindSafe + RefUnwindSafe {
+    /// Check if the execution should stop
+    fn is_aborted(&self) -> bool;
+}
+
+/// Source of the current time for the time limit of the [`Watchdog`].
+///
+/// How to get the time depends on the platform (for example, in WASM workers),
+/// so it's provided by the user of the processor
+pub trait Clock: Send + Sync + UnwindSafe + RefUnwindSafe {
+    /// Get the current time in milliseconds, from any fixed point in the past
+    fn now_ms(&self) -> u64;
+}
+
+/// Limit on the number of instructions executed and the time taken,
+/// so runaway code (for example, an infinite loop) stops with an error instead of hanging.
+///
+/// Instructions are counted when a block starts executing, so the count
+/// is the same on every platform. The time is checked periodically
+/// when starting a block, and is counted from the first block after the reset
+#[derive(Debug, Clone)]
+pub(crate) struct Watchdog {
+    instruction_limit: Option<u64>,
+    time_limit: Option<TimeLimit>,
+    /// Number of instructions executed since the last reset
+    executed: u64,
+    /// Time of the first block executed since the last reset
+    start_ms: Option<u64>,
+    /// Number of blocks to execute before checking the time
+    blocks_until_time_check: u32,
+    /// (start, end) of the recently executed blocks, as a ring buffer
+    recent: [(u64, u64); RECENT_BLOCKS],
+    /// Index in `recent` for the next block
+    next: usize,
+}
+
+#[derive(Clone)]
+struct TimeLimit {
+    clock: Arc<dyn Clock>,
+    limit_ms: u64,
+}
+
+impl std::fmt::Debug for TimeLimit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TimeLimit")
+            .field("limit_ms", &self.limit_ms)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Watchdog {
+    /// Create a watchdog without any limit
+    pub fn new() -> Self {
+        Self {
+            instruction_limit: None,
+            time_limit: None,
+            executed: 0,
+            start_ms: None,
+            blocks_until_time_check: TIME_CHECK_INTERVAL,
+            recent: [(0, 0); RECENT_BLOCKS],
+            next: 0,
+        }
+    }
+
+    /// Check if any limit is set
+    pub fn is_enabled(&self) -> bool {
+        self.instruction_limit.is_some() || self.time_limit.is_some()
+    }
+
+    pub fn instruction_limit(&self) -> Option<u64> {
+        self.instruction_limit
+    }
+
+    pub fn set_instruction_limit(&mut self, limit: Option<u64>) {
+        self.instruction_limit = limit;
+    }
+
+    pub fn time_limit_ms(&self) -> Option<u64> {
+        self.time_limit.as_ref().map(|x| x.limit_ms)
+    }
+
+    pub fn set_time_limit(&mut self, clock: Arc<dyn Clock>, limit_ms: Option<u64>) {
+        self.time_limit = limit_ms.map(|limit_ms| TimeLimit { clock, limit_ms });
+        self.start_ms = None;
+    }
+
+    /// Reset the instructions executed and the start time
+    pub fn reset(&mut self) {
+        self.executed = 0;
+        self.start_ms = None;
+        self.blocks_until_time_check = TIME_CHECK_INTERVAL;
+        self.recent = [(0, 0); RECENT_BLOCKS];
+        self.next = 0;
+    }
+
+    /// Count `count` instructions starting at `pc`, and return an error
+    /// if a limit is exceeded
+    pub fn tick(&mut self, pc: u64, count: u64) -> Result<(), Error> {
+        self.executed = self.executed.saturating_add(count);
+        self.recent[self.next] = (pc, pc.saturating_add(count.max(1) * 4));
+        self.next = (self.next + 1) % RECENT_BLOCKS;
+        if let Some(limit) = self.instruction_limit
+            && self.executed > limit
+        {
+            let (hot_start, hot_end) = self.hot_range();
+            return Err(Error::InstructionLimitReached {
+                limit,
+                hot_start,
+                hot_end,
+            });
+        }
+        let Some(time_limit) = &self.time_limit else {
+            return Ok(());
+        };
+        let start_ms = match self.start_ms {
+            Some(x) => x,
+            None => {
+                let now = time_limit.clock.now_ms();
+                self.start_ms = Some(now);
+                now
+            }
+        };
+        self.blocks_until_time_check -= 1;
+        if self.blocks_until_time_check != 0 {
+            return Ok(());
+        }
+        self.blocks_until_time_check = TIME_CHECK_INTERVAL;
+        let limit_ms = time_limit.limit_ms;
+        if time_limit.clock.now_ms().saturating_sub(start_ms) <= limit_ms {
+            return Ok(());
+        }
+        let (hot_start, hot_end) = self.hot_range();
+        Err(Error::TimeLimitReached {
+            limit_ms,
+            hot_start,
+            hot_end,
+        })
+    }
+
+    /// Get the range of code covered by the recently executed blocks
+    fn hot_range(&self) -> (u64, u64) {
+        let blocks = self.recent.iter().filter(|(start, end)| start != end);
+        let start = blocks.clone().map(|x| x.0).min().unwrap_or_default();
+        let end = blocks.map(|x| x.1).max().unwrap_or_default();
+        (start, end)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+
+    use super::*;
+    use crate::processor::Process;
+
+    #[test]
+    fn test_watchdog() {
+        let mut watchdog = Watchdog::new();
+        watchdog.set_instruction_limit(Some(100));
+        for _ in 0..9 {
+            assert!(watchdog.tick(0x1000, 8).is_ok());
+            assert!(watchdog.tick(0x1040, 2).is_ok());
+        }
+        // 90 executed
+        assert!(watchdog.tick(0x1000, 8).is_ok());
+        let error = watchdog.tick(0x1040, 3).unwrap_err();
+        assert_eq!(
+            error,
+            Error::InstructionLimitReached {
+                limit: 100,
+                hot_start: 0x1000,
+                hot_end: 0x104c,
+            }
+        );
+
+        watchdog.reset();
+        assert!(watchdog.tick(0x2000, 100).is_ok());
+        assert!(watchdog.tick(0x2000, 1).is_err());
+    }
+
+    struct TestClock(AtomicU64);
+    impl Clock for TestClock {
+        fn now_ms(&self) -> u64 {
+            self.0.load(Ordering::Relaxed)
+        }
+    }
+
+    #[test]
+    fn test_watchdog_time_limit() {
+        let clock = Arc::new(TestClock(AtomicU64::new(1000)));
+        let mut watchdog = Watchdog::new();
+        watchdog.set_time_limit(clock.clone(), Some(50));
+        assert!(watchdog.tick(0x1000, 4).is_ok());
+        // the time is only checked periodically
+        clock.0.store(2000, Ordering::Relaxed);
+        for _ in 0..TIME_CHECK_INTERVAL - 2 {
+            assert!(watchdog.tick(0x1000, 4).is_ok());
+        }
+        let error = watchdog.tick(0x1010, 2).unwrap_err();
+        assert_eq!(
+            error,
+            Error::TimeLimitReached {
+                limit_ms: 50,
+                hot_start: 0x1000,
+                hot_end: 0x1018,
+            }
+        );
+
+        // the time is counted from the first block after the reset
+        watchdog.reset();
+        clock.0.store(5000, Ordering::Relaxed);
+        assert!(watchdog.tick(0x2000, 4).is_ok());
+        clock.0.store(5050, Ordering::Relaxed);
+        for _ in 0..TIME_CHECK_INTERVAL * 2 {
+            assert!(watchdog.tick(0x2000, 4).is_ok());
+        }
+        clock.0.store(5051, Ordering::Relaxed);
+        let result = (0..TIME_CHECK_INTERVAL).try_for_each(|_| watchdog.tick(0x2000, 4));
+        assert!(matches!(result, Err(Error::TimeLimitReached { .. })));
+    }
+
+    struct TestSignal(AtomicBool);
+    impl AbortSignal for TestSignal {
+        fn is_aborted(&self) -> bool {
+            self.0.load(Ordering::Relaxed)
+        }
+    }
+
+    #[test]
+    fn test_abort_signal() {
+        let signal = Arc::new(TestSignal(AtomicBool::new(false)));
+        let mut proc = Process::new_for_test();
+        proc.set_abort_signal(Some(signal.clone()));
+        for _ in 0..ABORT_CHECK_INTERVAL * 2 {
+            assert!(proc.tick_watchdog(0x1000, 4).is_ok());
+        }
+        // aborting in the middle of execution stops at the next check
+        signal.0.store(true, Ordering::Relaxed);
+        let result = (0..ABORT_CHECK_INTERVAL).try_for_each(|_| proc.tick_watchdog(0x1000, 4));
+        assert_eq!(result, Err(Error::Aborted));
+
+        proc.set_abort_signal(None);
+        for _ in 0..ABORT_CHECK_INTERVAL * 2 {
+            assert!(proc.tick_watchdog(0x1000, 4).is_ok());
+        }
+    }
+}
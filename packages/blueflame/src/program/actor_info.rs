@@ -0,0 +1,179 @@
+use std::collections::{BTreeMap, HashMap};
+
+use crate::program::{Byml, BymlError};
+
+/// Actor properties from `ActorInfo.product.byml`, indexed by actor name
+#[derive(Debug, Default, Clone)]
+pub struct ActorInfoTable {
+    actors: HashMap<String, ActorInfo>,
+    /// Actor names by the CRC32 hash of the name
+    names: HashMap<u32, String>,
+}
+
+/// Properties of one actor in `ActorInfo.product.byml`
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ActorInfo {
+    properties: BTreeMap<String, Byml>,
+}
+
+impl ActorInfoTable {
+    /// Parse the table from the decompressed `ActorInfo.product.byml`
+    pub fn parse(data: &[u8]) -> Result<Self, BymlError> {
+        let root = Byml::parse(data)?;
+        let mut actors = HashMap::new();
+        let mut names = HashMap::new();
+        let entries = root
+            .get("Actors")
+            .and_then(|x| x.as_array())
+            .unwrap_or_default();
+        for entry in entries {
+            let Some(properties) = entry.as_hash() else {
+                continue;
+            };
+            let Some(name) = properties.get("name").and_then(|x| x.as_str()) else {
+                continue;
+            };
+            names.insert(crc32fast::hash(name.as_bytes()), name.to_string());
+            actors.insert(
+                name.to_string(),
+                ActorInfo {
+                    properties: properties.clone(),
+                },
+            );
+        }
+        Ok(Self { actors, names })
+    }
+
+    /// Get the properties of the actor
+    pub fn get(&self, actor: &str) -> Option<&ActorInfo> {
+        self.actors.get(actor)
+    }
+
+    /// Get the actor name by the CRC32 hash of the name, which is how
+    /// actors are referred to in other data files (e.g. recipes)
+    pub fn name_by_hash(&self, hash: u32) -> Option<&str> {
+        self.names.get(&hash).map(|x| x.as_str())
+    }
+
+    /// Get the number of actors in the table
+    pub fn len(&self) -> usize {
+        self.actors.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.actors.is_empty()
+    }
+}
+
+impl ActorInfo {
+    /// Get a property by its name in the BYML
+    pub fn get(&self, key: &str) -> Option<&Byml> {
+        self.properties.get(key)
+    }
+
+    pub fn get_str(&self, key: &str) -> Option<&str> {
+        self.get(key).and_then(|x| x.as_str())
+    }
+
+    pub fn get_i32(&self, key: &str) -> Option<i32> {
+        self.get(key).and_then(|x| x.as_i32())
+    }
+
+    pub fn get_f32(&self, key: &str) -> Option<f32> {
+        self.get(key).and_then(|x| x.as_f32())
+    }
+
+    pub fn get_bool(&self, key: &str) -> Option<bool> {
+        self.get(key).and_then(|x| x.as_bool())
+    }
+
+    /// Name of the actor
+    pub fn name(&self) -> &str {
+        self.get_str("name").unwrap_or_default()
+    }
+
+    /// `profile` of the actor, e.g. `WeaponSword`
+    pub fn profile(&self) -> Option<&str> {
+        self.get_str("profile")
+    }
+
+    /// Tags of the actor, e.g. `CanStack`
+    pub fn tags(&self) -> impl Iterator<Item = &str> {
+        let tags: Box<dyn Iterator<Item = &Byml>> = match self.get("tags") {
+            Some(Byml::Hash(x)) => Box::new(x.values()),
+            Some(Byml::Array(x)) => Box::new(x.iter()),
+            _ => Box::new(std::iter::empty()),
+        };
+        tags.filter_map(|x| x.as_str())
+    }
+
+    /// Check if the actor has the tag
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags().any(|x| x == tag)
+    }
+
+    /// `itemSellingPrice` of the actor
+    pub fn item_selling_price(&self) -> Option<i32> {
+        self.get_i32("itemSellingPrice")
+    }
+
+    /// `itemBuyingPrice` of the actor
+    pub fn item_buying_price(&self) -> Option<i32> {
+        self.get_i32("itemBuyingPrice")
+    }
+
+    /// `generalLife` of the actor (durability of equipments)
+    pub fn general_life(&self) -> Option<i32> {
+        self.get_i32("generalLife")
+    }
+
+    /// `cookSpiceBoostEffectiveTime` of the actor
+    pub fn cook_spice_boost_effective_time(&self) -> Option<i32> {
+        self.get_i32("cookSpiceBoostEffectiveTime")
+    }
+
+    /// `cookSpiceBoostHitPointRecover` of the actor
+    pub fn cook_spice_boost_hit_point_recover(&self) -> Option<i32> {
+        self.get_i32("cookSpiceBoostHitPointRecover")
+    }
+
+    /// `cureItemHitPointRecover` of the actor
+    pub fn cure_item_hit_point_recover(&self) -> Option<i32> {
+        self.get_i32("cureItemHitPointRecover")
+    }
+
+    /// `itemUseIconActorName` of the actor
+    pub fn item_use_icon_actor_name(&self) -> Option<&str> {
+        self.get_str("itemUseIconActorName")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_actor_info() {
+        let mut properties = BTreeMap::new();
+        properties.insert(
+            "name".to_string(),
+            Byml::String("Weapon_Sword_001".to_string()),
+        );
+        properties.insert(
+            "profile".to_string(),
+            Byml::String("WeaponSword".to_string()),
+        );
+        properties.insert("generalLife".to_string(), Byml::I32(22));
+        let mut tags = BTreeMap::new();
+        tags.insert("tag0".to_string(), Byml::String("CanStack".to_string()));
+        properties.insert("tags".to_string(), Byml::Hash(tags));
+        let info = ActorInfo { properties };
+
+        assert_eq!(info.name(), "Weapon_Sword_001");
+        assert_eq!(info.profile(), Some("WeaponSword"));
+        assert_eq!(info.general_life(), Some(22));
+        assert_eq!(info.item_selling_price(), None);
+        assert!(info.has_tag("CanStack"));
+        assert!(!info.has_tag("CannotSell"));
+    }
+}
@@ -0,0 +1,339 @@
+use std::collections::BTreeMap;
+
+/// Error reading BYML data
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum BymlError {
+    #[error("not a BYML file")]
+    BadMagic,
+    #[error("unsupported BYML version: {0}")]
+    UnsupportedVersion(u16),
+    #[error("BYML data is out of bounds at 0x{0:x}")]
+    OutOfBounds(usize),
+    #[error("invalid BYML node type 0x{1:02x} at 0x{0:x}")]
+    BadNodeType(usize, u8),
+    #[error("invalid BYML string index: {0}")]
+    BadStringIndex(u32),
+    #[error("BYML nodes are nested too deep")]
+    TooDeep,
+}
+
+/// A node in a BYML document (version 2 to 4, both endianness)
+#[derive(Debug, Clone, PartialEq)]
+pub enum Byml {
+    Null,
+    String(String),
+    Binary(Vec<u8>),
+    Array(Vec<Byml>),
+    Hash(BTreeMap<String, Byml>),
+    Bool(bool),
+    I32(i32),
+    F32(f32),
+    U32(u32),
+    I64(i64),
+    U64(u64),
+    F64(f64),
+}
+
+impl Byml {
+    /// Parse a BYML document. Yaz0-compressed data should be decompressed first
+    pub fn parse(data: &[u8]) -> Result<Self, BymlError> {
+        let big_endian = match data.get(0..2) {
+            Some(b"BY") => true,
+            Some(b"YB") => false,
+            _ => return Err(BymlError::BadMagic),
+        };
+        let mut parser = Parser {
+            data,
+            big_endian,
+            keys: Vec::new(),
+            strings: Vec::new(),
+        };
+        let version = parser.u16(2)?;
+        if !(2..=4).contains(&version) {
+            return Err(BymlError::UnsupportedVersion(version));
+        }
+        let key_table = parser.u32(4)? as usize;
+        let string_table = parser.u32(8)? as usize;
+        let root = parser.u32(0xc)? as usize;
+        parser.keys = parser.string_table(key_table)?;
+        parser.strings = parser.string_table(string_table)?;
+        if root == 0 {
+            return Ok(Self::Null);
+        }
+        let node_type = parser.u8(root)?;
+        parser.node(root, node_type, root as u32, 0)
+    }
+
+    /// Get the value of the key if the node is a hash
+    pub fn get(&self, key: &str) -> Option<&Byml> {
+        match self {
+            Self::Hash(x) => x.get(key),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Self::String(x) => Some(x),
+            _ => None,
+        }
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            Self::Bool(x) => Some(*x),
+            _ => None,
+        }
+    }
+
+    /// Get the value as i32. U32 is also accepted since some files
+    /// use them interchangeably
+    pub fn as_i32(&self) -> Option<i32> {
+        match self {
+            Self::I32(x) => Some(*x),
+            Self::U32(x) => Some(*x as i32),
+            _ => None,
+        }
+    }
+
+    /// Get the value as f32. I32 is also accepted, since whole numbers
+    /// are sometimes stored as integers
+    pub fn as_f32(&self) -> Option<f32> {
+        match self {
+            Self::F32(x) => Some(*x),
+            Self::I32(x) => Some(*x as f32),
+            _ => None,
+        }
+    }
+
+    pub fn as_array(&self) -> Option<&[Byml]> {
+        match self {
+            Self::Array(x) => Some(x),
+            _ => None,
+        }
+    }
+
+    pub fn as_hash(&self) -> Option<&BTreeMap<String, Byml>> {
+        match self {
+            Self::Hash(x) => Some(x),
+            _ => None,
+        }
+    }
+}
+
+struct Parser<'a> {
+    data: &'a [u8],
+    big_endian: bool,
+    keys: Vec<String>,
+    strings: Vec<String>,
+}
+
+impl Parser<'_> {
+    /// Parse a node with the type and the 4-byte value in the parent
+    fn node(&self, pos: usize, node_type: u8, value: u32, depth: usize) -> Result<Byml, BymlError> {
+        if depth > 64 {
+            return Err(BymlError::TooDeep);
+        }
+        let node = match node_type {
+            0xa0 => {
+                let s = self
+                    .strings
+                    .get(value as usize)
+                    .ok_or(BymlError::BadStringIndex(value))?;
+                Byml::String(s.clone())
+            }
+            0xa1 => {
+                let offset = value as usize;
+                let size = self.u32(offset)? as usize;
+                let bytes = self
+                    .data
+                    .get(offset + 4..offset + 4 + size)
+                    .ok_or(BymlError::OutOfBounds(offset))?;
+                Byml::Binary(bytes.to_vec())
+            }
+            0xc0 => self.array(value as usize, depth)?,
+            0xc1 => self.hash(value as usize, depth)?,
+            0xd0 => Byml::Bool(value != 0),
+            0xd1 => Byml::I32(value as i32),
+            0xd2 => Byml::F32(f32::from_bits(value)),
+            0xd3 => Byml::U32(value),
+            0xd4 => Byml::I64(self.u64(value as usize)? as i64),
+            0xd5 => Byml::U64(self.u64(value as usize)?),
+            0xd6 => Byml::F64(f64::from_bits(self.u64(value as usize)?)),
+            0xff => Byml::Null,
+            x => return Err(BymlError::BadNodeType(pos, x)),
+        };
+        Ok(node)
+    }
+
+    /// Get the number of bytes in the data after the offset
+    fn remaining(&self, offset: usize) -> usize {
+        self.data.len().saturating_sub(offset)
+    }
+
+    fn array(&self, offset: usize, depth: usize) -> Result<Byml, BymlError> {
+        let count = self.container_header(offset, 0xc0)?;
+        let values_start = (offset + 4 + count).next_multiple_of(4);
+        // each element takes at least 5 bytes (type and value), so the count
+        // from the file cannot make us allocate more than the data
+        let mut out = Vec::with_capacity(count.min(self.remaining(offset) / 5));
+        for i in 0..count {
+            let node_type = self.u8(offset + 4 + i)?;
+            let pos = values_start + i * 4;
+            let value = self.u32(pos)?;
+            out.push(self.node(pos, node_type, value, depth + 1)?);
+        }
+        Ok(Byml::Array(out))
+    }
+
+    fn hash(&self, offset: usize, depth: usize) -> Result<Byml, BymlError> {
+        let count = self.container_header(offset, 0xc1)?;
+        let mut out = BTreeMap::new();
+        for i in 0..count {
+            let pos = offset + 4 + i * 8;
+            let key_index = self.u24(pos)?;
+            let node_type = self.u8(pos + 3)?;
+            let value = self.u32(pos + 4)?;
+            let key = self
+                .keys
+                .get(key_index as usize)
+                .ok_or(BymlError::BadStringIndex(key_index))?;
+            out.insert(key.clone(), self.node(pos, node_type, value, depth + 1)?);
+        }
+        Ok(Byml::Hash(out))
+    }
+
+    fn string_table(&self, offset: usize) -> Result<Vec<String>, BymlError> {
+        if offset == 0 {
+            return Ok(Vec::new());
+        }
+        let count = self.container_header(offset, 0xc2)?;
+        // each string takes at least 4 bytes for the offset
+        let mut out = Vec::with_capacity(count.min(self.remaining(offset) / 4));
+        for i in 0..count {
+            let start = offset + self.u32(offset + 4 + i * 4)? as usize;
+            let bytes = self
+                .data
+                .get(start..)
+                .ok_or(BymlError::OutOfBounds(start))?;
+            let len = bytes
+                .iter()
+                .position(|x| *x == 0)
+                .ok_or(BymlError::OutOfBounds(start))?;
+            out.push(String::from_utf8_lossy(&bytes[..len]).into_owned());
+        }
+        Ok(out)
+    }
+
+    /// Check the node type and return the number of entries
+    fn container_header(&self, offset: usize, expected_type: u8) -> Result<usize, BymlError> {
+        let node_type = self.u8(offset)?;
+        if node_type != expected_type {
+            return Err(BymlError::BadNodeType(offset, node_type));
+        }
+        Ok(self.u24(offset + 1)? as usize)
+    }
+
+    fn bytes<const N: usize>(&self, offset: usize) -> Result<[u8; N], BymlError> {
+        let mut bytes: [u8; N] = self
+            .data
+            .get(offset..offset + N)
+            .and_then(|x| x.try_into().ok())
+            .ok_or(BymlError::OutOfBounds(offset))?;
+        if self.big_endian {
+            bytes.reverse();
+        }
+        Ok(bytes)
+    }
+
+    fn u8(&self, offset: usize) -> Result<u8, BymlError> {
+        self.data
+            .get(offset)
+            .copied()
+            .ok_or(BymlError::OutOfBounds(offset))
+    }
+
+    fn u16(&self, offset: usize) -> Result<u16, BymlError> {
+        Ok(u16::from_le_bytes(self.bytes(offset)?))
+    }
+
+    fn u24(&self, offset: usize) -> Result<u32, BymlError> {
+        let [a, b, c] = self.bytes(offset)?;
+        Ok(u32::from_le_bytes([a, b, c, 0]))
+    }
+
+    fn u32(&self, offset: usize) -> Result<u32, BymlError> {
+        Ok(u32::from_le_bytes(self.bytes(offset)?))
+    }
+
+    fn u64(&self, offset: usize) -> Result<u64, BymlError> {
+        Ok(u64::from_le_bytes(self.bytes(offset)?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Build a little endian BYML: { "a": [1, "x"], "b": true }
+    fn test_byml() -> Vec<u8> {
+        let mut data = Vec::new();
+        data.extend_from_slice(b"YB");
+        data.extend_from_slice(&2u16.to_le_bytes());
+        // key table at 0x10, string table at 0x24, root at 0x34
+        data.extend_from_slice(&0x10u32.to_le_bytes());
+        data.extend_from_slice(&0x24u32.to_le_bytes());
+        data.extend_from_slice(&0x34u32.to_le_bytes());
+        // key table: ["a", "b"]
+        data.extend_from_slice(&[0xc2, 2, 0, 0]);
+        data.extend_from_slice(&0x10u32.to_le_bytes());
+        data.extend_from_slice(&0x12u32.to_le_bytes());
+        data.extend_from_slice(&0x14u32.to_le_bytes());
+        data.extend_from_slice(b"a\0b\0");
+        // string table: ["x"]
+        data.extend_from_slice(&[0xc2, 1, 0, 0]);
+        data.extend_from_slice(&0xcu32.to_le_bytes());
+        data.extend_from_slice(&0xeu32.to_le_bytes());
+        data.extend_from_slice(b"x\0\0\0");
+        assert_eq!(data.len(), 0x34);
+        // root hash at 0x34
+        data.extend_from_slice(&[0xc1, 2, 0, 0]);
+        data.extend_from_slice(&[0, 0, 0, 0xc0]);
+        data.extend_from_slice(&0x48u32.to_le_bytes());
+        data.extend_from_slice(&[1, 0, 0, 0xd0]);
+        data.extend_from_slice(&1u32.to_le_bytes());
+        assert_eq!(data.len(), 0x48);
+        // array at 0x48
+        data.extend_from_slice(&[0xc0, 2, 0, 0]);
+        data.extend_from_slice(&[0xd1, 0xa0, 0, 0]);
+        data.extend_from_slice(&1u32.to_le_bytes());
+        data.extend_from_slice(&0u32.to_le_bytes());
+        data
+    }
+
+    #[test]
+    fn test_parse() {
+        let byml = Byml::parse(&test_byml()).unwrap();
+        let a = byml.get("a").and_then(|x| x.as_array()).unwrap();
+        assert_eq!(a, &[Byml::I32(1), Byml::String("x".to_string())]);
+        assert_eq!(byml.get("b").and_then(|x| x.as_bool()), Some(true));
+        assert_eq!(byml.get("c"), None);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(Byml::parse(b"hello"), Err(BymlError::BadMagic));
+        let mut data = test_byml();
+        data[2] = 9;
+        assert_eq!(Byml::parse(&data), Err(BymlError::UnsupportedVersion(9)));
+        let data = test_byml();
+        assert!(matches!(
+            Byml::parse(&data[..0x50]),
+            Err(BymlError::OutOfBounds(_))
+        ));
+        // count larger than the data should not be trusted
+        let mut data = test_byml();
+        data[0x49..0x4c].copy_from_slice(&[0xff, 0xff, 0xff]);
+        assert!(matches!(Byml::parse(&data), Err(BymlError::OutOfBounds(_))));
+    }
+}
@@ -0,0 +1,119 @@
+use std::collections::HashMap;
+
+use crate::program::{Byml, BymlError};
+
+/// Recipes from `CookData.byml`, indexed by the hash of the dish's actor name
+#[derive(Debug, Default, Clone)]
+pub struct CookDataTable {
+    /// Ingredients of the first recipe of each dish that has actor ingredients.
+    /// Each ingredient is a list of actor name hashes that can be used for it
+    recipes: HashMap<u32, Vec<Vec<u32>>>,
+}
+
+impl CookDataTable {
+    /// Parse the table from the decompressed `CookData.byml`
+    pub fn parse(data: &[u8]) -> Result<Self, BymlError> {
+        Ok(Self::from_root(&Byml::parse(data)?))
+    }
+
+    fn from_root(root: &Byml) -> Self {
+        let hash = |x: &Byml| match x {
+            Byml::U32(x) => Some(*x),
+            Byml::I32(x) => Some(*x as u32),
+            _ => None,
+        };
+        let mut recipes = HashMap::new();
+        for key in ["Recipes", "SingleRecipes"] {
+            let entries = root.get(key).and_then(|x| x.as_array()).unwrap_or_default();
+            for entry in entries {
+                let Some(recipe) = entry.get("Recipe").and_then(hash) else {
+                    continue;
+                };
+                // each ingredient is either one actor, or a list of actors
+                // that can be used interchangeably
+                let ingredients: Vec<Vec<u32>> = entry
+                    .get("Actors")
+                    .and_then(|x| x.as_array())
+                    .unwrap_or_default()
+                    .iter()
+                    .map(|x| match x.as_array() {
+                        Some(actors) => actors.iter().filter_map(hash).collect(),
+                        None => hash(x).into_iter().collect(),
+                    })
+                    .filter(|x: &Vec<u32>| !x.is_empty())
+                    .collect();
+                if ingredients.is_empty() {
+                    continue;
+                }
+                recipes.entry(recipe).or_insert(ingredients);
+            }
+        }
+        Self { recipes }
+    }
+
+    /// Get the ingredients of a recipe for the dish. Each ingredient is
+    /// a list of actor name hashes that can be used for it
+    pub fn ingredients(&self, dish_actor: &str) -> Option<&[Vec<u32>]> {
+        self.recipes
+            .get(&crc32fast::hash(dish_actor.as_bytes()))
+            .map(|x| x.as_slice())
+    }
+
+    /// Get the number of dishes with recipes in the table
+    pub fn len(&self) -> usize {
+        self.recipes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.recipes.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::*;
+
+    fn hash(name: &str) -> u32 {
+        crc32fast::hash(name.as_bytes())
+    }
+
+    #[test]
+    fn test_cook_data() {
+        let mut recipe = BTreeMap::new();
+        recipe.insert("Recipe".to_string(), Byml::U32(hash("Item_Cook_A_01")));
+        recipe.insert(
+            "Actors".to_string(),
+            Byml::Array(vec![
+                Byml::U32(hash("Item_Fruit_A")),
+                Byml::Array(vec![
+                    Byml::U32(hash("Item_Fruit_B")),
+                    Byml::U32(hash("Item_Fruit_C")),
+                ]),
+            ]),
+        );
+        let mut tag_recipe = BTreeMap::new();
+        tag_recipe.insert("Recipe".to_string(), Byml::U32(hash("Item_Cook_A_02")));
+        let mut root = BTreeMap::new();
+        root.insert(
+            "Recipes".to_string(),
+            Byml::Array(vec![Byml::Hash(recipe), Byml::Hash(tag_recipe)]),
+        );
+        let table = CookDataTable::from_root(&Byml::Hash(root));
+
+        assert_eq!(table.len(), 1);
+        assert_eq!(
+            table.ingredients("Item_Cook_A_01"),
+            Some(
+                [
+                    vec![hash("Item_Fruit_A")],
+                    vec![hash("Item_Fruit_B"), hash("Item_Fruit_C")]
+                ]
+                .as_slice()
+            )
+        );
+        // recipes with only tags are skipped
+        assert_eq!(table.ingredients("Item_Cook_A_02"), None);
+    }
+}
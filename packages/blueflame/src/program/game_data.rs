@@ -0,0 +1,148 @@
+use std::collections::BTreeMap;
+use std::sync::OnceLock;
+
+use crate::env::DataId;
+use crate::program::{
+    ActorInfoTable, ArchivedProgram, CookDataTable, GdtDefaultTable, ShopDataTable,
+};
+
+/// Game data files embedded in the program image.
+///
+/// The runtime reads the data from here instead of tables compiled into the
+/// simulator, so the same simulator can run images of different game versions.
+/// Data not in the image (e.g. older images) is `None`, and callers should
+/// fall back to the static data
+#[derive(Debug, Default, Clone)]
+pub struct GameData {
+    files: BTreeMap<DataId, Vec<u8>>,
+    /// ActorInfo parsed on first use
+    actor_info: OnceLock<Option<ActorInfoTable>>,
+    /// ShopGameDataInfo parsed on first use
+    shop_data: OnceLock<Option<ShopDataTable>>,
+    /// CookData parsed on first use
+    cook_data: OnceLock<Option<CookDataTable>>,
+    /// Initial flag values in gamedata.sarc parsed on first use
+    gdt_defaults: OnceLock<Option<GdtDefaultTable>>,
+}
+
+impl GameData {
+    /// Copy the game data out of the program image
+    pub fn from_program(program: &ArchivedProgram) -> Self {
+        let mut files = BTreeMap::new();
+        for data in program.data.iter() {
+            let id = DataId::from(data.id);
+            if id == DataId::SymbolMap {
+                // symbols are loaded into SymbolMap separately
+                continue;
+            }
+            files.insert(id, data.bytes.to_vec());
+        }
+        Self {
+            files,
+            actor_info: OnceLock::new(),
+            shop_data: OnceLock::new(),
+            cook_data: OnceLock::new(),
+            gdt_defaults: OnceLock::new(),
+        }
+    }
+
+    /// Add or replace a data file
+    pub fn insert(&mut self, id: DataId, bytes: Vec<u8>) {
+        match id {
+            DataId::ActorInfoByml => self.actor_info = OnceLock::new(),
+            DataId::ShopDataByml => self.shop_data = OnceLock::new(),
+            DataId::CookDataByml => self.cook_data = OnceLock::new(),
+            DataId::GameDataSarc => self.gdt_defaults = OnceLock::new(),
+            DataId::SymbolMap => {}
+        }
+        self.files.insert(id, bytes);
+    }
+
+    /// Get the bytes of the data file, if it's in the image
+    pub fn get(&self, id: DataId) -> Option<&[u8]> {
+        self.files.get(&id).map(|x| x.as_slice())
+    }
+
+    /// Check if the data file is in the image
+    pub fn contains(&self, id: DataId) -> bool {
+        self.files.contains_key(&id)
+    }
+
+    /// Get the data files available
+    pub fn ids(&self) -> impl Iterator<Item = DataId> {
+        self.files.keys().copied()
+    }
+
+    /// Get the parsed ActorInfo, if it's in the image.
+    ///
+    /// The data is parsed on first access. `None` if the data is not in the image,
+    /// or if it fails to parse (the error is logged)
+    pub fn actor_info(&self) -> Option<&ActorInfoTable> {
+        self.actor_info
+            .get_or_init(|| {
+                let bytes = self.get(DataId::ActorInfoByml)?;
+                match ActorInfoTable::parse(bytes) {
+                    Ok(table) => Some(table),
+                    Err(e) => {
+                        cu::error!("failed to parse ActorInfo in the program image: {e}");
+                        None
+                    }
+                }
+            })
+            .as_ref()
+    }
+
+    /// Get the parsed ShopGameDataInfo, if it's in the image.
+    ///
+    /// Parsed on first access like [`actor_info`](Self::actor_info)
+    pub fn shop_data(&self) -> Option<&ShopDataTable> {
+        self.shop_data
+            .get_or_init(|| {
+                let bytes = self.get(DataId::ShopDataByml)?;
+                match ShopDataTable::parse(bytes) {
+                    Ok(table) => Some(table),
+                    Err(e) => {
+                        cu::error!("failed to parse ShopGameDataInfo in the program image: {e}");
+                        None
+                    }
+                }
+            })
+            .as_ref()
+    }
+
+    /// Get the parsed CookData, if it's in the image.
+    ///
+    /// Parsed on first access like [`actor_info`](Self::actor_info)
+    pub fn cook_data(&self) -> Option<&CookDataTable> {
+        self.cook_data
+            .get_or_init(|| {
+                let bytes = self.get(DataId::CookDataByml)?;
+                match CookDataTable::parse(bytes) {
+                    Ok(table) => Some(table),
+                    Err(e) => {
+                        cu::error!("failed to parse CookData in the program image: {e}");
+                        None
+                    }
+                }
+            })
+            .as_ref()
+    }
+
+    /// Get the initial values of the flags in gamedata.sarc, if it's in the image.
+    ///
+    /// Parsed on first access like [`actor_info`](Self::actor_info)
+    pub fn gdt_defaults(&self) -> Option<&GdtDefaultTable> {
+        self.gdt_defaults
+            .get_or_init(|| {
+                let bytes = self.get(DataId::GameDataSarc)?;
+                match GdtDefaultTable::parse(bytes) {
+                    Ok(table) => Some(table),
+                    Err(e) => {
+                        cu::error!("failed to parse gamedata in the program image: {e}");
+                        None
+                    }
+                }
+            })
+            .as_ref()
+    }
+}
@@ -0,0 +1,289 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::program::{Byml, BymlError, Sarc, SarcError};
+
+/// Error reading the GDT defaults
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum GdtDefaultsError {
+    #[error("fail to read gamedata.sarc: {0}")]
+    Sarc(#[from] SarcError),
+    #[error("fail to read {0}: {1}")]
+    Byml(String, BymlError),
+}
+
+/// Initial value of a game data flag
+#[derive(Debug, Clone, PartialEq)]
+pub enum GdtDefaultValue {
+    Bool(bool),
+    S32(i32),
+    F32(f32),
+    /// Value of `str32`, `str64` and `str256` flags
+    String(Arc<str>),
+    Vector2f((f32, f32)),
+    Vector3f((f32, f32, f32)),
+    Vector4f((f32, f32, f32, f32)),
+    BoolArray(Arc<[bool]>),
+    S32Array(Arc<[i32]>),
+    F32Array(Arc<[f32]>),
+    /// Value of `str64[]` and `str256[]` flags
+    StringArray(Arc<[Arc<str>]>),
+    Vector2fArray(Arc<[(f32, f32)]>),
+    Vector3fArray(Arc<[(f32, f32, f32)]>),
+}
+
+/// Key of the flags of each type in a `.bgdata` file, and how to read the initial value
+#[rustfmt::skip]
+static BGDATA_KEYS: &[(&str, fn(&Byml) -> Option<GdtDefaultValue>)] = &[
+    ("bool_data", |x| Some(GdtDefaultValue::Bool(read_bool(x)?))),
+    ("s32_data", |x| Some(GdtDefaultValue::S32(x.as_i32()?))),
+    ("f32_data", |x| Some(GdtDefaultValue::F32(x.as_f32()?))),
+    ("string_data", |x| Some(GdtDefaultValue::String(x.as_str()?.into()))),
+    ("string64_data", |x| Some(GdtDefaultValue::String(x.as_str()?.into()))),
+    ("string256_data", |x| Some(GdtDefaultValue::String(x.as_str()?.into()))),
+    ("vector2f_data", |x| Some(GdtDefaultValue::Vector2f(read_vector2f(x)?))),
+    ("vector3f_data", |x| Some(GdtDefaultValue::Vector3f(read_vector3f(x)?))),
+    ("vector4_data", |x| Some(GdtDefaultValue::Vector4f(read_vector4f(x)?))),
+    ("vector4f_data", |x| Some(GdtDefaultValue::Vector4f(read_vector4f(x)?))),
+    ("bool_array_data", |x| Some(GdtDefaultValue::BoolArray(read_array(x, read_bool)?))),
+    ("s32_array_data", |x| Some(GdtDefaultValue::S32Array(read_array(x, Byml::as_i32)?))),
+    ("f32_array_data", |x| Some(GdtDefaultValue::F32Array(read_array(x, Byml::as_f32)?))),
+    ("string64_array_data", |x| Some(GdtDefaultValue::StringArray(read_array(x, read_string)?))),
+    ("string256_array_data", |x| Some(GdtDefaultValue::StringArray(read_array(x, read_string)?))),
+    ("vector2f_array_data", |x| Some(GdtDefaultValue::Vector2fArray(read_array(x, read_vector2f)?))),
+    ("vector3f_array_data", |x| Some(GdtDefaultValue::Vector3fArray(read_array(x, read_vector3f)?))),
+];
+
+/// Initial values of the game data flags from the `.bgdata` files in `gamedata.sarc`,
+/// indexed by the hash of the flag name
+#[derive(Debug, Default, Clone)]
+pub struct GdtDefaultTable {
+    flags: HashMap<i32, GdtDefaultValue>,
+    /// Number of flags with an initial value that cannot be read
+    skipped: usize,
+}
+
+impl GdtDefaultTable {
+    /// Parse the table from the decompressed `gamedata.sarc`
+    pub fn parse(data: &[u8]) -> Result<Self, GdtDefaultsError> {
+        let sarc = Sarc::parse(data)?;
+        let mut table = Self::default();
+        for (name, bytes) in sarc.files() {
+            if !name.ends_with(".bgdata") {
+                continue;
+            }
+            let root =
+                Byml::parse(bytes).map_err(|e| GdtDefaultsError::Byml(name.to_string(), e))?;
+            table.add_bgdata(&root);
+        }
+        Ok(table)
+    }
+
+    fn add_bgdata(&mut self, root: &Byml) {
+        for (key, read_value) in BGDATA_KEYS {
+            let entries = root.get(key).and_then(|x| x.as_array()).unwrap_or_default();
+            for entry in entries {
+                let Some(hash) = entry.get("HashValue").and_then(|x| x.as_i32()) else {
+                    continue;
+                };
+                match entry.get("InitValue").and_then(read_value) {
+                    Some(value) => {
+                        self.flags.insert(hash, value);
+                    }
+                    None => self.skipped += 1,
+                }
+            }
+        }
+    }
+
+    /// Get the initial value of a flag by the hash of its name
+    pub fn get(&self, hash: i32) -> Option<&GdtDefaultValue> {
+        self.flags.get(&hash)
+    }
+
+    /// Get the number of flags in the table
+    pub fn len(&self) -> usize {
+        self.flags.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.flags.is_empty()
+    }
+
+    /// Get the number of flags skipped because the initial value cannot be read
+    pub fn skipped(&self) -> usize {
+        self.skipped
+    }
+}
+
+fn read_bool(value: &Byml) -> Option<bool> {
+    match value {
+        Byml::Bool(x) => Some(*x),
+        x => x.as_i32().map(|x| x != 0),
+    }
+}
+
+fn read_string(value: &Byml) -> Option<Arc<str>> {
+    value.as_str().map(Arc::from)
+}
+
+/// Read a vector, which is an array of floats. The array can also be
+/// wrapped in another array
+fn read_floats<const N: usize>(value: &Byml) -> Option<[f32; N]> {
+    let mut values = value.as_array()?;
+    if let [inner @ Byml::Array(_)] = values {
+        values = inner.as_array()?;
+    }
+    if values.len() != N {
+        return None;
+    }
+    let mut out = [0f32; N];
+    for (out, value) in out.iter_mut().zip(values) {
+        *out = value.as_f32()?;
+    }
+    Some(out)
+}
+
+fn read_vector2f(value: &Byml) -> Option<(f32, f32)> {
+    let [x, y] = read_floats(value)?;
+    Some((x, y))
+}
+
+fn read_vector3f(value: &Byml) -> Option<(f32, f32, f32)> {
+    let [x, y, z] = read_floats(value)?;
+    Some((x, y, z))
+}
+
+fn read_vector4f(value: &Byml) -> Option<(f32, f32, f32, f32)> {
+    let [x, y, z, w] = read_floats(value)?;
+    Some((x, y, z, w))
+}
+
+/// Read an array flag value. The elements are either in the array directly,
+/// or in the `Values` of the only element of the array
+fn read_array<T>(value: &Byml, read_element: impl Fn(&Byml) -> Option<T>) -> Option<Arc<[T]>> {
+    let mut values = value.as_array()?;
+    if let [inner] = values
+        && let Some(inner) = inner.get("Values")
+    {
+        values = inner.as_array()?;
+    }
+    values.iter().map(read_element).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::*;
+
+    fn flag(name: &str, init: Byml) -> Byml {
+        let mut flag = BTreeMap::new();
+        flag.insert(
+            "HashValue".to_string(),
+            Byml::I32(crc32fast::hash(name.as_bytes()) as i32),
+        );
+        flag.insert("InitValue".to_string(), init);
+        Byml::Hash(flag)
+    }
+
+    #[test]
+    fn test_gdt_defaults() {
+        let mut table = GdtDefaultTable::default();
+        let mut root = BTreeMap::new();
+        root.insert(
+            "bool_data".to_string(),
+            Byml::Array(vec![flag("IsGet_Obj_HeroSoul_Zora", Byml::I32(1))]),
+        );
+        table.add_bgdata(&Byml::Hash(root));
+        let mut root = BTreeMap::new();
+        root.insert(
+            "s32_data".to_string(),
+            Byml::Array(vec![flag("WeaponPorchStockNum", Byml::I32(8))]),
+        );
+        table.add_bgdata(&Byml::Hash(root));
+
+        let hash = |name: &str| crc32fast::hash(name.as_bytes()) as i32;
+        assert_eq!(table.len(), 2);
+        assert_eq!(
+            table.get(hash("IsGet_Obj_HeroSoul_Zora")),
+            Some(&GdtDefaultValue::Bool(true))
+        );
+        assert_eq!(
+            table.get(hash("WeaponPorchStockNum")),
+            Some(&GdtDefaultValue::S32(8))
+        );
+        assert_eq!(table.get(hash("Location_Test")), None);
+    }
+
+    #[test]
+    fn test_gdt_defaults_other_types() {
+        let mut table = GdtDefaultTable::default();
+        let floats = |values: &[f32]| Byml::Array(values.iter().map(|x| Byml::F32(*x)).collect());
+        let mut values = BTreeMap::new();
+        values.insert(
+            "Values".to_string(),
+            Byml::Array(vec![Byml::I32(1), Byml::I32(2)]),
+        );
+        let mut root = BTreeMap::new();
+        root.insert(
+            "f32_data".to_string(),
+            Byml::Array(vec![flag("F32Flag", Byml::F32(1.5))]),
+        );
+        root.insert(
+            "string64_data".to_string(),
+            Byml::Array(vec![
+                flag("StringFlag", Byml::String("Weapon_Sword_070".to_string())),
+                flag("BadStringFlag", Byml::I32(1)),
+            ]),
+        );
+        root.insert(
+            "vector3f_data".to_string(),
+            Byml::Array(vec![
+                flag("Vector3fFlag", Byml::Array(vec![floats(&[1.0, 2.0, 3.0])])),
+                flag("BadVector3fFlag", floats(&[1.0, 2.0])),
+            ]),
+        );
+        root.insert(
+            "s32_array_data".to_string(),
+            Byml::Array(vec![flag(
+                "S32ArrayFlag",
+                Byml::Array(vec![Byml::Hash(values)]),
+            )]),
+        );
+        root.insert(
+            "vector2f_array_data".to_string(),
+            Byml::Array(vec![flag(
+                "Vector2fArrayFlag",
+                Byml::Array(vec![floats(&[1.0, 2.0]), floats(&[3.0, 4.0])]),
+            )]),
+        );
+        table.add_bgdata(&Byml::Hash(root));
+
+        let hash = |name: &str| crc32fast::hash(name.as_bytes()) as i32;
+        assert_eq!(table.len(), 5);
+        assert_eq!(table.skipped(), 2);
+        assert_eq!(table.get(hash("F32Flag")), Some(&GdtDefaultValue::F32(1.5)));
+        assert_eq!(
+            table.get(hash("StringFlag")),
+            Some(&GdtDefaultValue::String("Weapon_Sword_070".into()))
+        );
+        assert_eq!(
+            table.get(hash("Vector3fFlag")),
+            Some(&GdtDefaultValue::Vector3f((1.0, 2.0, 3.0)))
+        );
+        assert_eq!(
+            table.get(hash("S32ArrayFlag")),
+            Some(&GdtDefaultValue::S32Array(Arc::from([1, 2])))
+        );
+        assert_eq!(
+            table.get(hash("Vector2fArrayFlag")),
+            Some(&GdtDefaultValue::Vector2fArray(Arc::from([
+                (1.0, 2.0),
+                (3.0, 4.0)
+            ])))
+        );
+        assert_eq!(table.get(hash("BadStringFlag")), None);
+        assert_eq!(table.get(hash("BadVector3fFlag")), None);
+    }
+}
@@ -1,6 +1,24 @@
+mod actor_info;
+pub use actor_info::*;
 mod builder;
 pub use builder::*;
+mod byml;
+pub use byml::*;
+mod cook_data;
+pub use cook_data::*;
+mod game_data;
+pub use game_data::*;
+mod gdt_defaults;
+pub use gdt_defaults::*;
+mod nx;
+pub use nx::*;
 mod pack;
 pub use pack::*;
+mod sarc;
+pub use sarc::*;
+mod shop_data;
+pub use shop_data::*;
+mod symbols;
+pub use symbols::*;
 mod types;
 pub use types::*;
@@ -0,0 +1,724 @@
+use std::collections::HashMap;
+use std::str::FromStr;
+
+use crate::env::{DlcVer, Environment, GameVer};
+use crate::memory::{PAGE_SIZE, align_down, align_up};
+use crate::program::{BuilderPhase3, builder};
+
+/// Error loading or relocating the game's modules
+#[derive(Debug, thiserror::Error)]
+pub enum LoadError {
+    #[error("module `{0}` is not a valid NSO or ELF file: {1}")]
+    BadModule(String, String),
+    #[error("failed to decompress {1} segment in module `{0}`")]
+    Decompress(String, &'static str),
+    #[error("module `{0}` has unsupported relocation type {1} at 0x{2:08x}")]
+    UnsupportedRelocation(String, u32, u64),
+    #[error("main module cannot be placed at 0x{0:08x}, previous modules end at 0x{1:08x}")]
+    MainOffset(u32, u32),
+    #[error("program is too big: 0x{0:x} bytes")]
+    TooBig(u64),
+    #[error("invalid range `{0}`, expected `[MODULE]:0xSTART-0xEND`")]
+    BadRange(String),
+}
+
+/// Names of the modules in the ExeFS of the game, in the order they are loaded
+pub const MODULE_NAMES: &[&str] = &[
+    "rtld", "main", "subsdk0", "subsdk1", "subsdk2", "subsdk3", "subsdk4", "subsdk5", "subsdk6",
+    "subsdk7", "subsdk8", "subsdk9", "sdk",
+];
+
+/// A module of the game (like `main` or `sdk`), loaded from an NSO or ELF file
+#[derive(Debug, Clone)]
+pub struct NxModule {
+    name: String,
+    /// Memory image of the module, starting at the module base
+    image: Vec<u8>,
+    /// Loaded segments in the module
+    segments: Vec<NxSegment>,
+    /// Offset of the dynamic section in the image
+    dynamic: Option<u32>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct NxSegment {
+    /// Offset of the segment from the module base
+    offset: u32,
+    /// Size of the segment in the file, the rest of the segment
+    /// in memory is zero (e.g. .bss)
+    file_size: u32,
+    /// Permissions (same as [`Section`](crate::program::Section))
+    permissions: u32,
+}
+
+/// A range of a module to include in the image, relative to the module base
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ModuleRange {
+    pub module: String,
+    pub start: u32,
+    pub end: u32,
+}
+
+impl FromStr for ModuleRange {
+    type Err = LoadError;
+
+    /// Parse the range in the format `[MODULE]:0xSTART-0xEND`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let err = || LoadError::BadRange(s.to_string());
+        let (module, range) = s.split_once(':').ok_or_else(err)?;
+        let module = module
+            .strip_prefix('[')
+            .and_then(|x| x.strip_suffix(']'))
+            .ok_or_else(err)?;
+        let (start, end) = range.split_once('-').ok_or_else(err)?;
+        let parse_hex = |x: &str| {
+            let x = x.trim();
+            let x = x.strip_prefix("0x").unwrap_or(x);
+            u32::from_str_radix(x, 16).ok()
+        };
+        let start = parse_hex(start).ok_or_else(err)?;
+        let end = parse_hex(end).ok_or_else(err)?;
+        if start > end {
+            return Err(err());
+        }
+        Ok(Self {
+            module: module.to_string(),
+            start,
+            end,
+        })
+    }
+}
+
+impl NxModule {
+    /// Load the module from the content of an NSO or ELF file
+    pub fn load(name: &str, bytes: &[u8]) -> Result<Self, LoadError> {
+        if bytes.starts_with(b"NSO0") {
+            return Self::load_nso(name, bytes);
+        }
+        if bytes.starts_with(b"\x7fELF") {
+            return Self::load_elf(name, bytes);
+        }
+        Err(LoadError::BadModule(
+            name.to_string(),
+            "unknown file magic".to_string(),
+        ))
+    }
+
+    /// Get the name of the module
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Get the size of the module in memory
+    pub fn size(&self) -> u32 {
+        self.image.len() as u32
+    }
+
+    fn load_nso(name: &str, bytes: &[u8]) -> Result<Self, LoadError> {
+        let bad = |msg: &str| LoadError::BadModule(name.to_string(), msg.to_string());
+        let flags = read_u32(bytes, 0xc).ok_or_else(|| bad("truncated header"))?;
+        let bss_size = read_u32(bytes, 0x3c).ok_or_else(|| bad("truncated header"))?;
+
+        // (name, header offset, compressed size offset, permissions)
+        let segment_headers = [
+            ("text", 0x10, 0x60, 0x5),
+            ("rodata", 0x20, 0x64, 0x4),
+            ("data", 0x30, 0x68, 0x6),
+        ];
+        let mut loaded = Vec::with_capacity(3);
+        let mut image_size = 0;
+        for (i, (segment_name, header, file_size_offset, permissions)) in
+            segment_headers.into_iter().enumerate()
+        {
+            let (Some(file_offset), Some(mem_offset), Some(size), Some(file_size)) = (
+                read_u32(bytes, header),
+                read_u32(bytes, header + 4),
+                read_u32(bytes, header + 8),
+                read_u32(bytes, file_size_offset),
+            ) else {
+                return Err(bad("truncated header"));
+            };
+            let data = bytes
+                .get(file_offset as usize..(file_offset as usize + file_size as usize))
+                .ok_or_else(|| bad("segment out of bounds"))?;
+            let data = if flags & (1 << i) != 0 {
+                lz4_decompress(data, size as usize)
+                    .ok_or_else(|| LoadError::Decompress(name.to_string(), segment_name))?
+            } else {
+                if file_size > size {
+                    return Err(bad("segment is larger than its size in memory"));
+                }
+                data.to_vec()
+            };
+            let mut end = mem_offset as u64 + size as u64;
+            if segment_name == "data" {
+                end += bss_size as u64;
+            }
+            image_size = image_size.max(end);
+            loaded.push((mem_offset, data, permissions));
+        }
+
+        let image_size = align_up!(image_size, PAGE_SIZE as u64);
+        let mut image = vec![0; image_size as usize];
+        let mut segments = Vec::with_capacity(3);
+        for (mem_offset, data, permissions) in loaded {
+            let start = mem_offset as usize;
+            image[start..start + data.len()].copy_from_slice(&data);
+            segments.push(NxSegment {
+                offset: mem_offset,
+                file_size: data.len() as u32,
+                permissions,
+            });
+        }
+
+        // the dynamic section is found through the MOD0 header,
+        // which is pointed to by the second word of .text
+        let dynamic = read_u32(&image, 4).and_then(|mod0| {
+            if image.get(mod0 as usize..mod0 as usize + 4)? != b"MOD0" {
+                return None;
+            }
+            let offset = read_u32(&image, mod0 as usize + 4)? as i32;
+            mod0.checked_add_signed(offset)
+        });
+
+        Ok(Self {
+            name: name.to_string(),
+            image,
+            segments,
+            dynamic,
+        })
+    }
+
+    fn load_elf(name: &str, bytes: &[u8]) -> Result<Self, LoadError> {
+        let bad = |msg: &str| LoadError::BadModule(name.to_string(), msg.to_string());
+        // only 64-bit little endian is supported
+        if bytes.get(4) != Some(&2) || bytes.get(5) != Some(&1) {
+            return Err(bad("not a 64-bit little endian ELF"));
+        }
+        let (Some(phoff), Some(phentsize), Some(phnum)) = (
+            read_u64(bytes, 0x20),
+            read_u16(bytes, 0x36),
+            read_u16(bytes, 0x38),
+        ) else {
+            return Err(bad("truncated header"));
+        };
+
+        const PT_LOAD: u32 = 1;
+        const PT_DYNAMIC: u32 = 2;
+        // (vaddr, data, permissions)
+        let mut loaded = Vec::new();
+        let mut dynamic = None;
+        let mut image_size = 0;
+        for i in 0..phnum as usize {
+            let ph = phoff as usize + i * phentsize as usize;
+            let (Some(p_type), Some(p_flags), Some(offset), Some(vaddr), Some(filesz), Some(memsz)) = (
+                read_u32(bytes, ph),
+                read_u32(bytes, ph + 4),
+                read_u64(bytes, ph + 8),
+                read_u64(bytes, ph + 0x10),
+                read_u64(bytes, ph + 0x20),
+                read_u64(bytes, ph + 0x28),
+            ) else {
+                return Err(bad("truncated program header"));
+            };
+            match p_type {
+                PT_LOAD => {
+                    let end = offset
+                        .checked_add(filesz)
+                        .ok_or_else(|| bad("segment out of bounds"))?;
+                    let data = bytes
+                        .get(offset as usize..end as usize)
+                        .ok_or_else(|| bad("segment out of bounds"))?;
+                    if filesz > memsz {
+                        return Err(bad("segment is larger than its size in memory"));
+                    }
+                    let mem_end = vaddr
+                        .checked_add(memsz)
+                        .ok_or_else(|| bad("module is too big"))?;
+                    image_size = image_size.max(mem_end);
+                    // PF_X, PF_W and PF_R are the same bits as section permissions
+                    loaded.push((vaddr, data, p_flags & 0x7));
+                }
+                PT_DYNAMIC => {
+                    dynamic = u32::try_from(vaddr).ok();
+                }
+                _ => {}
+            }
+        }
+
+        let image_size = align_up!(image_size, PAGE_SIZE as u64);
+        let image_size = u32::try_from(image_size).map_err(|_| bad("module is too big"))?;
+        let mut image = vec![0; image_size as usize];
+        let mut segments = Vec::with_capacity(loaded.len());
+        for (vaddr, data, permissions) in loaded {
+            let start = vaddr as usize;
+            image[start..start + data.len()].copy_from_slice(data);
+            segments.push(NxSegment {
+                offset: vaddr as u32,
+                file_size: data.len() as u32,
+                permissions,
+            });
+        }
+
+        Ok(Self {
+            name: name.to_string(),
+            image,
+            segments,
+            dynamic,
+        })
+    }
+
+    /// Get the defined symbols that can be used by other modules,
+    /// as `(name, offset from module base)`
+    fn exported_symbols(&self) -> Vec<(String, u64)> {
+        let Some(dynamic) = self.dynamic_info() else {
+            return vec![];
+        };
+        let mut symbols = Vec::new();
+        for i in 0..dynamic.symbol_count() {
+            let Some(sym) = dynamic.symbol(&self.image, i) else {
+                break;
+            };
+            // defined global or weak symbols
+            if sym.shndx == 0 || !matches!(sym.bind, 1 | 2) {
+                continue;
+            }
+            if let Some(name) = dynamic.string(&self.image, sym.name) {
+                symbols.push((name, sym.value));
+            }
+        }
+        symbols
+    }
+
+    /// Apply the dynamic relocations, as if the module is loaded at `base`.
+    ///
+    /// Returns the number of symbols that cannot be resolved
+    fn relocate(&mut self, base: u64, exports: &HashMap<String, u64>) -> Result<usize, LoadError> {
+        const R_AARCH64_ABS64: u32 = 257;
+        const R_AARCH64_GLOB_DAT: u32 = 1025;
+        const R_AARCH64_JUMP_SLOT: u32 = 1026;
+        const R_AARCH64_RELATIVE: u32 = 1027;
+
+        let Some(dynamic) = self.dynamic_info() else {
+            return Ok(0);
+        };
+        let mut unresolved = 0;
+        let relocations = [
+            (dynamic.rela, dynamic.rela_size),
+            (dynamic.jmprel, dynamic.jmprel_size),
+        ];
+        for (start, size) in relocations {
+            for i in 0..(size / 24) {
+                let entry = (start + i * 24) as usize;
+                let (Some(offset), Some(info), Some(addend)) = (
+                    read_u64(&self.image, entry),
+                    read_u64(&self.image, entry + 8),
+                    read_u64(&self.image, entry + 16),
+                ) else {
+                    return Err(LoadError::BadModule(
+                        self.name.clone(),
+                        "relocation out of bounds".to_string(),
+                    ));
+                };
+                let r_type = info as u32;
+                let r_sym = (info >> 32) as usize;
+                let value = match r_type {
+                    R_AARCH64_RELATIVE => base.wrapping_add(addend),
+                    R_AARCH64_ABS64 | R_AARCH64_GLOB_DAT | R_AARCH64_JUMP_SLOT => {
+                        let Some(sym) = dynamic.symbol(&self.image, r_sym) else {
+                            return Err(LoadError::BadModule(
+                                self.name.clone(),
+                                "symbol out of bounds".to_string(),
+                            ));
+                        };
+                        let address = if sym.shndx != 0 {
+                            Some(base + sym.value)
+                        } else {
+                            dynamic
+                                .string(&self.image, sym.name)
+                                .and_then(|name| exports.get(&name).copied())
+                        };
+                        match address {
+                            Some(x) => x.wrapping_add(addend),
+                            None => {
+                                // unresolved weak symbols are null
+                                if sym.bind != 2 {
+                                    unresolved += 1;
+                                }
+                                0
+                            }
+                        }
+                    }
+                    _ => {
+                        return Err(LoadError::UnsupportedRelocation(
+                            self.name.clone(),
+                            r_type,
+                            offset,
+                        ));
+                    }
+                };
+                let offset = offset as usize;
+                let Some(target) = self.image.get_mut(offset..offset + 8) else {
+                    return Err(LoadError::BadModule(
+                        self.name.clone(),
+                        "relocation target out of bounds".to_string(),
+                    ));
+                };
+                target.copy_from_slice(&value.to_le_bytes());
+            }
+        }
+        Ok(unresolved)
+    }
+
+    fn dynamic_info(&self) -> Option<DynamicInfo> {
+        const DT_NULL: u64 = 0;
+        const DT_PLTRELSZ: u64 = 2;
+        const DT_STRTAB: u64 = 5;
+        const DT_SYMTAB: u64 = 6;
+        const DT_RELA: u64 = 7;
+        const DT_RELASZ: u64 = 8;
+        const DT_STRSZ: u64 = 10;
+        const DT_JMPREL: u64 = 23;
+
+        let mut info = DynamicInfo::default();
+        let mut entry = self.dynamic? as usize;
+        loop {
+            let tag = read_u64(&self.image, entry)?;
+            let value = read_u64(&self.image, entry + 8)?;
+            match tag {
+                DT_NULL => break,
+                DT_PLTRELSZ => info.jmprel_size = value,
+                DT_STRTAB => info.strtab = value,
+                DT_SYMTAB => info.symtab = value,
+                DT_RELA => info.rela = value,
+                DT_RELASZ => info.rela_size = value,
+                DT_STRSZ => info.strtab_size = value,
+                DT_JMPREL => info.jmprel = value,
+                _ => {}
+            }
+            entry += 16;
+        }
+        Some(info)
+    }
+}
+
+#[derive(Debug, Default)]
+struct DynamicInfo {
+    strtab: u64,
+    strtab_size: u64,
+    symtab: u64,
+    rela: u64,
+    rela_size: u64,
+    jmprel: u64,
+    jmprel_size: u64,
+}
+
+struct Symbol {
+    name: u32,
+    bind: u8,
+    shndx: u16,
+    value: u64,
+}
+
+impl DynamicInfo {
+    /// Number of symbols in the symbol table. The count is not in the dynamic section,
+    /// but the string table is always right after the symbol table
+    fn symbol_count(&self) -> usize {
+        (self.strtab.saturating_sub(self.symtab) / 24) as usize
+    }
+
+    fn symbol(&self, image: &[u8], i: usize) -> Option<Symbol> {
+        let entry = self.symtab as usize + i * 24;
+        Some(Symbol {
+            name: read_u32(image, entry)?,
+            bind: *image.get(entry + 4)? >> 4,
+            shndx: read_u16(image, entry + 6)?,
+            value: read_u64(image, entry + 8)?,
+        })
+    }
+
+    fn string(&self, image: &[u8], offset: u32) -> Option<String> {
+        if offset as u64 >= self.strtab_size {
+            return None;
+        }
+        let start = (self.strtab + offset as u64) as usize;
+        let bytes = image.get(start..)?;
+        let len = bytes.iter().position(|x| *x == 0)?;
+        Some(String::from_utf8_lossy(&bytes[..len]).into_owned())
+    }
+}
+
+/// Lay out, relocate and add the modules to a program builder.
+///
+/// The modules are placed one after another in the order given, except
+/// the `main` module is placed at the main offset of the environment.
+/// If `ranges` is not empty, only the pages in the ranges are included in the image,
+/// which can be used to make a smaller image with only the code and data needed.
+///
+/// Returns the builder for adding data
+pub fn build_from_modules(
+    ver: GameVer,
+    program_start: u64,
+    mut modules: Vec<NxModule>,
+    ranges: &[ModuleRange],
+) -> Result<BuilderPhase3, LoadError> {
+    let main_offset = Environment::new(ver, DlcVer::None).main_offset();
+
+    // layout the modules
+    let mut rel_starts = Vec::with_capacity(modules.len());
+    let mut end = 0u64;
+    for module in &modules {
+        let mut start = end;
+        if module.name == "main" {
+            if start > main_offset as u64 {
+                return Err(LoadError::MainOffset(main_offset, start as u32));
+            }
+            start = main_offset as u64;
+        }
+        end = align_up!(start + module.size() as u64, PAGE_SIZE as u64);
+        if end > u32::MAX as u64 {
+            return Err(LoadError::TooBig(end));
+        }
+        rel_starts.push(start as u32);
+    }
+    let program_size = end as u32;
+
+    // resolve symbols across modules, the first definition is used
+    let mut exports = HashMap::new();
+    for (module, rel_start) in modules.iter().zip(&rel_starts) {
+        let base = program_start + *rel_start as u64;
+        for (name, value) in module.exported_symbols() {
+            exports.entry(name).or_insert(base + value);
+        }
+    }
+    for (module, rel_start) in modules.iter_mut().zip(&rel_starts) {
+        let base = program_start + *rel_start as u64;
+        let unresolved = module.relocate(base, &exports)?;
+        if unresolved > 0 {
+            cu::warn!(
+                "{unresolved} symbols cannot be resolved in module `{}`",
+                module.name
+            );
+        }
+    }
+
+    let mut builder = builder(ver, program_start, program_size);
+    for (module, rel_start) in modules.iter().zip(&rel_starts) {
+        builder = builder.add_module(&module.name, *rel_start);
+    }
+    let mut builder = builder.done_with_modules();
+    for (module, rel_start) in modules.iter().zip(&rel_starts) {
+        for segment in &module.segments {
+            builder = builder.add_section(rel_start + segment.offset, segment.permissions);
+        }
+    }
+    let mut builder = builder.done_with_sections();
+
+    for (module, rel_start) in modules.iter().zip(&rel_starts) {
+        let module_ranges = merge_ranges(ranges, &module.name);
+        for segment in &module.segments {
+            let seg_start = segment.offset;
+            let seg_end = align_up!(seg_start + segment.file_size, PAGE_SIZE);
+            let included = if ranges.is_empty() {
+                vec![(seg_start, seg_end)]
+            } else {
+                module_ranges
+                    .iter()
+                    .filter_map(|(start, end)| {
+                        let start = align_down!(*start, PAGE_SIZE).max(seg_start);
+                        let end = align_up!(*end, PAGE_SIZE).min(seg_end);
+                        (start < end).then_some((start, end))
+                    })
+                    .collect()
+            };
+            for (start, end) in included {
+                let mut data = vec![0; (end - start) as usize];
+                let copy_end = (end as usize).min(module.image.len());
+                data[..copy_end - start as usize]
+                    .copy_from_slice(&module.image[start as usize..copy_end]);
+                builder = builder.add_segment(rel_start + start, data);
+            }
+        }
+    }
+
+    Ok(builder)
+}
+
+/// Get the sorted and merged ranges of the module
+fn merge_ranges(ranges: &[ModuleRange], module: &str) -> Vec<(u32, u32)> {
+    let mut module_ranges = ranges
+        .iter()
+        .filter(|x| x.module == module)
+        .map(|x| (align_down!(x.start, PAGE_SIZE), align_up!(x.end, PAGE_SIZE)))
+        .collect::<Vec<_>>();
+    module_ranges.sort();
+    let mut merged: Vec<(u32, u32)> = Vec::with_capacity(module_ranges.len());
+    for (start, end) in module_ranges {
+        match merged.last_mut() {
+            Some(last) if start <= last.1 => last.1 = last.1.max(end),
+            _ => merged.push((start, end)),
+        }
+    }
+    merged
+}
+
+/// Decompress an LZ4 block (used by NSO segments)
+fn lz4_decompress(input: &[u8], size: usize) -> Option<Vec<u8>> {
+    let mut out = Vec::with_capacity(size);
+    let mut i = 0;
+    let read_len = |i: &mut usize, mut len: usize| {
+        if len != 15 {
+            return Some(len);
+        }
+        loop {
+            let x = *input.get(*i)?;
+            *i += 1;
+            len += x as usize;
+            if x != 255 {
+                return Some(len);
+            }
+        }
+    };
+    while i < input.len() {
+        let token = input[i];
+        i += 1;
+        let literal_len = read_len(&mut i, (token >> 4) as usize)?;
+        out.extend_from_slice(input.get(i..i + literal_len)?);
+        i += literal_len;
+        if i >= input.len() {
+            // the last sequence only has literals
+            break;
+        }
+        let offset = read_u16(input, i)? as usize;
+        i += 2;
+        let match_len = read_len(&mut i, (token & 0xf) as usize)? + 4;
+        if offset == 0 || offset > out.len() {
+            return None;
+        }
+        // the match can overlap with the output being written
+        let start = out.len() - offset;
+        for j in 0..match_len {
+            out.push(out[start + j]);
+        }
+    }
+    (out.len() == size).then_some(out)
+}
+
+/// Decompress Yaz0 data (e.g. `.sbyml` files in the romfs).
+///
+/// Returns the data as-is if it's not compressed, or `None` if the data is corrupted
+pub fn decompress_yaz0(input: &[u8]) -> Option<Vec<u8>> {
+    if !input.starts_with(b"Yaz0") {
+        return Some(input.to_vec());
+    }
+    let size = u32::from_be_bytes(input.get(4..8)?.try_into().ok()?) as usize;
+    let mut out = Vec::with_capacity(size);
+    let mut i = 0x10;
+    'outer: while out.len() < size {
+        let header = *input.get(i)?;
+        i += 1;
+        for bit in (0..8).rev() {
+            if out.len() >= size {
+                break 'outer;
+            }
+            if header & (1 << bit) != 0 {
+                out.push(*input.get(i)?);
+                i += 1;
+                continue;
+            }
+            let b1 = *input.get(i)? as usize;
+            let b2 = *input.get(i + 1)? as usize;
+            i += 2;
+            let distance = (((b1 & 0xf) << 8) | b2) + 1;
+            let len = match b1 >> 4 {
+                0 => {
+                    let n = *input.get(i)? as usize;
+                    i += 1;
+                    n + 0x12
+                }
+                n => n + 2,
+            };
+            if distance > out.len() {
+                return None;
+            }
+            let start = out.len() - distance;
+            for j in 0..len {
+                out.push(out[start + j]);
+            }
+        }
+    }
+    out.truncate(size);
+    Some(out)
+}
+
+fn read_u16(bytes: &[u8], offset: usize) -> Option<u16> {
+    Some(u16::from_le_bytes(
+        bytes.get(offset..offset + 2)?.try_into().ok()?,
+    ))
+}
+
+fn read_u32(bytes: &[u8], offset: usize) -> Option<u32> {
+    Some(u32::from_le_bytes(
+        bytes.get(offset..offset + 4)?.try_into().ok()?,
+    ))
+}
+
+fn read_u64(bytes: &[u8], offset: usize) -> Option<u64> {
+    Some(u64::from_le_bytes(
+        bytes.get(offset..offset + 8)?.try_into().ok()?,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lz4_decompress() {
+        // "abcabcabcabcX": literals "abc", match offset 3 len 9, then literal "X"
+        let input = [0x35, b'a', b'b', b'c', 0x03, 0x00, 0x10, b'X'];
+        let out = lz4_decompress(&input, 13).unwrap();
+        assert_eq!(out, b"abcabcabcabcX");
+        assert_eq!(lz4_decompress(&input, 12), None);
+    }
+
+    #[test]
+    fn test_decompress_yaz0() {
+        // "abcabcabcX": literals "abc", back reference distance 3 len 6, then literal "X"
+        let mut input = b"Yaz0\0\0\0\x0a\0\0\0\0\0\0\0\0".to_vec();
+        input.extend_from_slice(&[0b1110_1000, b'a', b'b', b'c', 0x40, 0x02, b'X']);
+        assert_eq!(decompress_yaz0(&input).unwrap(), b"abcabcabcX");
+        assert_eq!(decompress_yaz0(b"BY").unwrap(), b"BY");
+    }
+
+    #[test]
+    fn test_parse_range() {
+        let range: ModuleRange = "[main]:0x00001000-0x00003000".parse().unwrap();
+        assert_eq!(
+            range,
+            ModuleRange {
+                module: "main".to_string(),
+                start: 0x1000,
+                end: 0x3000
+            }
+        );
+        assert!("main:0x0-0x1000".parse::<ModuleRange>().is_err());
+        assert!("[main]:0x2000-0x1000".parse::<ModuleRange>().is_err());
+    }
+
+    #[test]
+    fn test_merge_ranges() {
+        let ranges = [
+            "[main]:0x3000-0x4000",
+            "[main]:0x1000-0x2800",
+            "[sdk]:0x0-0x1000",
+            "[main]:0x2000-0x3000",
+            "[main]:0x8000-0x9000",
+        ]
+        .map(|x| x.parse::<ModuleRange>().unwrap());
+        assert_eq!(
+            merge_ranges(&ranges, "main"),
+            vec![(0x1000, 0x4000), (0x8000, 0x9000)]
+        );
+    }
+}
@@ -1,11 +1,19 @@
 use std::io::{Read, Write};
 
-use flate2::Compression;
 use flate2::write::GzEncoder;
 use rkyv::rancor;
+use sha2::{Digest, Sha256};
 
+use crate::env::GameVer;
 use crate::program::{ArchivedProgram, Program};
 
+/// Magic at the start of a Blueflame image
+pub const IMAGE_MAGIC: &[u8; 8] = b"BFIMAGE\0";
+/// Current version of the image container format
+pub const IMAGE_FORMAT_VERSION: u32 = 1;
+/// Size of the fixed part of the header, before the build metadata
+const HEADER_SIZE: usize = 0x48;
+
 /// Errors packing or unpacking programs
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
@@ -18,21 +26,114 @@ pub enum Error {
     Decompress(String),
     #[error("fail to deserialize the program: {0}")]
     Deserialize(String),
+    #[error("not a Blueflame image")]
+    BadMagic,
+    #[error("image format version {0} is not supported (supported version is {1})")]
+    UnsupportedFormatVersion(u32, u32),
+    #[error("unknown compression method in image: {0}")]
+    UnknownCompression(u32),
+    #[error("unknown game version in image: {0}")]
+    UnknownGameVer(u32),
+    #[error("image is truncated: expected {0} bytes, got {1} bytes")]
+    Truncated(u64, u64),
+    #[error("image checksum mismatch: expected {0}, got {1}")]
+    ChecksumMismatch(String, String),
+    #[error("image is built with an incompatible program format: {0}")]
+    IncompatibleFormat(String),
+}
+
+/// Compression of the program data in the image
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[repr(u32)]
+pub enum ImageCompression {
+    None = 0,
+    Gzip = 1,
+    #[default]
+    Zstd = 2,
+}
+
+/// Header of a Blueflame image, which can be read without unpacking the image
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImageHeader {
+    /// Version of the image container format
+    pub format_version: u32,
+    /// Compression of the program data
+    pub compression: ImageCompression,
+    /// SHA-256 of the uncompressed program data, which identifies the image
+    pub hash: [u8; 32],
+    /// Size of the program data in the image (compressed)
+    pub data_size: u64,
+    /// Size of the program data after decompression
+    pub uncompressed_size: u64,
+    /// Game version of the program
+    pub ver: GameVer,
+    /// Free-form metadata about how the image is built
+    pub metadata: String,
+}
+
+impl ImageHeader {
+    /// Get the hash as a lowercase hex string
+    pub fn hash_hex(&self) -> String {
+        hex_string(&self.hash)
+    }
+}
+
+/// Options for packing an image
+#[derive(Debug, Default, Clone)]
+pub struct PackOptions {
+    pub compression: ImageCompression,
+    /// Free-form metadata about how the image is built
+    pub metadata: String,
 }
 
 /// Pack the program into a Blueflame image
 pub fn pack(program: &Program) -> Result<Vec<u8>, Error> {
+    pack_with_options(program, &PackOptions::default())
+}
+
+/// Pack the program into a Blueflame image with options
+pub fn pack_with_options(program: &Program, options: &PackOptions) -> Result<Vec<u8>, Error> {
     let data =
         rkyv::to_bytes::<rancor::Error>(program).map_err(|e| Error::Serialize(e.to_string()))?;
+    let hash: [u8; 32] = Sha256::digest(&data).into();
+
+    let compressed = match options.compression {
+        ImageCompression::None => data.to_vec(),
+        ImageCompression::Gzip => {
+            let mut encoder = GzEncoder::new(Vec::new(), flate2::Compression::default());
+            encoder
+                .write_all(&data)
+                .map_err(|e| Error::Compress(e.to_string()))?;
+            encoder
+                .finish()
+                .map_err(|e| Error::Compress(e.to_string()))?
+        }
+        ImageCompression::Zstd => ruzstd::encoding::compress_to_vec(
+            &data[..],
+            ruzstd::encoding::CompressionLevel::Fastest,
+        ),
+    };
 
-    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
-    encoder
-        .write_all(&data)
-        .map_err(|e| Error::Compress(e.to_string()))?;
-    let data = encoder
-        .finish()
-        .map_err(|e| Error::Compress(e.to_string()))?;
-    Ok(data)
+    let metadata = options.metadata.as_bytes();
+    let mut out = Vec::with_capacity(HEADER_SIZE + metadata.len() + compressed.len());
+    out.extend_from_slice(IMAGE_MAGIC);
+    out.extend_from_slice(&IMAGE_FORMAT_VERSION.to_le_bytes());
+    out.extend_from_slice(&(options.compression as u32).to_le_bytes());
+    out.extend_from_slice(&hash);
+    out.extend_from_slice(&(compressed.len() as u64).to_le_bytes());
+    out.extend_from_slice(&(data.len() as u64).to_le_bytes());
+    out.extend_from_slice(&(program.ver as u32).to_le_bytes());
+    out.extend_from_slice(&(metadata.len() as u32).to_le_bytes());
+    out.extend_from_slice(metadata);
+    out.extend_from_slice(&compressed);
+    Ok(out)
+}
+
+/// Read the header of a Blueflame image.
+///
+/// Returns `None` if the image is in the legacy format (gzip without header)
+pub fn read_header(data: &[u8]) -> Result<Option<ImageHeader>, Error> {
+    read_header_internal(data).map(|x| x.map(|(header, _)| header))
 }
 
 /// Unpack a Blueflame image into a program
@@ -43,15 +144,213 @@ pub fn unpack(data: &[u8]) -> Result<Program, Error> {
         .map_err(|e| Error::Deserialize(e.to_string()))
 }
 
-/// Unpack a Blueflame image into a program with zero-copy deserialization
+/// Unpack a Blueflame image into a program with zero-copy deserialization.
+///
+/// The checksum in the header is verified after decompressing
 pub fn unpack_zc<'a>(data: &[u8], out: &'a mut Vec<u8>) -> Result<&'a ArchivedProgram, Error> {
-    let mut decoder = flate2::read::GzDecoder::new(data);
-    decoder
-        .read_to_end(out)
-        .map_err(|e| Error::Decompress(e.to_string()))?;
+    match read_header_internal(data)? {
+        None => {
+            // legacy images are gzip-compressed program without header
+            let mut decoder = flate2::read::GzDecoder::new(data);
+            decoder
+                .read_to_end(out)
+                .map_err(|e| Error::Decompress(e.to_string()))?;
+        }
+        Some((header, payload)) => {
+            // read at most 1 byte more than the expected size, so a bad header
+            // or corrupted data is detected without decompressing everything
+            let limit = header.uncompressed_size.saturating_add(1);
+            match header.compression {
+                ImageCompression::None => out.extend_from_slice(payload),
+                ImageCompression::Gzip => {
+                    let decoder = flate2::read::GzDecoder::new(payload);
+                    decoder
+                        .take(limit)
+                        .read_to_end(out)
+                        .map_err(|e| Error::Decompress(e.to_string()))?;
+                }
+                ImageCompression::Zstd => {
+                    let decoder = ruzstd::decoding::StreamingDecoder::new(payload)
+                        .map_err(|e| Error::Decompress(e.to_string()))?;
+                    decoder
+                        .take(limit)
+                        .read_to_end(out)
+                        .map_err(|e| Error::Decompress(e.to_string()))?;
+                }
+            }
+            if out.len() as u64 != header.uncompressed_size {
+                return Err(Error::Decompress(format!(
+                    "expected {} bytes after decompression, got {} bytes",
+                    header.uncompressed_size,
+                    out.len()
+                )));
+            }
+            let hash: [u8; 32] = Sha256::digest(&out[..]).into();
+            if hash != header.hash {
+                return Err(Error::ChecksumMismatch(
+                    header.hash_hex(),
+                    hex_string(&hash),
+                ));
+            }
+        }
+    }
 
     let program = rkyv::access::<ArchivedProgram, rancor::Error>(&out[..])
-        .map_err(|e| Error::Deserialize(e.to_string()))?;
+        .map_err(|e| Error::IncompatibleFormat(e.to_string()))?;
 
     Ok(program)
 }
+
+/// Read the header and get the compressed program data
+fn read_header_internal(data: &[u8]) -> Result<Option<(ImageHeader, &[u8])>, Error> {
+    if data.starts_with(&[0x1f, 0x8b]) {
+        return Ok(None);
+    }
+    if !data.starts_with(IMAGE_MAGIC) {
+        return Err(Error::BadMagic);
+    }
+    if data.len() < HEADER_SIZE {
+        return Err(Error::Truncated(HEADER_SIZE as u64, data.len() as u64));
+    }
+    let read_u32 = |offset: usize| {
+        let bytes = data
+            .get(offset..offset + 4)
+            .and_then(|x| x.try_into().ok())
+            .unwrap_or_default();
+        u32::from_le_bytes(bytes)
+    };
+    let read_u64 = |offset: usize| {
+        let bytes = data
+            .get(offset..offset + 8)
+            .and_then(|x| x.try_into().ok())
+            .unwrap_or_default();
+        u64::from_le_bytes(bytes)
+    };
+
+    let format_version = read_u32(0x8);
+    if format_version != IMAGE_FORMAT_VERSION {
+        return Err(Error::UnsupportedFormatVersion(
+            format_version,
+            IMAGE_FORMAT_VERSION,
+        ));
+    }
+    let compression = match read_u32(0xc) {
+        0 => ImageCompression::None,
+        1 => ImageCompression::Gzip,
+        2 => ImageCompression::Zstd,
+        x => return Err(Error::UnknownCompression(x)),
+    };
+    let mut hash = [0; 32];
+    hash.copy_from_slice(&data[0x10..0x30]);
+    let data_size = read_u64(0x30);
+    let uncompressed_size = read_u64(0x38);
+    let ver = match read_u32(0x40) {
+        0 => GameVer::X150,
+        1 => GameVer::X160,
+        x => return Err(Error::UnknownGameVer(x)),
+    };
+    let metadata_size = read_u32(0x44) as u64;
+
+    let metadata_end = HEADER_SIZE as u64 + metadata_size;
+    let expected_size = metadata_end.checked_add(data_size).unwrap_or(u64::MAX);
+    let truncated = || Error::Truncated(expected_size, data.len() as u64);
+    let payload_end = usize::try_from(expected_size).map_err(|_| truncated())?;
+    let metadata_end = metadata_end as usize;
+    let metadata = data.get(HEADER_SIZE..metadata_end).ok_or_else(truncated)?;
+    let metadata = String::from_utf8_lossy(metadata).into_owned();
+    let payload = data.get(metadata_end..payload_end).ok_or_else(truncated)?;
+
+    let header = ImageHeader {
+        format_version,
+        compression,
+        hash,
+        data_size,
+        uncompressed_size,
+        ver,
+        metadata,
+    };
+    Ok(Some((header, payload)))
+}
+
+fn hex_string(bytes: &[u8]) -> String {
+    bytes.iter().map(|x| format!("{x:02x}")).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::program::builder;
+
+    fn test_program() -> Program {
+        builder(GameVer::X150, 0x1000000, 0x2000)
+            .add_module("main", 0)
+            .done_with_modules()
+            .add_section(0, 0x5)
+            .done_with_sections()
+            .add_segment(0, vec![0x1f; 0x1000])
+            .done()
+    }
+
+    #[test]
+    fn test_pack_roundtrip() {
+        let program = test_program();
+        for compression in [
+            ImageCompression::None,
+            ImageCompression::Gzip,
+            ImageCompression::Zstd,
+        ] {
+            let options = PackOptions {
+                compression,
+                metadata: "test".to_string(),
+            };
+            let data = pack_with_options(&program, &options).unwrap();
+            let header = read_header(&data).unwrap().unwrap();
+            assert_eq!(header.compression, compression);
+            assert_eq!(header.ver, GameVer::X150);
+            assert_eq!(header.metadata, "test");
+            let unpacked = unpack(&data).unwrap();
+            assert_eq!(unpacked.program_start, 0x1000000);
+        }
+    }
+
+    #[test]
+    fn test_unpack_errors() {
+        let data = pack(&test_program()).unwrap();
+
+        let truncated = &data[..data.len() - 1];
+        assert!(matches!(
+            unpack(truncated),
+            Err(Error::Truncated(x, y)) if x == data.len() as u64 && y == x - 1
+        ));
+
+        let mut bad_version = data.clone();
+        bad_version[8] = 99;
+        assert!(matches!(
+            unpack(&bad_version),
+            Err(Error::UnsupportedFormatVersion(99, IMAGE_FORMAT_VERSION))
+        ));
+
+        let mut bad_hash = data.clone();
+        bad_hash[0x10] ^= 0xff;
+        assert!(matches!(
+            unpack(&bad_hash),
+            Err(Error::ChecksumMismatch(_, _))
+        ));
+
+        let mut bad_data_size = data.clone();
+        bad_data_size[0x30..0x38].copy_from_slice(&u64::MAX.to_le_bytes());
+        assert!(matches!(
+            unpack(&bad_data_size),
+            Err(Error::Truncated(u64::MAX, _))
+        ));
+
+        let mut bad_uncompressed_size = data.clone();
+        bad_uncompressed_size[0x38..0x40].copy_from_slice(&1u64.to_le_bytes());
+        assert!(matches!(
+            unpack(&bad_uncompressed_size),
+            Err(Error::Decompress(_))
+        ));
+
+        assert!(matches!(unpack(b"hello"), Err(Error::BadMagic)));
+    }
+}
@@ -0,0 +1,183 @@
+/// Error reading SARC archives
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum SarcError {
+    #[error("not a SARC archive")]
+    BadMagic,
+    #[error("only little endian SARC archives are supported")]
+    BigEndian,
+    #[error("SARC data is out of bounds at 0x{0:x}")]
+    OutOfBounds(usize),
+}
+
+/// A SARC archive (e.g. `Pack/Bootup.pack` or the decompressed `gamedata.ssarc`)
+#[derive(Debug, Clone)]
+pub struct Sarc<'a> {
+    data: &'a [u8],
+    /// (name, start, end) of the files, with offsets from the start of the archive
+    files: Vec<(Option<String>, usize, usize)>,
+}
+
+impl<'a> Sarc<'a> {
+    /// Parse a SARC archive. Yaz0-compressed data should be decompressed first
+    pub fn parse(data: &'a [u8]) -> Result<Self, SarcError> {
+        use SarcError::*;
+        if data.get(0..4) != Some(&b"SARC"[..]) {
+            return Err(BadMagic);
+        }
+        if data.get(6..8) != Some(&[0xff, 0xfe][..]) {
+            return Err(BigEndian);
+        }
+        let header_size = read_u16(data, 4)? as usize;
+        let data_start = read_u32(data, 0xc)? as usize;
+
+        let sfat = header_size;
+        if data.get(sfat..sfat + 4) != Some(&b"SFAT"[..]) {
+            return Err(BadMagic);
+        }
+        let sfat_header_size = read_u16(data, sfat + 4)? as usize;
+        let node_count = read_u16(data, sfat + 6)? as usize;
+        let nodes = sfat + sfat_header_size;
+        let sfnt = nodes + node_count * 0x10;
+        if data.get(sfnt..sfnt + 4) != Some(&b"SFNT"[..]) {
+            return Err(BadMagic);
+        }
+        let names = sfnt + read_u16(data, sfnt + 4)? as usize;
+
+        let mut files = Vec::with_capacity(node_count);
+        for i in 0..node_count {
+            let node = nodes + i * 0x10;
+            let attributes = read_u32(data, node + 4)?;
+            let start = data_start + read_u32(data, node + 8)? as usize;
+            let end = data_start + read_u32(data, node + 0xc)? as usize;
+            if start > end || end > data.len() {
+                return Err(OutOfBounds(node));
+            }
+            // the high byte is set if the file has a name,
+            // and the lower bits are the offset of the name in 4-byte units
+            let name = if attributes & 0xff00_0000 != 0 {
+                let name_start = names + (attributes & 0xffff) as usize * 4;
+                let name = data.get(name_start..).ok_or(OutOfBounds(name_start))?;
+                let len = name.iter().position(|x| *x == 0).unwrap_or(name.len());
+                Some(String::from_utf8_lossy(&name[..len]).into_owned())
+            } else {
+                None
+            };
+            files.push((name, start, end));
+        }
+
+        Ok(Self { data, files })
+    }
+
+    /// Get the data of a file by its name
+    pub fn get(&self, name: &str) -> Option<&'a [u8]> {
+        let (_, start, end) = self
+            .files
+            .iter()
+            .find(|(x, _, _)| x.as_deref() == Some(name))?;
+        self.data.get(*start..*end)
+    }
+
+    /// Iterate the files as `(name, data)`. Files without names are skipped
+    pub fn files(&self) -> impl Iterator<Item = (&str, &'a [u8])> {
+        let data = self.data;
+        self.files
+            .iter()
+            .filter_map(move |(name, start, end)| Some((name.as_deref()?, data.get(*start..*end)?)))
+    }
+
+    /// Get the number of files in the archive
+    pub fn len(&self) -> usize {
+        self.files.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+}
+
+fn read_u16(data: &[u8], offset: usize) -> Result<u16, SarcError> {
+    let bytes = data
+        .get(offset..offset + 2)
+        .ok_or(SarcError::OutOfBounds(offset))?;
+    Ok(u16::from_le_bytes([bytes[0], bytes[1]]))
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Result<u32, SarcError> {
+    let bytes = data
+        .get(offset..offset + 4)
+        .ok_or(SarcError::OutOfBounds(offset))?;
+    Ok(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Build a SARC archive with the files, names are stored in order
+    fn build(files: &[(&str, &[u8])]) -> Vec<u8> {
+        let mut names = Vec::new();
+        let mut nodes = Vec::new();
+        let mut file_data = Vec::new();
+        for (name, data) in files {
+            let attributes = 0x0100_0000 | (names.len() / 4) as u32;
+            names.extend_from_slice(name.as_bytes());
+            names.push(0);
+            while names.len() % 4 != 0 {
+                names.push(0);
+            }
+            nodes.extend_from_slice(&0u32.to_le_bytes());
+            nodes.extend_from_slice(&attributes.to_le_bytes());
+            nodes.extend_from_slice(&(file_data.len() as u32).to_le_bytes());
+            file_data.extend_from_slice(data);
+            nodes.extend_from_slice(&(file_data.len() as u32).to_le_bytes());
+        }
+        let data_start = 0x14 + 0xc + nodes.len() + 8 + names.len();
+
+        let mut out = Vec::new();
+        out.extend_from_slice(b"SARC");
+        out.extend_from_slice(&0x14u16.to_le_bytes());
+        out.extend_from_slice(&[0xff, 0xfe]);
+        out.extend_from_slice(&((data_start + file_data.len()) as u32).to_le_bytes());
+        out.extend_from_slice(&(data_start as u32).to_le_bytes());
+        out.extend_from_slice(&[0, 1, 0, 0]);
+        out.extend_from_slice(b"SFAT");
+        out.extend_from_slice(&0xcu16.to_le_bytes());
+        out.extend_from_slice(&(files.len() as u16).to_le_bytes());
+        out.extend_from_slice(&0x65u32.to_le_bytes());
+        out.extend_from_slice(&nodes);
+        out.extend_from_slice(b"SFNT");
+        out.extend_from_slice(&8u16.to_le_bytes());
+        out.extend_from_slice(&[0, 0]);
+        out.extend_from_slice(&names);
+        out.extend_from_slice(&file_data);
+        out
+    }
+
+    #[test]
+    fn test_sarc() {
+        let data = build(&[
+            ("GameData/gamedata.ssarc", b"hello"),
+            ("Actor/test.bxml", b"world!"),
+        ]);
+        let sarc = Sarc::parse(&data).unwrap();
+        assert_eq!(sarc.len(), 2);
+        assert_eq!(sarc.get("GameData/gamedata.ssarc"), Some(&b"hello"[..]));
+        assert_eq!(sarc.get("Actor/test.bxml"), Some(&b"world!"[..]));
+        assert_eq!(sarc.get("Actor/none.bxml"), None);
+        let names: Vec<_> = sarc.files().map(|(name, _)| name).collect();
+        assert_eq!(names, ["GameData/gamedata.ssarc", "Actor/test.bxml"]);
+    }
+
+    #[test]
+    fn test_sarc_errors() {
+        assert!(matches!(Sarc::parse(b"hello"), Err(SarcError::BadMagic)));
+        let data = build(&[("a", b"hello")]);
+        assert!(Sarc::parse(&data[..0x20]).is_err());
+        let mut bad_end = data.clone();
+        bad_end[0x20 + 0xc] = 0xff;
+        assert!(matches!(
+            Sarc::parse(&bad_end),
+            Err(SarcError::OutOfBounds(_))
+        ));
+    }
+}
@@ -0,0 +1,93 @@
+use std::collections::HashMap;
+
+use crate::program::{Byml, BymlError};
+
+/// Items sold in shops from `ShopGameDataInfo.byml`, indexed by
+/// the hash of the shop NPC's actor name
+#[derive(Debug, Default, Clone)]
+pub struct ShopDataTable {
+    shops: HashMap<u32, Vec<String>>,
+}
+
+impl ShopDataTable {
+    /// Parse the table from the decompressed `ShopGameDataInfo.byml`
+    pub fn parse(data: &[u8]) -> Result<Self, BymlError> {
+        Ok(Self::from_root(&Byml::parse(data)?))
+    }
+
+    fn from_root(root: &Byml) -> Self {
+        let mut shops = HashMap::new();
+        let entries = root
+            .get("ShopAreaInfo")
+            .and_then(|x| x.as_array())
+            .unwrap_or_default();
+        for entry in entries {
+            let hash = match entry.get("Hash") {
+                Some(Byml::U32(x)) => *x,
+                Some(Byml::I32(x)) => *x as u32,
+                _ => continue,
+            };
+            let items = entry
+                .get("Actors")
+                .and_then(|x| x.as_array())
+                .unwrap_or_default()
+                .iter()
+                .filter_map(|x| x.get("Name").and_then(|x| x.as_str()))
+                .map(|x| x.to_string())
+                .collect();
+            shops.insert(hash, items);
+        }
+        Self { shops }
+    }
+
+    /// Get the actor names of the items sold by the NPC
+    pub fn get(&self, npc_actor: &str) -> Option<&[String]> {
+        self.get_by_hash(crc32fast::hash(npc_actor.as_bytes()))
+    }
+
+    /// Get the actor names of the items sold by the NPC, by the hash of its actor name
+    pub fn get_by_hash(&self, hash: u32) -> Option<&[String]> {
+        self.shops.get(&hash).map(|x| x.as_slice())
+    }
+
+    /// Get the number of shops in the table
+    pub fn len(&self) -> usize {
+        self.shops.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.shops.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::*;
+
+    #[test]
+    fn test_shop_data() {
+        let mut item = BTreeMap::new();
+        item.insert("Name".to_string(), Byml::String("NormalArrow".to_string()));
+        let mut shop = BTreeMap::new();
+        shop.insert(
+            "Hash".to_string(),
+            Byml::U32(crc32fast::hash(b"Npc_TripMaster_00")),
+        );
+        shop.insert("Actors".to_string(), Byml::Array(vec![Byml::Hash(item)]));
+        let mut root = BTreeMap::new();
+        root.insert(
+            "ShopAreaInfo".to_string(),
+            Byml::Array(vec![Byml::Hash(shop)]),
+        );
+        let table = ShopDataTable::from_root(&Byml::Hash(root));
+
+        assert_eq!(table.len(), 1);
+        assert_eq!(
+            table.get("Npc_TripMaster_00"),
+            Some(["NormalArrow".to_string()].as_slice())
+        );
+        assert_eq!(table.get("Npc_TripMaster_01"), None);
+    }
+}
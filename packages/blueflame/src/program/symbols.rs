@@ -0,0 +1,148 @@
+use crate::env::DataId;
+use crate::program::ArchivedProgram;
+
+/// Address of the main module in the symbol lists published by the decomp project
+const DECOMP_MAIN_START: u64 = 0x0000007100000000;
+
+/// Symbol table for resolving main offsets to function names
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SymbolMap {
+    /// Symbols, sorted by main offset
+    symbols: Vec<Symbol>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Symbol {
+    /// Offset of the start of the function in the main module
+    pub main_offset: u32,
+    /// Size of the function in bytes. 0 if unknown
+    pub size: u32,
+    /// Name of the function (can be mangled)
+    pub name: String,
+}
+
+impl SymbolMap {
+    /// Parse a symbol list in the format of the function CSV from the decomp project:
+    ///
+    /// ```text
+    /// Address,Quality,Size,Name
+    /// 0x0000007100000000,U,000052,_ZN4sead...
+    /// ```
+    ///
+    /// The address can be absolute (starting at `0x7100000000`) or a main offset.
+    /// Lines that cannot be parsed (like the header) and symbols without names are skipped
+    pub fn parse_csv(text: &str) -> Self {
+        let mut symbols = vec![];
+        for line in text.lines() {
+            let mut parts = line.split(',');
+            let (Some(addr), Some(_), Some(size), Some(name)) =
+                (parts.next(), parts.next(), parts.next(), parts.next())
+            else {
+                continue;
+            };
+            let name = name.trim();
+            if name.is_empty() {
+                continue;
+            }
+            let Some(addr) = addr.trim().strip_prefix("0x") else {
+                continue;
+            };
+            let Ok(addr) = u64::from_str_radix(addr, 16) else {
+                continue;
+            };
+            let addr = addr.checked_sub(DECOMP_MAIN_START).unwrap_or(addr);
+            let Ok(main_offset) = u32::try_from(addr) else {
+                continue;
+            };
+            let size = size.trim().parse().unwrap_or_default();
+            symbols.push(Symbol {
+                main_offset,
+                size,
+                name: name.to_string(),
+            });
+        }
+        Self::new(symbols)
+    }
+
+    /// Create the symbol map from the symbols
+    pub fn new(mut symbols: Vec<Symbol>) -> Self {
+        symbols.sort_by_key(|s| s.main_offset);
+        Self { symbols }
+    }
+
+    /// Load the symbol map embedded in the program image, if any
+    pub fn from_program(program: &ArchivedProgram) -> Option<Self> {
+        let data = program.data.iter().find(|d| d.id == DataId::SymbolMap)?;
+        match std::str::from_utf8(&data.bytes) {
+            Ok(text) => Some(Self::parse_csv(text)),
+            Err(e) => {
+                cu::error!("symbol map in the program image is not valid UTF-8: {e}");
+                None
+            }
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.symbols.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.symbols.is_empty()
+    }
+
+    /// Find the symbol containing the main offset, and return the symbol
+    /// and the offset into the symbol
+    pub fn resolve(&self, main_offset: u32) -> Option<(&Symbol, u32)> {
+        let i = match self
+            .symbols
+            .binary_search_by_key(&main_offset, |s| s.main_offset)
+        {
+            Ok(i) => i,
+            Err(0) => return None,
+            Err(i) => i - 1,
+        };
+        let symbol = &self.symbols[i];
+        let offset = main_offset - symbol.main_offset;
+        if symbol.size != 0 && offset >= symbol.size {
+            return None;
+        }
+        Some((symbol, offset))
+    }
+
+    /// Find the symbol with the name
+    pub fn find_by_name(&self, name: &str) -> Option<&Symbol> {
+        self.symbols.iter().find(|s| s.name == name)
+    }
+
+    /// Format the main offset as `function+0xoffset`, if it can be resolved
+    pub fn format_main_offset(&self, main_offset: u32) -> Option<String> {
+        let (symbol, offset) = self.resolve(main_offset)?;
+        Some(format!("{}+0x{offset:x}", symbol.name))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_and_resolve() {
+        let csv = "Address,Quality,Size,Name\n\
+            0x0000007100000100,O,000032,foo\n\
+            0x0000000000000080,U,000016,bar\n\
+            0x0000007100000200,U,000000,\n";
+        let symbols = SymbolMap::parse_csv(csv);
+        assert_eq!(symbols.len(), 2);
+        assert_eq!(
+            symbols.format_main_offset(0x100).as_deref(),
+            Some("foo+0x0")
+        );
+        assert_eq!(
+            symbols.format_main_offset(0x11c).as_deref(),
+            Some("foo+0x1c")
+        );
+        assert_eq!(symbols.format_main_offset(0x120), None);
+        assert_eq!(symbols.format_main_offset(0x84).as_deref(), Some("bar+0x4"));
+        assert_eq!(symbols.format_main_offset(0x40), None);
+    }
+}
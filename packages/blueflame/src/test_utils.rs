@@ -23,7 +23,7 @@ impl Process {
     pub fn new_for_test() -> Self {
         let mem = Arc::new(Memory::new_for_test());
         let proxies = Arc::new(Proxies::default());
-        Self::new(mem, proxies, Arc::new(EmptyHookProvider))
+        Self::new(mem, proxies, Arc::new(EmptyHookProvider), None)
     }
 }
 
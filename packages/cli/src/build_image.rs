@@ -0,0 +1,202 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use blueflame::env::{DataId, GameVer};
+use blueflame::program::{
+    self, ImageCompression, MODULE_NAMES, ModuleRange, NxModule, PackOptions,
+};
+use cu::pre::*;
+
+#[derive(clap::Args, Clone)]
+pub struct CmdBuildImage {
+    /// Directory with the modules of the game (`main`, `sdk`, etc.),
+    /// as NSO (no extension or `.nso`) or ELF (`.elf`)
+    dir: String,
+    /// Address to load the program at
+    #[clap(short, long, default_value = "0x1042000000")]
+    start: String,
+    /// Path to the output image (.bfi)
+    #[clap(short, long)]
+    output: String,
+    /// Game version of the modules (1.5 or 1.6)
+    #[clap(long, default_value = "1.5")]
+    ver: String,
+    /// Only include the pages in the range, in the format `[MODULE]:0xSTART-0xEND`.
+    ///
+    /// Offsets are relative to the module. Can be specified multiple times.
+    /// If not specified, the whole program is included
+    #[clap(short, long)]
+    range: Vec<String>,
+    /// Read ranges from a file, one range per line, with the same format as `--range`.
+    /// Lines can also be prefixed with `-r`, like the output of the memory trace
+    #[clap(long)]
+    range_file: Option<String>,
+    /// Path to the ActorInfo.product.byml (or .sbyml) to embed in the image.
+    ///
+    /// Same as `--data actor-info=PATH`
+    #[clap(long)]
+    actor_info: Option<String>,
+    /// Path to the symbol list (CSV) to embed in the image
+    #[clap(long)]
+    symbols: Option<String>,
+    /// Data file to embed in the image, in the format `NAME=PATH`.
+    ///
+    /// NAME can be `actor-info`, `cook-data`, `shop-data` or `gamedata`. Game data not specified
+    /// is loaded from the `romfs` directory in the module directory if it exists
+    /// (for example, `romfs/Cooking/CookData.sbyml`, or `gamedata.ssarc` inside
+    /// `romfs/Pack/Bootup.pack`). Yaz0-compressed files are decompressed
+    #[clap(long)]
+    data: Vec<String>,
+    /// Compression of the image (none, gzip or zstd)
+    #[clap(long, default_value = "zstd")]
+    compression: String,
+}
+
+pub fn run(cmd: CmdBuildImage) -> cu::Result<()> {
+    let ver = match cmd.ver.as_str() {
+        "1.5" | "1.5.0" => GameVer::X150,
+        "1.6" | "1.6.0" => GameVer::X160,
+        _ => cu::bail!("unsupported game version: {}", cmd.ver),
+    };
+    let compression = match cmd.compression.as_str() {
+        "none" => ImageCompression::None,
+        "gzip" => ImageCompression::Gzip,
+        "zstd" => ImageCompression::Zstd,
+        _ => cu::bail!("unsupported compression: {}", cmd.compression),
+    };
+    let start = match cmd.start.strip_prefix("0x") {
+        Some(hex) => u64::from_str_radix(hex, 16).ok(),
+        None => cmd.start.parse().ok(),
+    };
+    let Some(start) = start else {
+        cu::bail!("invalid start address: {}", cmd.start);
+    };
+
+    let mut ranges = Vec::new();
+    for range in &cmd.range {
+        ranges.push(range.parse::<ModuleRange>()?);
+    }
+    if let Some(path) = &cmd.range_file {
+        let content = cu::fs::read_string(path)?;
+        for line in content.lines() {
+            let line = line.trim();
+            let line = line.strip_prefix("-r").unwrap_or(line).trim();
+            if line.is_empty() {
+                continue;
+            }
+            ranges.push(line.parse::<ModuleRange>()?);
+        }
+    }
+
+    let dir = Path::new(&cmd.dir);
+    let mut modules = Vec::new();
+    for name in MODULE_NAMES {
+        let candidates = [
+            dir.join(name),
+            dir.join(format!("{name}.nso")),
+            dir.join(format!("{name}.elf")),
+        ];
+        let Some(path) = candidates.iter().find(|x| x.is_file()) else {
+            continue;
+        };
+        cu::info!("loading {}", path.display());
+        let bytes = cu::fs::read(path)?;
+        let module = NxModule::load(name, &bytes)?;
+        cu::debug!("module `{name}` size: 0x{:08x}", module.size());
+        modules.push(module);
+    }
+    if !modules.iter().any(|x| x.name() == "main") {
+        cu::bail!("main module not found in {}", cmd.dir);
+    }
+
+    let mut builder = program::build_from_modules(ver, start, modules, &ranges)
+        .context("failed to build program from modules")?;
+    let mut data_paths = BTreeMap::new();
+    if let Some(path) = &cmd.actor_info {
+        data_paths.insert(DataId::ActorInfoByml, PathBuf::from(path));
+    }
+    if let Some(path) = &cmd.symbols {
+        data_paths.insert(DataId::SymbolMap, PathBuf::from(path));
+    }
+    for data in &cmd.data {
+        let Some((name, path)) = data.split_once('=') else {
+            cu::bail!("invalid data `{data}`, expected `NAME=PATH`");
+        };
+        let Some(id) = DataId::ALL.iter().find(|x| x.name() == name) else {
+            cu::bail!("unknown data name: {name}");
+        };
+        data_paths.insert(*id, PathBuf::from(path));
+    }
+    for id in DataId::ALL {
+        let bytes = match data_paths.remove(id) {
+            Some(path) => {
+                cu::info!("loading {}", path.display());
+                cu::fs::read(&path)?
+            }
+            None => match load_romfs_data(dir, *id)? {
+                Some(bytes) => bytes,
+                None => continue,
+            },
+        };
+        let Some(bytes) = program::decompress_yaz0(&bytes) else {
+            cu::bail!("failed to decompress {}", id.name());
+        };
+        builder = builder.add_data(*id, bytes);
+    }
+    let program = builder.done();
+
+    let options = PackOptions {
+        compression,
+        metadata: format!(
+            "built by skybook {} from {} modules, start=0x{start:016x}, ranges={}",
+            env!("CARGO_PKG_VERSION"),
+            cmd.ver,
+            ranges.len()
+        ),
+    };
+    let bytes = program::pack_with_options(&program, &options).context("failed to pack program")?;
+    cu::fs::write(&cmd.output, &bytes)?;
+    if let Some(header) = program::read_header(&bytes)? {
+        cu::info!("image hash: {}", header.hash_hex());
+    }
+    cu::info!("image written to {}", cmd.output);
+
+    Ok(())
+}
+
+/// Load the data from the `romfs` directory in the module directory,
+/// looking inside the pack if the data is in one.
+///
+/// Returns `None` (with a warning) if the file is not found
+fn load_romfs_data(dir: &Path, id: DataId) -> cu::Result<Option<Vec<u8>>> {
+    let Some(romfs_path) = id.romfs_path() else {
+        return Ok(None);
+    };
+    let romfs = dir.join("romfs");
+    let Some(pack_path) = id.pack_path() else {
+        let path = romfs.join(romfs_path);
+        if !path.is_file() {
+            cu::warn!("{} is not found, it will not be in the image", id.name());
+            return Ok(None);
+        }
+        cu::info!("loading {}", path.display());
+        return Ok(Some(cu::fs::read(&path)?));
+    };
+    let path = romfs.join(pack_path);
+    if !path.is_file() {
+        cu::warn!("{} is not found, it will not be in the image", id.name());
+        return Ok(None);
+    }
+    cu::info!("loading {romfs_path} from {}", path.display());
+    let pack = cu::fs::read(&path)?;
+    let sarc = program::Sarc::parse(&pack)
+        .with_context(|| format!("failed to read {}", path.display()))?;
+    let Some(bytes) = sarc.get(romfs_path) else {
+        cu::warn!(
+            "{romfs_path} is not found in {}, it will not be in the image",
+            path.display()
+        );
+        return Ok(None);
+    };
+    Ok(Some(bytes.to_vec()))
+}
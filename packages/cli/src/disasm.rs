@@ -0,0 +1,72 @@
+use blueflame::linker::Disassembler;
+use blueflame::program::{self, SymbolMap};
+use cu::pre::*;
+
+#[derive(clap::Args, Clone)]
+pub struct CmdDisasm {
+    /// Main offset (e.g. `0x971540`) or name of the symbol to disassemble
+    target: String,
+    /// Path to the program image (.bfi)
+    #[clap(short, long)]
+    image: String,
+    /// Number of bytes to disassemble.
+    ///
+    /// Defaults to the size of the symbol, or 0x40 if the size is unknown
+    #[clap(short, long)]
+    size: Option<String>,
+    /// Path to a symbol list (CSV) to use instead of the symbols in the image
+    #[clap(long)]
+    symbols: Option<String>,
+}
+
+pub fn run(cmd: CmdDisasm) -> cu::Result<()> {
+    cu::info!("loading {}", cmd.image);
+    let image_bytes = cu::fs::read(&cmd.image).context("failed to read BFI")?;
+    let mut program_bytes = Vec::new();
+    let image =
+        program::unpack_zc(&image_bytes, &mut program_bytes).context("failed to unpack BFI")?;
+
+    let mut disassembler = Disassembler::new(image).context("failed to read BFI")?;
+    if let Some(path) = &cmd.symbols {
+        let csv = cu::fs::read_string(path)?;
+        disassembler.set_symbols(Some(SymbolMap::parse_csv(&csv)));
+    }
+
+    let (main_offset, symbol_size) = match parse_u32(&cmd.target) {
+        Some(x) => (x, 0),
+        None => {
+            let Some(symbols) = disassembler.symbols() else {
+                cu::bail!("no symbols available to find `{}`", cmd.target);
+            };
+            let Some(symbol) = symbols.find_by_name(&cmd.target) else {
+                cu::bail!("symbol not found: {}", cmd.target);
+            };
+            (symbol.main_offset, symbol.size)
+        }
+    };
+    let size = match &cmd.size {
+        Some(size) => {
+            let Some(size) = parse_u32(size) else {
+                cu::bail!("invalid size: {size}");
+            };
+            size
+        }
+        None if symbol_size != 0 => symbol_size,
+        None => 0x40,
+    };
+
+    let lines = disassembler
+        .disassemble(main_offset, size)
+        .context("failed to disassemble")?;
+    print!("{}", disassembler.format(&lines));
+
+    Ok(())
+}
+
+/// Parse a hex (with 0x prefix) or decimal number
+fn parse_u32(input: &str) -> Option<u32> {
+    match input.strip_prefix("0x") {
+        Some(hex) => u32::from_str_radix(hex, 16).ok(),
+        None => input.parse().ok(),
+    }
+}
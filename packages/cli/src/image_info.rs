@@ -0,0 +1,47 @@
+use blueflame::env::DataId;
+use blueflame::program;
+use cu::pre::*;
+
+#[derive(clap::Args, Clone)]
+pub struct CmdImageInfo {
+    /// Path to the program image (.bfi)
+    image: String,
+}
+
+pub fn run(cmd: CmdImageInfo) -> cu::Result<()> {
+    let image_bytes = cu::fs::read(&cmd.image).context("failed to read BFI")?;
+    match program::read_header(&image_bytes).context("failed to read BFI header")? {
+        None => println!("format: legacy (gzip without header)"),
+        Some(header) => {
+            println!("format: {}", header.format_version);
+            println!("compression: {:?}", header.compression);
+            println!("hash: {}", header.hash_hex());
+            println!("game version: {:?}", header.ver);
+            println!(
+                "size: {} bytes ({} bytes uncompressed)",
+                header.data_size, header.uncompressed_size
+            );
+            println!("metadata: {}", header.metadata);
+        }
+    }
+
+    // unpack to verify the checksum and the program
+    let mut program_bytes = Vec::new();
+    let image =
+        program::unpack_zc(&image_bytes, &mut program_bytes).context("failed to unpack BFI")?;
+    println!("program start: 0x{:016x}", image.program_start);
+    println!("program size: 0x{:08x}", image.program_size);
+    for module in image.modules.iter() {
+        println!(
+            "module `{}` at 0x{:08x}",
+            module.name,
+            module.rel_start.to_native()
+        );
+    }
+    for data in image.data.iter() {
+        let id = DataId::from(data.id);
+        println!("data {id:?}: {} bytes", data.bytes.len());
+    }
+
+    Ok(())
+}
@@ -0,0 +1,39 @@
+use cu::pre::*;
+
+mod build_image;
+mod disasm;
+mod image_info;
+mod run;
+mod util;
+
+/// Skybook command line tools
+#[derive(clap::Parser, Clone)]
+struct Args {
+    #[clap(subcommand)]
+    command: Command,
+
+    #[clap(flatten)]
+    common: cu::cli::Flags,
+}
+
+#[derive(clap::Subcommand, Clone)]
+enum Command {
+    /// Run a script and print the state of the game at each step
+    Run(run::CmdRun),
+    /// Disassemble code in the program image, as the emulator will run it
+    Disasm(disasm::CmdDisasm),
+    /// Build a program image (.bfi) from the modules of the game
+    BuildImage(build_image::CmdBuildImage),
+    /// Print the header and contents of a program image, and verify its checksum
+    ImageInfo(image_info::CmdImageInfo),
+}
+
+#[cu::cli(flags = "common")]
+fn main(args: Args) -> cu::Result<()> {
+    match args.command {
+        Command::Run(cmd) => run::run(cmd),
+        Command::Disasm(cmd) => disasm::run(cmd),
+        Command::BuildImage(cmd) => build_image::run(cmd),
+        Command::ImageInfo(cmd) => image_info::run(cmd),
+    }
+}
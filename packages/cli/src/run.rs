@@ -0,0 +1,327 @@
+use std::sync::Arc;
+
+use blueflame::linker;
+use blueflame::processor::{decode_exec_traces, encode_exec_traces};
+use cu::pre::*;
+use skybook_runtime::{MaybeAborted, iv, sim};
+
+use crate::util;
+
+#[derive(clap::Args, Clone)]
+pub struct CmdRun {
+    /// Path to the script file
+    script: String,
+    /// Path to the program image (.bfi)
+    #[clap(short, long)]
+    image: String,
+    /// DLC version to simulate (0-3)
+    #[clap(long, default_value_t = 3)]
+    dlc: u32,
+    /// Maximum number of instructions executed in one step, before
+    /// the step is stopped with an error. 0 means using the default
+    #[clap(long, default_value_t = 0)]
+    instruction_limit: u64,
+    /// Maximum time in milliseconds to execute one step, before
+    /// the step is stopped with an error. 0 means no limit
+    #[clap(long, default_value_t = 0)]
+    time_limit: u64,
+    /// GDT flag to watch. The value is printed at each step.
+    ///
+    /// The format is `TYPE:NAME` (e.g. `s32:MaxHart` or `bool[]:IsOpenItemCategory`),
+    /// or `TYPE:#HASH` to find the flag by the hash of its name (decimal or 0x hex)
+    #[clap(short, long)]
+    watch: Vec<String>,
+    /// Only print the state at this step (0-based)
+    #[clap(short, long)]
+    step: Option<usize>,
+    /// Read the watched flags from the named save instead of the current game data
+    #[clap(long, conflicts_with = "manual_save")]
+    save: Option<String>,
+    /// Read the watched flags from the manual save instead of the current game data
+    #[clap(long)]
+    manual_save: bool,
+    /// Print the PauseMenuDataMgr at each step as a tree of typed fields.
+    ///
+    /// Optionally takes the path of a field to only print that field,
+    /// for example `mList1` or `mItemBuffer[0].mListNode`
+    #[clap(long, value_name = "FIELD", num_args = 0..=1, default_missing_value = "")]
+    pmdm: Option<String>,
+    /// Count the instructions executed in each function, and write the
+    /// profile to the file in the folded stack format (for flamegraph tools)
+    #[clap(long, value_name = "FILE")]
+    profile: Option<String>,
+    /// Group the profile by each step, instead of only by the command type
+    #[clap(long, requires = "profile")]
+    profile_by_step: bool,
+    /// Record the blocks executed in each step, with the registers changed
+    /// and memory written, and write the trace to the file
+    #[clap(long, value_name = "FILE")]
+    exec_trace: Option<String>,
+    /// Compare the execution against the trace recorded with `--exec-trace`
+    /// (for example, by another build), and print the first difference
+    #[clap(long, value_name = "FILE")]
+    compare_trace: Option<String>,
+    /// Number of events to print before the first difference
+    #[clap(long, default_value_t = 16, requires = "compare_trace")]
+    trace_context: usize,
+    /// Start the script with the game already running, and the PauseMenuDataMgr
+    /// loaded from a memory dump from a console, instead of a new game.
+    ///
+    /// The dump is the raw bytes of the memory containing the PMDM singleton
+    #[clap(long, value_name = "FILE", requires_all = ["dump_start", "dump_main"])]
+    pmdm_dump: Option<String>,
+    /// Address of the first byte of the PMDM dump on the console
+    #[clap(long, value_name = "ADDR", value_parser = parse_address, requires = "pmdm_dump")]
+    dump_start: Option<u64>,
+    /// Address of the PMDM singleton on the console. Defaults to `--dump-start`
+    #[clap(long, value_name = "ADDR", value_parser = parse_address, requires = "pmdm_dump")]
+    dump_pmdm: Option<u64>,
+    /// Start of the main module on the console when the dump was taken
+    #[clap(long, value_name = "ADDR", value_parser = parse_address, requires = "pmdm_dump")]
+    dump_main: Option<u64>,
+}
+
+pub fn run(cmd: CmdRun) -> cu::Result<()> {
+    let queries = cmd
+        .watch
+        .iter()
+        .map(|x| parse_flag_query(x))
+        .collect::<cu::Result<Vec<_>>>()?;
+    let script = cu::fs::read_string(&cmd.script)?;
+    let runtime = util::setup_runtime(&cmd.image, cmd.dlc, cmd.instruction_limit, cmd.time_limit)?;
+
+    cu::co::run(async move { run_script(cmd, queries, script, runtime).await })
+}
+
+async fn run_script(
+    cmd: CmdRun,
+    queries: Vec<iv::GdtFlagQuery>,
+    script: String,
+    runtime: Arc<sim::Runtime>,
+) -> cu::Result<()> {
+    let parsed = skybook_parser::parse(&util::NoQuotedItemResolver, &script).await;
+    for error in &parsed.errors {
+        let prefix = if error.is_warning { "warning" } else { "error" };
+        cu::warn!(
+            "parse {prefix}: {}\n  at: {}",
+            error.error,
+            &script[error.span.0..error.span.1]
+        );
+    }
+
+    let mut run =
+        sim::Run::new(Arc::new(sim::RunHandle::new())).with_watched_flags(queries.clone());
+    if cmd.profile.is_some() {
+        run = run.with_profile();
+    }
+    if cmd.exec_trace.is_some() || cmd.compare_trace.is_some() {
+        run = run.with_exec_trace();
+    }
+    let output = match &cmd.pmdm_dump {
+        Some(path) => {
+            let state = load_pmdm_dump_state(&cmd, path, &runtime)?;
+            run.run_parsed_from_state(&parsed, &runtime, state).await
+        }
+        None => run.run_parsed(&parsed, &runtime).await,
+    };
+    let MaybeAborted::Ok(output) = output else {
+        cu::bail!("the run is aborted");
+    };
+    for error in &output.errors {
+        let prefix = if error.is_warning { "warning" } else { "error" };
+        cu::warn!(
+            "runtime {prefix}: {}\n  at: {}",
+            error.error,
+            &script[error.span.0..error.span.1]
+        );
+    }
+
+    if let Some(path) = &cmd.profile {
+        let folded = output.get_profile_folded(cmd.profile_by_step);
+        cu::fs::write(path, folded)?;
+        cu::info!("profile written to {path}");
+    }
+
+    if let Some(path) = &cmd.exec_trace {
+        let bytes = encode_exec_traces(&output.exec_traces)?;
+        cu::fs::write(path, bytes)?;
+        cu::info!("execution trace written to {path}");
+    }
+
+    if let Some(path) = &cmd.compare_trace {
+        let bytes = cu::fs::read(path)?;
+        let expected = decode_exec_traces(&bytes)?;
+        match output.find_exec_trace_divergence(&expected, cmd.trace_context) {
+            None => cu::info!("execution is the same as the trace in {path}"),
+            Some((i, divergence)) => {
+                let span = parsed.steps.get(i).map(|x| x.span());
+                let step = span.map(|x| script[x.lo..x.hi].trim()).unwrap_or_default();
+                cu::warn!("execution diverges from the trace in {path} at step {i}: {step}");
+                println!("{divergence}");
+            }
+        }
+    }
+
+    let save_name = match (&cmd.save, cmd.manual_save) {
+        (Some(name), _) => Some(Some(name.as_str())),
+        (None, true) => Some(None),
+        (None, false) => None,
+    };
+
+    for (i, step) in parsed.steps.iter().enumerate() {
+        if cmd.step.is_some_and(|x| x != i) {
+            continue;
+        }
+        let span = step.span();
+        println!("----- Step[{i}]: {}", script[span.lo..span.hi].trim());
+        if let Some(report) = output.get_crash_report(i) {
+            println!("  game crashed: {report:?}");
+            continue;
+        }
+        let flags = match save_name {
+            Some(name) => output.get_save_gdt_flags(i, name, &queries),
+            None => match output.get_watched_gdt_flags(i) {
+                Ok(flags) => flags,
+                Err(e) => {
+                    println!("  cannot read flags: {e}");
+                    continue;
+                }
+            },
+        };
+        for flag in flags {
+            let name = if flag.query.name.is_empty() {
+                format!("#0x{:08x}", flag.hash)
+            } else {
+                flag.query.name
+            };
+            match flag.value {
+                Some(value) => println!("  {} {name} = {value}", flag.query.ty),
+                None => println!("  {} {name} = <not found>", flag.query.ty),
+            }
+        }
+        if let Some(path) = &cmd.pmdm {
+            print_pmdm(&output, i, path);
+        }
+    }
+
+    Ok(())
+}
+
+/// Create the state to start the run from, with the PMDM dump loaded
+fn load_pmdm_dump_state(
+    cmd: &CmdRun,
+    path: &str,
+    runtime: &sim::Runtime,
+) -> cu::Result<sim::State> {
+    let bytes = cu::fs::read(path)?;
+    let dump_start = cmd.dump_start.unwrap_or_default();
+    let dump = linker::PmdmDump {
+        bytes: &bytes,
+        dump_start,
+        pmdm_addr: cmd.dump_pmdm.unwrap_or(dump_start),
+        main_start: cmd.dump_main.unwrap_or_default(),
+    };
+    let process = runtime
+        .initial_process()
+        .context("failed to get initial process")?;
+    let state = sim::State::from_pmdm_dump(process, &dump).context("failed to load PMDM dump")?;
+    Ok(state)
+}
+
+/// Print the PauseMenuDataMgr tree, or the field at the path
+fn print_pmdm(output: &sim::RunOutput, step: usize, path: &str) {
+    let tree = match output.get_pmdm_tree(step) {
+        Ok(tree) => tree,
+        Err(e) => {
+            println!("  cannot inspect pmdm: {e}");
+            return;
+        }
+    };
+    if path.is_empty() {
+        print_node(&tree, 1);
+        return;
+    }
+    match find_node(&tree, path, "") {
+        Some(node) => print_node(node, 1),
+        None => println!("  pmdm has no field `{path}`"),
+    }
+}
+
+/// Find the node at the path, relative to `prefix` (the path of the node)
+fn find_node<'a>(
+    node: &'a iv::InspectNode,
+    path: &str,
+    prefix: &str,
+) -> Option<&'a iv::InspectNode> {
+    for child in &node.children {
+        let child_path = if prefix.is_empty() || child.name.starts_with('[') {
+            format!("{prefix}{}", child.name)
+        } else {
+            format!("{prefix}.{}", child.name)
+        };
+        if child_path == path {
+            return Some(child);
+        }
+        if path.starts_with(&child_path)
+            && let Some(x) = find_node(child, path, &child_path)
+        {
+            return Some(x);
+        }
+    }
+    None
+}
+
+fn print_node(node: &iv::InspectNode, depth: usize) {
+    let indent = "  ".repeat(depth);
+    let addr = node.addr.as_u64();
+    let mut line = format!("{indent}[0x{addr:016x}] {}: {}", node.name, node.ty);
+    if !node.value.is_empty() {
+        line.push_str(&format!(" = {}", node.value));
+    }
+    if !node.annotation.is_empty() {
+        line.push_str(&format!(" ({})", node.annotation));
+    }
+    println!("{line}");
+    for child in &node.children {
+        print_node(child, depth + 1);
+    }
+}
+
+/// Parse `TYPE:NAME` or `TYPE:#HASH` into a flag query
+fn parse_flag_query(input: &str) -> cu::Result<iv::GdtFlagQuery> {
+    let Some((ty, name)) = input.split_once(':') else {
+        cu::bail!("invalid flag `{input}`, expected TYPE:NAME or TYPE:#HASH");
+    };
+    let Some(ty) = iv::GdtFlagType::from_name(ty) else {
+        let types = iv::GdtFlagType::ALL.map(|x| x.as_str()).join(", ");
+        cu::bail!("invalid flag type `{ty}`, expected one of: {types}");
+    };
+    let Some(hash) = name.strip_prefix('#') else {
+        return Ok(iv::GdtFlagQuery {
+            name: name.to_string(),
+            hash: 0,
+            ty,
+        });
+    };
+    let hash = match hash.strip_prefix("0x") {
+        Some(hex) => u32::from_str_radix(hex, 16).map(|x| x as i32).ok(),
+        None => hash.parse::<i32>().ok(),
+    };
+    let Some(hash) = hash else {
+        cu::bail!("invalid flag hash in `{input}`");
+    };
+    Ok(iv::GdtFlagQuery {
+        name: String::new(),
+        hash,
+        ty,
+    })
+}
+
+/// Parse an address in hex (with the 0x prefix) or decimal
+fn parse_address(input: &str) -> Result<u64, String> {
+    let address = match input.strip_prefix("0x") {
+        Some(hex) => u64::from_str_radix(&hex.replace('_', ""), 16).ok(),
+        None => input.parse::<u64>().ok(),
+    };
+    address.ok_or_else(|| format!("invalid address `{input}`"))
+}
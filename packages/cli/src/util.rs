@@ -0,0 +1,57 @@
+use std::sync::Arc;
+
+use cu::pre::*;
+use skybook_parser::cir;
+use skybook_runtime::{exec, sim};
+
+/// Load the image and initialize the runtime.
+///
+/// `instruction_limit` of 0 means using the default limit,
+/// and `time_limit` (in milliseconds) of 0 means no limit
+pub fn setup_runtime(
+    image_file: &str,
+    dlc: u32,
+    instruction_limit: u64,
+    time_limit: u64,
+) -> cu::Result<Arc<sim::Runtime>> {
+    cu::info!("loading {image_file}");
+
+    let image_bytes = cu::fs::read(image_file).context("failed to read BFI")?;
+
+    let runtime = sim::Runtime::new(exec::Spawner::new()?);
+    let threads = std::thread::available_parallelism()
+        .map(usize::from)
+        .unwrap_or(1)
+        .clamp(1, 8);
+    runtime
+        .init(
+            &image_bytes,
+            threads,
+            Some(&sim::RuntimeInitParams {
+                dlc,
+                program_start: "".to_string(),
+                stack_start: "".to_string(),
+                stack_size: 0,
+                heap_free_size: 0,
+                pmdm_addr: "".to_string(),
+                enable_features: Vec::new(),
+                disable_features: Vec::new(),
+                instruction_limit,
+                time_limit,
+            }),
+        )
+        .context("failed to initialize runtime")?;
+
+    Ok(Arc::new(runtime))
+}
+
+/// Quoted item resolver for the CLI, which doesn't support searching
+pub struct NoQuotedItemResolver;
+impl cir::QuotedItemResolver for NoQuotedItemResolver {
+    type Future = cu::BoxedFuture<Option<cir::ResolvedItem>>;
+
+    fn resolve_quoted(&self, word: &str) -> Self::Future {
+        cu::warn!("quoted item search is not supported in the CLI, searching: {word}");
+        Box::pin(async { None })
+    }
+}
@@ -0,0 +1,16 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|script: &str| {
+    let parsed = skybook_fuzz::parse(script);
+    // converting to script should produce the same commands when parsed again
+    let script1 = parsed.to_script();
+    let parsed1 = skybook_fuzz::parse(&script1);
+    let script2 = parsed1.to_script();
+    assert_eq!(
+        script1, script2,
+        "to_script does not round-trip\noriginal:\n{script}"
+    );
+    assert_eq!(parsed.steps.len(), parsed1.steps.len());
+});
@@ -0,0 +1,15 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use skybook_fuzz::FuzzCommand;
+
+fuzz_target!(|commands: Vec<FuzzCommand>| {
+    if commands.len() > 64 {
+        return;
+    }
+    let script = skybook_fuzz::commands_to_script(&commands);
+    let parsed = skybook_fuzz::parse(&script);
+    if let Err(e) = skybook_fuzz::run_steps(&parsed.steps) {
+        panic!("failed to execute script: {e}\nscript:\n{script}");
+    }
+});
@@ -0,0 +1,295 @@
+//! Shared utilities for the fuzz targets
+
+use std::future::Future;
+use std::pin::pin;
+use std::sync::{Arc, OnceLock};
+use std::task::{Context, Poll, Wake, Waker};
+
+use arbitrary::Arbitrary;
+use skybook_parser::{ParseOutput, cir};
+use skybook_runtime::{exec, sim};
+
+/// Block on a future on the current thread
+pub fn block_on<F: Future>(f: F) -> F::Output {
+    struct ThreadWaker(std::thread::Thread);
+    impl Wake for ThreadWaker {
+        fn wake(self: Arc<Self>) {
+            self.0.unpark();
+        }
+    }
+    let waker = Waker::from(Arc::new(ThreadWaker(std::thread::current())));
+    let mut cx = Context::from_waker(&waker);
+    let mut f = pin!(f);
+    loop {
+        match f.as_mut().poll(&mut cx) {
+            Poll::Ready(x) => return x,
+            Poll::Pending => std::thread::park(),
+        }
+    }
+}
+
+/// Parse the script. Quoted item searches are always resolved to nothing
+pub fn parse(script: &str) -> ParseOutput {
+    block_on(skybook_parser::parse(
+        &|_: &str| std::future::ready(None),
+        script,
+    ))
+}
+
+/// Items used for generating commands
+///
+/// These are picked to cover all the categories and some special cases
+/// (arrows, master sword, champion abilities, stackables, etc)
+#[derive(Debug, Clone, Copy, Arbitrary)]
+pub enum FuzzItem {
+    Apple,
+    Banana,
+    Wood,
+    Diamond,
+    Elixir,
+    DubiousFood,
+    FairyTonic,
+    Fairy,
+    RoyalClaymore,
+    MasterSword,
+    Axe,
+    Torch,
+    TravelersBow,
+    Arrow,
+    FireArrow,
+    AncientArrow,
+    PotLid,
+    HylianShield,
+    HylianHood,
+    HylianTunic,
+    Glider,
+    Slate,
+    Gale,
+    SpiritOrb,
+    Weapon,
+    Bow,
+    Shield,
+    Armor,
+    Material,
+    Food,
+    KeyItem,
+}
+
+impl FuzzItem {
+    pub fn to_script(self) -> &'static str {
+        match self {
+            Self::Apple => "apple",
+            Self::Banana => "banana",
+            Self::Wood => "wood",
+            Self::Diamond => "diamond",
+            Self::Elixir => "elixir",
+            Self::DubiousFood => "dubious-food",
+            Self::FairyTonic => "fairy-tonic",
+            Self::Fairy => "fairy",
+            Self::RoyalClaymore => "royal-claymore",
+            Self::MasterSword => "master-sword",
+            Self::Axe => "axe",
+            Self::Torch => "torch",
+            Self::TravelersBow => "trav-bow",
+            Self::Arrow => "arrow",
+            Self::FireArrow => "fire-arrow",
+            Self::AncientArrow => "ancient-arrow",
+            Self::PotLid => "pot-lid",
+            Self::HylianShield => "hylian-shield",
+            Self::HylianHood => "hylian-hood",
+            Self::HylianTunic => "hylian-tunic",
+            Self::Glider => "glider",
+            Self::Slate => "slate",
+            Self::Gale => "gale",
+            Self::SpiritOrb => "spirit-orb",
+            Self::Weapon => "weapon",
+            Self::Bow => "bow",
+            Self::Shield => "shield",
+            Self::Armor => "armor",
+            Self::Material => "material",
+            Self::Food => "food",
+            Self::KeyItem => "key-item",
+        }
+    }
+}
+
+/// Amount of items in a command
+#[derive(Debug, Clone, Copy, Arbitrary)]
+pub enum FuzzAmount {
+    One,
+    Num(u8),
+    All,
+}
+
+impl FuzzAmount {
+    fn to_script(self, out: &mut String) {
+        match self {
+            Self::One => {}
+            Self::Num(n) => {
+                out.push_str(&n.to_string());
+                out.push(' ');
+            }
+            Self::All => out.push_str("all "),
+        }
+    }
+}
+
+/// A command to generate, which is converted to script
+/// and then parsed into [`cir::Command`]
+#[derive(Debug, Clone, Copy, Arbitrary)]
+pub enum FuzzCommand {
+    Get(FuzzAmount, FuzzItem),
+    PickUp(FuzzAmount, FuzzItem),
+    Pause,
+    Unpause,
+    Hold(FuzzAmount, FuzzItem),
+    Unhold,
+    Drop(FuzzAmount, FuzzItem),
+    Dnp(FuzzAmount, FuzzItem),
+    Eat(FuzzAmount, FuzzItem),
+    Entangle(FuzzItem),
+    Sort(FuzzItem),
+    Overload,
+    Unoverload,
+    Equip(FuzzItem),
+    Unequip(FuzzItem),
+    Use(FuzzItem, u8),
+    TalkTo,
+    CloseDialog,
+    Buy(FuzzAmount, FuzzItem),
+    Sell(FuzzAmount, FuzzItem),
+    Save(bool),
+    Reload(bool),
+    CloseGame,
+    NewGame,
+    Smug,
+    PauseDuring,
+    SameDialog,
+    Dpad,
+    Overworld,
+    SuBreak(u8),
+    SuInit(FuzzAmount, FuzzItem),
+    SuAddSlot(FuzzAmount, FuzzItem),
+    SuRemove(FuzzAmount, FuzzItem),
+    SuSwap(FuzzItem, FuzzItem),
+    SuArrowlessSmuggle,
+    SuTrialStart,
+    SuTrialEnd,
+}
+
+impl FuzzCommand {
+    pub fn to_script(self, out: &mut String) {
+        fn with_items(cmd: &str, amount: FuzzAmount, item: FuzzItem, out: &mut String) {
+            out.push_str(cmd);
+            out.push(' ');
+            amount.to_script(out);
+            out.push_str(item.to_script());
+        }
+        fn with_item(cmd: &str, item: FuzzItem, out: &mut String) {
+            out.push_str(cmd);
+            out.push(' ');
+            out.push_str(item.to_script());
+        }
+        match self {
+            Self::Get(a, i) => with_items("get", a, i, out),
+            Self::PickUp(a, i) => with_items("pick-up", a, i, out),
+            Self::Pause => out.push_str("pause"),
+            Self::Unpause => out.push_str("unpause"),
+            Self::Hold(a, i) => with_items("hold", a, i, out),
+            Self::Unhold => out.push_str("unhold"),
+            Self::Drop(a, i) => with_items("drop", a, i, out),
+            Self::Dnp(a, i) => with_items("dnp", a, i, out),
+            Self::Eat(a, i) => with_items("eat", a, i, out),
+            Self::Entangle(i) => with_item("entangle", i, out),
+            Self::Sort(i) => with_item("sort", i, out),
+            Self::Overload => out.push_str("overload"),
+            Self::Unoverload => out.push_str("unoverload"),
+            Self::Equip(i) => with_item("equip", i, out),
+            Self::Unequip(i) => with_item("unequip", i, out),
+            Self::Use(i, times) => {
+                with_item("use", i, out);
+                out.push_str(&format!(" {} times", times % 8 + 1));
+            }
+            Self::TalkTo => out.push_str("talk-to npc"),
+            Self::CloseDialog => out.push_str("close-dialog"),
+            Self::Buy(a, i) => with_items("buy", a, i, out),
+            Self::Sell(a, i) => with_items("sell", a, i, out),
+            Self::Save(named) => out.push_str(if named { "save-as fuzz" } else { "save" }),
+            Self::Reload(named) => out.push_str(if named { "reload fuzz" } else { "reload" }),
+            Self::CloseGame => out.push_str("close-game"),
+            Self::NewGame => out.push_str("new-game"),
+            Self::Smug => out.push_str(":smug"),
+            Self::PauseDuring => out.push_str(":pause-during"),
+            Self::SameDialog => out.push_str(":same-dialog"),
+            Self::Dpad => out.push_str(":dpad"),
+            Self::Overworld => out.push_str(":overworld"),
+            Self::SuBreak(n) => out.push_str(&format!("!break {} slots", n % 30)),
+            Self::SuInit(a, i) => with_items("!init", a, i, out),
+            Self::SuAddSlot(a, i) => with_items("!add-slot", a, i, out),
+            Self::SuRemove(a, i) => with_items("!remove", a, i, out),
+            Self::SuSwap(i1, i2) => {
+                with_item("!swap", i1, out);
+                out.push_str(" and ");
+                out.push_str(i2.to_script());
+            }
+            Self::SuArrowlessSmuggle => out.push_str("!arrowless-smuggle"),
+            Self::SuTrialStart => out.push_str("!trial-start"),
+            Self::SuTrialEnd => out.push_str("!trial-end"),
+        }
+    }
+}
+
+/// Convert generated commands to a script, one command per line
+pub fn commands_to_script(commands: &[FuzzCommand]) -> String {
+    let mut out = String::new();
+    for command in commands {
+        command.to_script(&mut out);
+        out.push('\n');
+    }
+    out
+}
+
+/// Get the runtime for fuzzing.
+///
+/// The image is loaded from the path in the `SKYBOOK_FUZZ_IMAGE` environment
+/// variable, or the mini image in runtime-tests if not set
+pub fn runtime() -> &'static sim::Runtime {
+    static RUNTIME: OnceLock<sim::Runtime> = OnceLock::new();
+    RUNTIME.get_or_init(|| {
+        let image_file = std::env::var("SKYBOOK_FUZZ_IMAGE")
+            .unwrap_or_else(|_| "../runtime-tests/data/program-mini.bfi".to_string());
+        let image = std::fs::read(&image_file)
+            .unwrap_or_else(|e| panic!("failed to read image {image_file}: {e}"));
+        let spawner = exec::Spawner::new().expect("failed to create spawner");
+        let runtime = sim::Runtime::new(spawner);
+        runtime
+            .init(&image, 1, None)
+            .expect("failed to initialize runtime");
+        runtime
+    })
+}
+
+/// Run the steps on a new state, and view the inventory after each step.
+///
+/// Game crashes are reported through `CrashReport` and are fine. Panics are not caught
+/// here, since libfuzzer aborts on panic and handles them (use `cargo fuzz tmin`
+/// to minimize the input). Errors from the executor (for example, the processor
+/// thread is lost) are returned for the fuzz target to fail with
+pub fn run_steps(steps: &[cir::Step]) -> Result<(), exec::Error> {
+    let runtime = runtime();
+    block_on(async {
+        let process = runtime.initial_process().expect("runtime not initialized");
+        let mut state = sim::State::new(process);
+        let mut ctx = sim::Context::new(Arc::new(sim::RunHandle::new()), runtime);
+        for step in steps {
+            ctx.span = step.span();
+            state = state.execute_step(ctx.clone(), step).await?.value;
+            // the views should never panic
+            if let sim::Game::Running(game) = &state.game {
+                let _ = sim::view::extract_pouch_view(&game.process, &game.systems);
+                let _ = sim::view::extract_gdt_view(&game.process);
+            }
+        }
+        Ok(())
+    })
+}
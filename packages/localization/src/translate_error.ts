@@ -69,6 +69,10 @@ export const translateRuntimeInitError = (
     switch (error.type) {
         case "BadDlcVersion":
             return translator(key, { version: error.data });
+        case "BadImage":
+            return translator(key, { reason: error.data });
+        case "UnknownFeature":
+            return translator(key, { name: error.data });
         case "ProgramStartMismatch": {
             const [addr_ci, addr_script] = error.data;
             return translator(key, { addr_ci, addr_script });
@@ -107,7 +111,24 @@ export const translateRuntimeError = (
             const [need, actual] = error.data;
             return translator(key, { need, actual });
         }
-        case "SaveNotFound": {
+        case "NotEnoughRupees": {
+            const [need, actual] = error.data;
+            return translator(key, { need, actual });
+        }
+        case "NotEnoughUpgradeMaterial": {
+            const [material, need, actual] = error.data;
+            return translator(key, { material, need, actual });
+        }
+        case "NotSoldInShop": {
+            const npc = error.data;
+            return translator(key, { npc });
+        }
+        case "OutOfStock": {
+            const remaining = error.data;
+            return translator(key, { remaining });
+        }
+        case "SaveNotFound":
+        case "UnknownFeature": {
             const name = error.data;
             return translator(key, { name });
         }
@@ -115,6 +136,11 @@ export const translateRuntimeError = (
             const time = error.data;
             return translator(key, { time });
         }
+        case "InstructionLimitReached":
+        case "TimeLimitReached": {
+            const [limit, range] = error.data;
+            return translator(key, { limit, range });
+        }
         case "CannotFindGdtFlag": {
             const [flag, type] = error.data;
             return translator(key, { flag, type });
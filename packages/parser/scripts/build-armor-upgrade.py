@@ -35,5 +35,45 @@ pub static ARMOR_UPGRADE: &[[&str; 5]] = &[
         f.write("];\n")
     print("armor_upgrade generated");
 
+def generate_armor_upgrade_cost():
+    """
+    armor-upgrade-costs.yaml maps the 0-star armor (without the Armor_ prefix)
+    to the cost to upgrade to 1, 2, 3 and 4 star:
+
+    001_Head:
+    - materials: { Item_Enemy_00: 5 }
+      rupees: 0
+    - ...
+    """
+    cost_path = os.path.join(DATA_DIR, "output", "armor-upgrade-costs.yaml")
+    costs = {}
+    if os.path.exists(cost_path):
+        with open(cost_path, "r", encoding="utf-8") as f:
+            costs = yaml.safe_load(f) or {}
+    else:
+        print(f"warning: {cost_path} not found, generating empty armor upgrade costs")
+
+    output_path = os.path.join(OUTPUT_DIR, "armor_upgrade_cost.gen.rs")
+
+    with open(output_path, "w", encoding="utf-8", newline="\n") as f:
+        f.write(HEADER)
+        f.write("""
+/// Cost to upgrade armor, by the 0 star armor. Each slice is to 1 star to 4 star
+#[rustfmt::skip]
+pub static ARMOR_UPGRADE_COST: &[(&str, [ArmorUpgradeCost; 4])] = &[
+""")
+        for armor, levels in costs.items():
+            f.write(f'    ("{armor}", [\n')
+            for level in levels:
+                materials = ", ".join(
+                    [f'("{name}", {count})' for name, count in level.get("materials", {}).items()]
+                )
+                rupees = level.get("rupees", 0)
+                f.write(f"        ArmorUpgradeCost {{ materials: &[{materials}], rupees: {rupees} }},\n")
+            f.write("    ]),\n")
+        f.write("];\n")
+    print("armor_upgrade_cost generated");
+
 if __name__ == "__main__":
     generate_armor_upgrade()
+    generate_armor_upgrade_cost()
@@ -120,8 +120,8 @@ pub enum Command {
     /// See [`syn::CmdDisplay`]
     Display(Vec<cir::ItemSelectSpec>),
 
-    /// See [`syn::CmdOpenShop`]
-    OpenShop,
+    /// See [`syn::CmdOpenShop`], with the name of the NPC
+    OpenShop(Option<String>),
     /// See [`syn::CmdCloseShop`]
     CloseShop,
     /// See [`syn::CmdBuy`]
@@ -134,6 +134,13 @@ pub enum Command {
     /// returning to overworld
     CoSameDialog,
 
+    /// See [`syn::CmdUpgrade`]
+    Upgrade(Box<cir::ItemSelectSpec>),
+    /// See [`syn::CmdHestu`], the amount is the number of upgrades
+    Hestu(cir::CategorySpec),
+    /// See [`syn::CmdPray`], second arg is times
+    Pray(cir::PrayTarget, usize),
+
     /// `save` - make a manual save or named save, see [`syn::CmdSaveAs`]
     Save(Option<String>),
     /// `reload` - Load manual save or named save, see [`syn::CmdReload`]
@@ -301,7 +308,7 @@ pub async fn parse_command<R: cir::QuotedItemResolver>(
             cir::parse_item_list_constrained(&cmd.items, resolver, errors).await,
         )),
         //////////////////////////////////////////////////////////////////
-        C::OpenShop(_) => Some(X::OpenShop),
+        C::OpenShop(cmd) => Some(X::OpenShop(cmd.name.as_ref().map(|x| x.to_string()))),
         C::CloseShop(_) => Some(X::CloseShop),
         C::Buy(cmd) => Some(X::Buy(
             cir::parse_item_list_finite(&cmd.items, resolver, errors).await,
@@ -311,6 +318,34 @@ pub async fn parse_command<R: cir::QuotedItemResolver>(
         )),
         A![SameDialog(_)] => Some(X::CoSameDialog),
         //////////////////////////////////////////////////////////////////
+        C::Upgrade(cmd) => Some(X::Upgrade(Box::new(
+            cir::parse_one_item_constrained(&cmd.item, resolver, errors).await?,
+        ))),
+        C::Hestu(cmd) => {
+            let category = absorb_error(
+                errors,
+                cir::parse_category_in(
+                    &cmd.category,
+                    cir::Category::Weapon | cir::Category::Bow | cir::Category::Shield,
+                ),
+            )?;
+            let times = absorb_error(errors, cir::parse_times_clause(cmd.times.as_ref()))?;
+            Some(X::Hestu(cir::CategorySpec {
+                category,
+                amount: times,
+                row: 0,
+                col: 0,
+            }))
+        }
+        C::Pray(cmd) => {
+            let target = absorb_error(
+                errors,
+                cir::parse_pray_target(cmd.target.as_str(), &cmd.target.span()),
+            )?;
+            let times = absorb_error(errors, cir::parse_times_clause(cmd.times.as_ref()))?;
+            Some(X::Pray(target, times as usize))
+        }
+        //////////////////////////////////////////////////////////////////
         C::Save(_) => Some(X::Save(None)),
         C::SaveAs(cmd) => Some(X::Save(Some(parse_save_name(&cmd.name)))),
         C::Reload(cmd) => Some(X::Reload(cmd.name.as_ref().map(parse_save_name))),
@@ -490,11 +525,29 @@ impl Command {
             Command::ThrowWeapon => out.push_str("throw weapon"),
             Command::Display(items) => item_select_specs_to_script("display", items, out),
 
-            Command::OpenShop => out.push_str("talk-to npc"),
+            Command::OpenShop(npc) => {
+                write!(out, "talk-to {}", npc.as_deref().unwrap_or("npc")).unwrap()
+            }
             Command::CloseShop => out.push_str("untalk"),
             Command::Buy(items) => item_specs_to_script("buy", items, out),
             Command::Sell(items) => item_select_specs_to_script("sell", items, out),
             Command::CoSameDialog => out.push_str(":same-dialog"),
+            Command::Upgrade(item) => {
+                out.push_str("upgrade ");
+                item.to_script(out);
+            }
+            Command::Hestu(cat) => {
+                write!(out, "hestu {}", cat.category).unwrap();
+                if cat.amount != 1 {
+                    write!(out, " {} times", cat.amount).unwrap();
+                }
+            }
+            Command::Pray(target, times) => {
+                write!(out, "pray {target}").unwrap();
+                if *times != 1 {
+                    write!(out, " {times} times").unwrap();
+                }
+            }
             Command::Save(file) => {
                 out.push_str("save");
                 if let Some(file) = file {
@@ -19,5 +19,7 @@ mod inventory_meta;
 pub use inventory_meta::*;
 mod system_meta;
 pub use system_meta::*;
+mod pray;
+pub use pray::*;
 
 pub use skybook_api::parser::cir::*;
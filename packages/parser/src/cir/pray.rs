@@ -0,0 +1,29 @@
+use teleparse::Span;
+
+use crate::error::{ErrorReport, cir_fail};
+
+/// What to get when praying at a Goddess Statue
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PrayTarget {
+    /// A Heart Container
+    Heart,
+    /// A Stamina Vessel
+    Stamina,
+}
+
+impl std::fmt::Display for PrayTarget {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Heart => write!(f, "heart"),
+            Self::Stamina => write!(f, "stamina"),
+        }
+    }
+}
+
+pub fn parse_pray_target(target: &str, span: &Span) -> Result<PrayTarget, ErrorReport> {
+    match target.to_ascii_lowercase().as_str() {
+        "heart" | "hearts" | "heart-container" => Ok(PrayTarget::Heart),
+        "stamina" | "stamina-vessel" => Ok(PrayTarget::Stamina),
+        _ => cir_fail!(span, InvalidPrayTarget(target.to_string())),
+    }
+}
@@ -36,6 +36,10 @@ pub enum SysCommandData {
     LoadingScreen,
     /// Trigger a loading screen without first removing translucent items
     LoadingScreenNoRemoveTranslucent,
+    /// Enable a BlueFlame feature by its kebab-case name
+    EnableFeature(String),
+    /// Disable a BlueFlame feature by its kebab-case name
+    DisableFeature(String),
 }
 
 impl SysCommandData {
@@ -62,6 +66,8 @@ impl SysCommandData {
             SysCommandData::LoadingScreenNoRemoveTranslucent => {
                 write!(out, "loading-screen=no-remove-translucent").unwrap()
             }
+            SysCommandData::EnableFeature(x) => write!(out, "enable-feature={x}").unwrap(),
+            SysCommandData::DisableFeature(x) => write!(out, "disable-feature={x}").unwrap(),
         }
     }
 }
@@ -127,6 +133,12 @@ impl MetaParser for SysCommandMeta {
                     }
                 }
             },
+            "enable-feature" => required {
+                string(x) => self.commands.push(make_cmd!(EnableFeature(x.trim().to_string()))),
+            },
+            "disable-feature" => required {
+                string(x) => self.commands.push(make_cmd!(DisableFeature(x.trim().to_string()))),
+            },
         }
     }
 
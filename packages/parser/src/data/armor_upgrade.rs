@@ -1,6 +1,16 @@
 use std::borrow::Cow;
 
 include!("armor_upgrade.gen.rs");
+include!("armor_upgrade_cost.gen.rs");
+
+/// Cost to upgrade an armor by one star at a Great Fairy
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ArmorUpgradeCost {
+    /// Material actors and the amount of each material needed
+    pub materials: &'static [(&'static str, i32)],
+    /// Rupees needed
+    pub rupees: i32,
+}
 
 /// Given any armor, get the armor actor with the number of stars
 ///
@@ -26,3 +36,26 @@ pub fn get_armor_with_star(mut actor: &'_ str, star: i32) -> Cow<'_, str> {
 
     Cow::Borrowed(actor)
 }
+
+/// Get the number of stars of an armor, if it can be upgraded
+pub fn get_armor_star(actor: &str) -> Option<i32> {
+    let to_search = actor.strip_prefix("Armor_")?;
+    ARMOR_UPGRADE.iter().find_map(|armor_group| {
+        armor_group
+            .iter()
+            .position(|x| *x == to_search)
+            .map(|star| star as i32)
+    })
+}
+
+/// Get the cost to upgrade the armor to the number of stars (1-4),
+/// if the cost is known
+pub fn get_armor_upgrade_cost(actor: &str, star: i32) -> Option<&'static ArmorUpgradeCost> {
+    let to_search = actor.strip_prefix("Armor_")?;
+    let armor_group = ARMOR_UPGRADE.iter().find(|g| g.contains(&to_search))?;
+    let index = usize::try_from(star).ok()?.checked_sub(1)?;
+    let (_, costs) = ARMOR_UPGRADE_COST
+        .iter()
+        .find(|(armor, _)| *armor == armor_group[0])?;
+    costs.get(index)
+}
@@ -0,0 +1,25 @@
+// This file is auto-generated by scripts/build-armor-upgrade.py
+// DO NOT EDIT MANUALLY
+
+/// Cost to upgrade armor, by the 0 star armor. Each slice is to 1 star to 4 star
+#[rustfmt::skip]
+pub static ARMOR_UPGRADE_COST: &[(&str, [ArmorUpgradeCost; 4])] = &[
+    ("001_Head", [
+        ArmorUpgradeCost { materials: &[("Item_Enemy_00", 5)], rupees: 0 },
+        ArmorUpgradeCost { materials: &[("Item_Enemy_00", 8), ("Item_Enemy_01", 5)], rupees: 0 },
+        ArmorUpgradeCost { materials: &[("Item_Enemy_01", 10), ("Item_Enemy_02", 5)], rupees: 0 },
+        ArmorUpgradeCost { materials: &[("Item_Enemy_02", 15), ("Item_Ore_F", 15)], rupees: 0 },
+    ]),
+    ("001_Lower", [
+        ArmorUpgradeCost { materials: &[("Item_Enemy_00", 5)], rupees: 0 },
+        ArmorUpgradeCost { materials: &[("Item_Enemy_00", 8), ("Item_Enemy_01", 5)], rupees: 0 },
+        ArmorUpgradeCost { materials: &[("Item_Enemy_01", 10), ("Item_Enemy_02", 5)], rupees: 0 },
+        ArmorUpgradeCost { materials: &[("Item_Enemy_02", 15), ("Item_Ore_F", 15)], rupees: 0 },
+    ]),
+    ("001_Upper", [
+        ArmorUpgradeCost { materials: &[("Item_Enemy_00", 5)], rupees: 0 },
+        ArmorUpgradeCost { materials: &[("Item_Enemy_00", 8), ("Item_Enemy_01", 5)], rupees: 0 },
+        ArmorUpgradeCost { materials: &[("Item_Enemy_01", 10), ("Item_Enemy_02", 5)], rupees: 0 },
+        ArmorUpgradeCost { materials: &[("Item_Enemy_02", 15), ("Item_Ore_F", 15)], rupees: 0 },
+    ]),
+];
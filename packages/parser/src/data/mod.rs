@@ -1,5 +1,7 @@
 mod armor_upgrade;
-pub use armor_upgrade::get_armor_with_star;
+pub use armor_upgrade::{
+    ArmorUpgradeCost, get_armor_star, get_armor_upgrade_cost, get_armor_with_star,
+};
 mod item_name;
 pub use item_name::*;
 mod search_result;
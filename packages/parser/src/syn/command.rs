@@ -82,6 +82,14 @@ pub enum Command {
     /// `sell ITEMS`
     Sell(CmdSell),
 
+    // ==== great fairy ====
+    /// `upgrade ITEM`
+    Upgrade(CmdUpgrade),
+    /// `hestu CATEGORY [X times]`
+    Hestu(CmdHestu),
+    /// `pray heart|stamina [X times]`
+    Pray(CmdPray),
+
     // ==== saves/game state ====
     /// `save`
     Save(syn::KwSave),
@@ -364,6 +372,36 @@ pub struct CmdSell {
 
 ///////////////////////////////////////////////////////////
 
+/// `upgrade ITEM` - upgrade an armor to the next star at a Great Fairy
+#[derive_syntax]
+#[derive(Debug)]
+pub struct CmdUpgrade {
+    pub lit: syn::KwUpgrade,
+    pub item: syn::ItemOrCategory,
+}
+
+/// `hestu CATEGORY [X times]` - trade Korok Seeds for more slots in
+/// the weapon, bow or shield category
+#[derive_syntax]
+#[derive(Debug)]
+pub struct CmdHestu {
+    pub lit: syn::KwHestu,
+    pub category: syn::CategoryName,
+    pub times: tp::Option<syn::TimesClause>,
+}
+
+/// `pray heart|stamina [X times]` - trade Spirit Orbs for a Heart Container
+/// or Stamina Vessel at a Goddess Statue
+#[derive_syntax]
+#[derive(Debug)]
+pub struct CmdPray {
+    pub lit: syn::KwPray,
+    pub target: tp::String<syn::Word>,
+    pub times: tp::Option<syn::TimesClause>,
+}
+
+///////////////////////////////////////////////////////////
+
 /// `save-as NAME` - save the game to a named slot
 #[derive_syntax]
 #[derive(Debug)]
@@ -81,6 +81,10 @@ pub enum TT {
         KwBuy = "buy",
         KwSell = "sell",
 
+        KwUpgrade = "upgrade",
+        KwHestu = "hestu",
+        KwPray = "pray",
+
         KwSave = "save",
         KwSaveAs = "save-as",
         KwReload = "reload",
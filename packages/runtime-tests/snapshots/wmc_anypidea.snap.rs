@@ -2265,7 +2265,10 @@ game: (Running)
       [010]: (idx=10, actor=Weapon_Shield_040, value=1000, is_equipped=true, )
         weapon: (idx=0, modifier=none, )
 
------ Step[34]: buy 10 arrows
+----- Step[34]: !set-gdt <CurrentRupee>[s32=999]
+
+<same>
+----- Step[35]: buy 10 arrows
 
 game: (Running)
   screen: (Overworld)
@@ -2335,7 +2338,7 @@ game: (Running)
       [011]: (idx=11, actor=Obj_DungeonClearSeal, value=4, is_equipped=false, )
       [012]: (idx=12, actor=Obj_DungeonClearSeal, value=4, is_equipped=false, )
 
------ Step[35]: buy 4 razorshrooms
+----- Step[36]: buy 4 razorshrooms
 
 game: (Running)
   screen: (Overworld)
@@ -2408,7 +2411,7 @@ game: (Running)
       [012]: (idx=12, actor=Obj_DungeonClearSeal, value=4, is_equipped=false, )
       [013]: (idx=13, actor=Obj_DungeonClearSeal, value=4, is_equipped=false, )
 
------ Step[36]: !remove 4 razorshrooms
+----- Step[37]: !remove 4 razorshrooms
 
 game: (Running)
   screen: (Inventory)
@@ -2478,7 +2481,7 @@ game: (Running)
       [011]: (idx=11, actor=Obj_DungeonClearSeal, value=4, is_equipped=false, )
       [012]: (idx=12, actor=Obj_DungeonClearSeal, value=4, is_equipped=false, )
 
------ Step[37]: get 1 mighty-elixir
+----- Step[38]: get 1 mighty-elixir
 
 game: (Running)
   screen: (Overworld)
@@ -2553,7 +2556,7 @@ game: (Running)
       [012]: (idx=12, actor=Obj_DungeonClearSeal, value=4, is_equipped=false, )
       [013]: (idx=13, actor=Obj_DungeonClearSeal, value=4, is_equipped=false, )
 
------ Step[38]: eat 1 seared-steak 1 charred-pepper
+----- Step[39]: eat 1 seared-steak 1 charred-pepper
 
 game: (Running)
   screen: (Inventory)
@@ -2626,7 +2629,7 @@ game: (Running)
       [010]: (idx=10, actor=Obj_DungeonClearSeal, value=4, is_equipped=false, )
       [011]: (idx=11, actor=Obj_DungeonClearSeal, value=4, is_equipped=false, )
 
------ Step[39]: reload hc-autosave
+----- Step[40]: reload hc-autosave
 
 game: (Running)
   screen: (Overworld)
@@ -0,0 +1,77 @@
+//! Tests for aborting a run, including in the middle of a step
+use std::sync::Arc;
+use std::sync::atomic::AtomicBool;
+
+use cu::pre::*;
+use skybook_runtime::{Error, MaybeAborted, sim};
+
+use crate::script_tests::StubQuotedItemResolver;
+use crate::util::{TestGroup, run_test};
+
+pub fn run(runtime: Arc<sim::Runtime>) -> cu::Result<bool> {
+    cu::debug!("running abort tests");
+    let passed = cu::co::run(async move { run_tests(&runtime).await })?;
+    Ok(passed)
+}
+
+async fn run_tests(runtime: &sim::Runtime) -> cu::Result<bool> {
+    let mut tests = TestGroup::new("abort");
+    run_test!(tests, test_abort_mid_step(runtime).await);
+
+    Ok(tests.finish())
+}
+
+/// Abort the run right before a long step starts, so the processor
+/// stops in the middle of the step and the game crashes with the abort error.
+/// The partial state must not be kept as the result, or cached for the next run
+async fn test_abort_mid_step(runtime: &sim::Runtime) -> cu::Result<()> {
+    let script = "get 3 apple 2 banana\nsave-as abort-test\nreload abort-test\nget 1 apple";
+    let resolver = StubQuotedItemResolver(AtomicBool::new(false));
+    let parsed = skybook_parser::parse(&resolver, script).await;
+    if let Some(error) = parsed.errors.iter().find(|x| !x.is_warning) {
+        cu::bail!("parse error: {}", error.error);
+    }
+
+    let handle = Arc::new(sim::RunHandle::new());
+    let run = sim::Run::new(Arc::clone(&handle));
+    let mut notified = 0;
+    let result = run
+        .run_parsed_with_notify(&parsed, runtime, |_, _| {
+            notified += 1;
+            // notified before the `reload` step, which loads the whole save
+            if notified == 2 {
+                handle.abort();
+            }
+            async {}
+        })
+        .await;
+    if !matches!(result, MaybeAborted::Aborted) {
+        cu::bail!("expected the run to be aborted");
+    }
+
+    let run = sim::Run::new(Arc::new(sim::RunHandle::new()));
+    let output = match run.run_parsed(&parsed, runtime).await {
+        MaybeAborted::Ok(output) => output,
+        MaybeAborted::Aborted => cu::bail!("expected the second run to finish"),
+    };
+    if output.states.len() != parsed.steps.len() {
+        cu::bail!(
+            "expected {} states, got {}",
+            parsed.steps.len(),
+            output.states.len()
+        );
+    }
+    if let Some(error) = output
+        .errors
+        .iter()
+        .find(|x| matches!(x.error, Error::Crash))
+    {
+        cu::bail!("unexpected crash at {:?}", error.span);
+    }
+    for (i, state) in output.states.iter().enumerate() {
+        if !matches!(state.game, sim::Game::Running(_)) {
+            cu::bail!("expected the game to be running after step {i}");
+        }
+    }
+    Ok(())
+}
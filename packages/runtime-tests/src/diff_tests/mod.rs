@@ -0,0 +1,390 @@
+//! Differential tests against observations recorded from the real game
+//!
+//! A test is a script where each step can be followed by observation lines.
+//! Observation lines are comments in the script, so the test file itself
+//! is also a valid script:
+//!
+//! ```text
+//! get 2 apple 1 royal-claymore
+//! #> tabs: Sword Material
+//! #> [0:0] Weapon_Lsword_024 4000 equipped
+//! #> [1:0] Item_Fruit_A 2
+//! ```
+//!
+//! - `#> tabs: <types>` are the types of the tabs that are visible, in order
+//! - `#> [tab:slot] <actor> <value> [equipped]` is an item visible in the inventory.
+//!   If `equipped` is not specified, the item is expected to be not equipped
+//!
+//! If any item is observed after a step, all visible items in that step
+//! must be listed. Fields that cannot be observed on the console
+//! (for example, node addresses) are not compared.
+use std::path::Path;
+use std::sync::Arc;
+use std::sync::atomic::AtomicBool;
+
+use blueflame::game::PouchItemType;
+use cu::pre::*;
+use skybook_runtime::{MaybeAborted, iv, sim};
+
+use crate::script_tests::StubQuotedItemResolver;
+
+pub fn run(runtime: Arc<sim::Runtime>) -> cu::Result<bool> {
+    cu::debug!("running differential tests");
+
+    let mut test_names = vec![];
+    let test_dir = std::fs::read_dir("src/diff_tests").context("failed to read diff test dir")?;
+    for entry in test_dir {
+        let Ok(entry) = entry else {
+            continue;
+        };
+        let file_name = entry.file_name();
+        let file_name = file_name.to_string_lossy();
+        let Some(test_name) = file_name.strip_suffix(".txt") else {
+            continue;
+        };
+        test_names.push(test_name.to_string());
+    }
+    test_names.sort();
+
+    let total_count = test_names.len();
+    let passed_count = cu::co::run(async move { run_tests(runtime, test_names).await })
+        .context("there were failures running differential tests")?;
+
+    cu::info!("{passed_count}/{total_count} differential tests passed");
+
+    Ok(passed_count == total_count)
+}
+
+async fn run_tests(runtime: Arc<sim::Runtime>, test_names: Vec<String>) -> cu::Result<usize> {
+    let mut passed_count = 0;
+    for test in test_names {
+        let path = Path::new("src/diff_tests").join(format!("{test}.txt"));
+        let test_file = std::fs::read_to_string(&path).context("cannot read test file")?;
+        let (observed_count, mismatches) = match run_test(&runtime, &test_file).await {
+            Ok(x) => x,
+            Err(e) => {
+                cu::error!("FAIL {test} - {e}");
+                continue;
+            }
+        };
+        if mismatches.is_empty() {
+            cu::info!("PASS {test} - {observed_count} observed steps matched");
+            passed_count += 1;
+            continue;
+        }
+        let mismatched_steps = {
+            let mut steps = mismatches.iter().map(|x| x.step).collect::<Vec<_>>();
+            steps.dedup();
+            steps.len()
+        };
+        cu::error!(
+            "FAIL {test} - {}/{observed_count} observed steps matched",
+            observed_count - mismatched_steps
+        );
+        for mismatch in &mismatches {
+            cu::error!("  {mismatch}");
+        }
+    }
+
+    Ok(passed_count)
+}
+
+/// Run the test, returning the number of steps that have observations and
+/// the mismatches
+async fn run_test(
+    runtime: &sim::Runtime,
+    test_script: &str,
+) -> Result<(usize, Vec<Mismatch>), String> {
+    let resolver = StubQuotedItemResolver(AtomicBool::new(false));
+    let parsed = skybook_parser::parse(&resolver, test_script).await;
+    if resolver.0.load(std::sync::atomic::Ordering::Acquire) {
+        return Err("quoted item search not supported".to_string());
+    }
+    if let Some(error) = parsed.errors.iter().find(|x| !x.is_warning) {
+        return Err(format!("parse error: {}", error.error));
+    }
+    let step_positions = parsed.steps.iter().map(|x| x.pos()).collect::<Vec<_>>();
+    let observations = parse_observations(test_script, &step_positions)?;
+
+    let run = sim::Run::new(Arc::new(sim::RunHandle::new()));
+    let MaybeAborted::Ok(output) = run.run_parsed(&parsed, runtime).await else {
+        return Err("run was aborted".to_string());
+    };
+
+    let mut mismatches = vec![];
+    for observation in &observations {
+        let step = observation.step;
+        match output.get_pouch_list(step) {
+            Err(e) => mismatches.push(Mismatch {
+                step,
+                kind: MismatchKind::PouchError(e.to_string()),
+            }),
+            Ok(pouch) => compare_pouch(observation, &pouch, &mut mismatches),
+        }
+    }
+
+    Ok((observations.len(), mismatches))
+}
+
+/// Observations recorded after one step
+#[derive(Debug, Default)]
+struct Observation {
+    /// Index of the step
+    step: usize,
+    /// Types of the visible tabs
+    tabs: Option<Vec<String>>,
+    /// Visible items
+    items: Vec<ObservedItem>,
+}
+
+#[derive(Debug)]
+struct ObservedItem {
+    tab: i32,
+    slot: i32,
+    actor_name: String,
+    value: i32,
+    is_equipped: bool,
+}
+
+/// Parse the observation lines in the test script. `step_positions` are the
+/// byte positions of the steps in the script
+fn parse_observations(script: &str, step_positions: &[usize]) -> Result<Vec<Observation>, String> {
+    let mut observations: Vec<Observation> = vec![];
+    let mut pos = 0;
+    for (line_no, line) in script.split_inclusive('\n').enumerate() {
+        let line_pos = pos;
+        pos += line.len();
+        let Some(line) = line.trim().strip_prefix("#>") else {
+            continue;
+        };
+        let line = line.trim();
+        let line_no = line_no + 1;
+        let step = match step_positions.iter().rposition(|x| *x < line_pos) {
+            Some(step) => step,
+            None => return Err(format!("line {line_no}: observation before the first step")),
+        };
+        if observations.last().is_none_or(|x| x.step != step) {
+            observations.push(Observation {
+                step,
+                ..Default::default()
+            });
+        }
+        // unwrap: just pushed if empty
+        let observation = observations.last_mut().unwrap();
+        if let Some(tabs) = line.strip_prefix("tabs:") {
+            let tabs = tabs.split_whitespace().map(|x| x.to_string()).collect();
+            observation.tabs = Some(tabs);
+            continue;
+        }
+        let item = parse_observed_item(line)
+            .ok_or_else(|| format!("line {line_no}: invalid observation: {line}"))?;
+        observation.items.push(item);
+    }
+    Ok(observations)
+}
+
+fn parse_observed_item(line: &str) -> Option<ObservedItem> {
+    let line = line.strip_prefix('[')?;
+    let (pos, rest) = line.split_once(']')?;
+    let (tab, slot) = pos.split_once(':')?;
+    let mut parts = rest.split_whitespace();
+    let actor_name = parts.next()?.to_string();
+    let value = parts.next()?.parse().ok()?;
+    let is_equipped = match parts.next() {
+        None => false,
+        Some("equipped") => true,
+        Some(_) => return None,
+    };
+    if parts.next().is_some() {
+        return None;
+    }
+    Some(ObservedItem {
+        tab: tab.trim().parse().ok()?,
+        slot: slot.trim().parse().ok()?,
+        actor_name,
+        value,
+        is_equipped,
+    })
+}
+
+/// A difference between the observation and the simulator
+#[derive(Debug)]
+pub struct Mismatch {
+    /// Index of the step
+    pub step: usize,
+    pub kind: MismatchKind,
+}
+
+#[derive(Debug)]
+pub enum MismatchKind {
+    /// The pouch cannot be viewed in the simulator
+    PouchError(String),
+    /// The tabs are corrupted in the simulator, so visible tabs cannot be compared
+    InvalidTabs,
+    /// Visible tabs are different
+    Tabs {
+        expected: Vec<String>,
+        actual: Vec<String>,
+    },
+    /// An item is observed, but the slot is empty in the simulator
+    MissingItem {
+        tab: i32,
+        slot: i32,
+        expected: String,
+    },
+    /// An item is visible in the simulator, but not observed
+    ExtraItem { tab: i32, slot: i32, actual: String },
+    /// The item in the slot is different
+    ActorName {
+        tab: i32,
+        slot: i32,
+        expected: String,
+        actual: String,
+    },
+    /// The value (count or durability) is different
+    Value {
+        tab: i32,
+        slot: i32,
+        expected: i32,
+        actual: i32,
+    },
+    /// The equipped flag is different
+    Equipped {
+        tab: i32,
+        slot: i32,
+        expected: bool,
+        actual: bool,
+    },
+}
+
+impl std::fmt::Display for Mismatch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let step = self.step;
+        write!(f, "Step[{step}]: ")?;
+        match &self.kind {
+            MismatchKind::PouchError(e) => write!(f, "cannot view pouch: {e}"),
+            MismatchKind::InvalidTabs => write!(f, "tabs are invalid in the simulator"),
+            MismatchKind::Tabs { expected, actual } => {
+                write!(f, "tabs: expected {expected:?}, got {actual:?}")
+            }
+            MismatchKind::MissingItem {
+                tab,
+                slot,
+                expected,
+            } => write!(f, "[{tab}:{slot}]: expected {expected}, got nothing"),
+            MismatchKind::ExtraItem { tab, slot, actual } => {
+                write!(f, "[{tab}:{slot}]: expected nothing, got {actual}")
+            }
+            MismatchKind::ActorName {
+                tab,
+                slot,
+                expected,
+                actual,
+            } => write!(f, "[{tab}:{slot}]: expected {expected}, got {actual}"),
+            MismatchKind::Value {
+                tab,
+                slot,
+                expected,
+                actual,
+            } => write!(f, "[{tab}:{slot}]: expected value {expected}, got {actual}"),
+            MismatchKind::Equipped {
+                tab,
+                slot,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "[{tab}:{slot}]: expected equipped={expected}, got equipped={actual}"
+            ),
+        }
+    }
+}
+
+fn compare_pouch(observation: &Observation, pouch: &iv::PouchList, out: &mut Vec<Mismatch>) {
+    let step = observation.step;
+    macro_rules! mismatch {
+        ($kind:expr) => {
+            out.push(Mismatch { step, kind: $kind })
+        };
+    }
+    if let Some(expected) = &observation.tabs {
+        if !pouch.are_tabs_valid {
+            mismatch!(MismatchKind::InvalidTabs);
+        } else {
+            let actual = pouch
+                .tabs
+                .iter()
+                .map(|x| PouchItemType::describe(x.tab_type))
+                .collect::<Vec<_>>();
+            if &actual != expected {
+                mismatch!(MismatchKind::Tabs {
+                    expected: expected.clone(),
+                    actual,
+                });
+            }
+        }
+    }
+    if observation.items.is_empty() {
+        return;
+    }
+    let visible_items = pouch
+        .items
+        .iter()
+        .filter(|x| x.accessible)
+        .collect::<Vec<_>>();
+    for expected in &observation.items {
+        let (tab, slot) = (expected.tab, expected.slot);
+        let Some(actual) = visible_items
+            .iter()
+            .find(|x| x.tab_idx == tab && x.tab_slot == slot)
+        else {
+            mismatch!(MismatchKind::MissingItem {
+                tab,
+                slot,
+                expected: expected.actor_name.clone(),
+            });
+            continue;
+        };
+        let actual = &actual.common;
+        if actual.actor_name != expected.actor_name {
+            mismatch!(MismatchKind::ActorName {
+                tab,
+                slot,
+                expected: expected.actor_name.clone(),
+                actual: actual.actor_name.clone(),
+            });
+            // other fields are meaningless if the item is different
+            continue;
+        }
+        if actual.value != expected.value {
+            mismatch!(MismatchKind::Value {
+                tab,
+                slot,
+                expected: expected.value,
+                actual: actual.value,
+            });
+        }
+        if actual.is_equipped != expected.is_equipped {
+            mismatch!(MismatchKind::Equipped {
+                tab,
+                slot,
+                expected: expected.is_equipped,
+                actual: actual.is_equipped,
+            });
+        }
+    }
+    for actual in visible_items {
+        let (tab, slot) = (actual.tab_idx, actual.tab_slot);
+        if !observation
+            .items
+            .iter()
+            .any(|x| x.tab == tab && x.slot == slot)
+        {
+            mismatch!(MismatchKind::ExtraItem {
+                tab,
+                slot,
+                actual: actual.common.actor_name.clone(),
+            });
+        }
+    }
+}
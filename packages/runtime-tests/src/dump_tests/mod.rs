@@ -0,0 +1,214 @@
+//! Tests for starting a simulation from a PMDM memory dump
+use std::sync::Arc;
+use std::sync::atomic::AtomicBool;
+
+use blueflame::game::{PauseMenuDataMgr, singleton_instance};
+use blueflame::linker;
+use blueflame::memory::Ptr;
+use blueflame::processor::Process;
+use cu::pre::*;
+use skybook_runtime::{MaybeAborted, exec, iv, sim};
+
+use crate::script_tests::StubQuotedItemResolver;
+use crate::util::{self, TestGroup, run_test};
+
+pub fn run(runtime: Arc<sim::Runtime>, image_file: &str) -> cu::Result<bool> {
+    cu::debug!("running dump tests");
+    let image = cu::fs::read(image_file).context("failed to read BFI")?;
+    let passed = cu::co::run(async move { run_tests(&runtime, &image).await })?;
+    Ok(passed)
+}
+
+async fn run_tests(runtime: &sim::Runtime, image: &[u8]) -> cu::Result<bool> {
+    let mut tests = TestGroup::new("dump");
+    run_test!(tests, test_round_trip_same_base(runtime).await);
+    run_test!(tests, test_round_trip_different_base(runtime, image).await);
+    run_test!(tests, test_run_from_dump(runtime).await);
+    run_test!(tests, test_dump_without_pmdm(runtime).await);
+
+    Ok(tests.finish())
+}
+
+const SCRIPT: &str = "get 5 apple 1 pot-lid 1 hylian-hood 1 trav-bow\nequip pot-lid";
+
+async fn test_round_trip_same_base(runtime: &sim::Runtime) -> cu::Result<()> {
+    let process = process_after(runtime, SCRIPT).await?;
+    let (dump, expected) = dump_pmdm(&process)?;
+    let initial_process = runtime
+        .initial_process()
+        .context("failed to get initial process")?;
+    let state = sim::State::from_pmdm_dump(initial_process, &dump.as_dump())?;
+    let actual = pouch_view(&state)?;
+    compare_views(&expected, dump.pmdm_addr, &actual, dump.pmdm_addr)
+}
+
+async fn test_round_trip_different_base(runtime: &sim::Runtime, image: &[u8]) -> cu::Result<()> {
+    let process = process_after(runtime, SCRIPT).await?;
+    let (dump, expected) = dump_pmdm(&process)?;
+
+    // load the dump into a process where PMDM is somewhere else
+    let params = sim::RuntimeInitParams {
+        pmdm_addr: "0x0000003333300000".to_string(),
+        ..util::test_init_params()
+    };
+    let other_runtime = sim::Runtime::new(exec::Spawner::new()?);
+    other_runtime
+        .init(image, 1, Some(&params))
+        .context("failed to initialize runtime")?;
+    let initial_process = other_runtime
+        .initial_process()
+        .context("failed to get initial process")?;
+    let state = sim::State::from_pmdm_dump(initial_process, &dump.as_dump())?;
+    let sim::Game::Running(game) = &state.game else {
+        cu::bail!("expected the game to be running after loading the dump");
+    };
+    let other_pmdm = singleton_instance!(pmdm(game.process.memory()))?.to_raw();
+    cu::ensure!(
+        other_pmdm != dump.pmdm_addr,
+        "expected PMDM to be at a different address"
+    )?;
+
+    let actual = pouch_view(&state)?;
+    compare_views(&expected, dump.pmdm_addr, &actual, other_pmdm)
+}
+
+async fn test_run_from_dump(runtime: &sim::Runtime) -> cu::Result<()> {
+    let process = process_after(runtime, SCRIPT).await?;
+    let (dump, _) = dump_pmdm(&process)?;
+    let initial_process = runtime
+        .initial_process()
+        .context("failed to get initial process")?;
+    let state = sim::State::from_pmdm_dump(initial_process, &dump.as_dump())?;
+
+    let resolver = StubQuotedItemResolver(AtomicBool::new(false));
+    let parsed = skybook_parser::parse(&resolver, "get 2 apple").await;
+    let run = sim::Run::new(Arc::new(sim::RunHandle::new()));
+    let MaybeAborted::Ok(output) = run.run_parsed_from_state(&parsed, runtime, state).await else {
+        cu::bail!("run was aborted");
+    };
+    if let Some(error) = output.errors.first() {
+        cu::bail!("unexpected error: {}", error.error);
+    }
+    let Some(state) = output.states.first() else {
+        cu::bail!("expected the state after the step");
+    };
+    let view = pouch_view(state)?;
+    let apples = view
+        .items
+        .iter()
+        .find(|x| x.common.actor_name == "Item_Fruit_A")
+        .map(|x| x.common.value);
+    // the apples from the dump are kept
+    cu::ensure!(apples == Some(7), "expected 7 apples, got {apples:?}")?;
+    Ok(())
+}
+
+async fn test_dump_without_pmdm(runtime: &sim::Runtime) -> cu::Result<()> {
+    let bytes = vec![0u8; 0x100];
+    let dump = linker::PmdmDump {
+        bytes: &bytes,
+        dump_start: 0x1000,
+        pmdm_addr: 0x1000,
+        main_start: 0,
+    };
+    let initial_process = runtime
+        .initial_process()
+        .context("failed to get initial process")?;
+    match sim::State::from_pmdm_dump(initial_process, &dump) {
+        Err(linker::Error::BadDump(_)) => Ok(()),
+        Err(e) => cu::bail!("expected BadDump, got: {e}"),
+        Ok(_) => cu::bail!("expected loading the dump to fail"),
+    }
+}
+
+/// Bytes of a PMDM dumped from a process, and the addresses in that process
+struct OwnedDump {
+    bytes: Vec<u8>,
+    pmdm_addr: u64,
+    main_start: u64,
+}
+
+impl OwnedDump {
+    fn as_dump(&self) -> linker::PmdmDump<'_> {
+        linker::PmdmDump {
+            bytes: &self.bytes,
+            dump_start: self.pmdm_addr,
+            pmdm_addr: self.pmdm_addr,
+            main_start: self.main_start,
+        }
+    }
+}
+
+/// Dump the PMDM from the process like from a console, and read the pouch view
+fn dump_pmdm(process: &Process) -> cu::Result<(OwnedDump, iv::PouchList)> {
+    let memory = process.memory();
+    let pmdm_addr = singleton_instance!(pmdm(memory))?.to_raw();
+    let mut bytes = vec![0u8; PauseMenuDataMgr::SIZE as usize];
+    Ptr!(<u8>(pmdm_addr)).load_slice(&mut bytes, memory)?;
+    let view = sim::view::extract_pouch_view(process, &sim::GameSystems::default())?;
+    let dump = OwnedDump {
+        bytes,
+        pmdm_addr,
+        main_start: process.main_start(),
+    };
+    Ok((dump, view))
+}
+
+/// Get the process of the running game after the script
+async fn process_after(runtime: &sim::Runtime, script: &str) -> cu::Result<Process> {
+    let resolver = StubQuotedItemResolver(AtomicBool::new(false));
+    let parsed = skybook_parser::parse(&resolver, script).await;
+    if let Some(error) = parsed.errors.iter().find(|x| !x.is_warning) {
+        cu::bail!("parse error: {}", error.error);
+    }
+    let run = sim::Run::new(Arc::new(sim::RunHandle::new()));
+    let MaybeAborted::Ok(output) = run.run_parsed(&parsed, runtime).await else {
+        cu::bail!("run was aborted");
+    };
+    match output.states.last().map(|x| &x.game) {
+        Some(sim::Game::Running(game)) => Ok(game.process.clone()),
+        _ => cu::bail!("expected the game to be running after the script"),
+    }
+}
+
+fn pouch_view(state: &sim::State) -> cu::Result<iv::PouchList> {
+    let sim::Game::Running(game) = &state.game else {
+        cu::bail!("expected the game to be running");
+    };
+    Ok(sim::view::extract_pouch_view(
+        &game.process,
+        &sim::GameSystems::default(),
+    )?)
+}
+
+/// Compare the pouch views, with the node pointers relative to the PMDM
+fn compare_views(
+    expected: &iv::PouchList,
+    expected_pmdm: u64,
+    actual: &iv::PouchList,
+    actual_pmdm: u64,
+) -> cu::Result<()> {
+    let expected = relative_to_pmdm(expected.clone(), expected_pmdm);
+    let actual = relative_to_pmdm(actual.clone(), actual_pmdm);
+    if expected != actual {
+        cu::bail!(
+            "pouch is different after loading the dump\nexpected: {expected:#?}\nactual: {actual:#?}"
+        );
+    }
+    Ok(())
+}
+
+fn relative_to_pmdm(mut view: iv::PouchList, pmdm: u64) -> iv::PouchList {
+    let relative = |ptr: iv::Pointer| -> iv::Pointer {
+        match ptr.as_u64() {
+            0 => ptr,
+            x => x.wrapping_sub(pmdm).into(),
+        }
+    };
+    for item in &mut view.items {
+        item.node_addr = relative(item.node_addr);
+        item.node_prev = relative(item.node_prev);
+        item.node_next = relative(item.node_next);
+    }
+    view
+}
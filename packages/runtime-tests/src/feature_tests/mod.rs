@@ -0,0 +1,130 @@
+//! Tests for toggling simulator features, with `!system` commands
+//! and with the `enable-features`/`disable-features` keys in the `env` block
+use std::sync::Arc;
+use std::sync::atomic::AtomicBool;
+
+use blueflame::env::{Feature, FeatureSet};
+use cu::pre::*;
+use skybook_runtime::{Error, MaybeAborted, RuntimeInitError, exec, sim};
+
+use crate::script_tests::StubQuotedItemResolver;
+use crate::util::{self, TestGroup, run_test};
+
+pub fn run(runtime: Arc<sim::Runtime>, image_file: &str) -> cu::Result<bool> {
+    cu::debug!("running feature tests");
+    let image = cu::fs::read(image_file).context("failed to read BFI")?;
+    let passed = cu::co::run(async move { run_tests(&runtime, &image).await })?;
+    Ok(passed)
+}
+
+async fn run_tests(runtime: &sim::Runtime, image: &[u8]) -> cu::Result<bool> {
+    let mut tests = TestGroup::new("feature");
+    run_test!(tests, test_system_toggle_feature(runtime).await);
+    run_test!(tests, test_system_unknown_feature(runtime).await);
+    run_test!(tests, test_env_features(image).await);
+    run_test!(tests, test_env_unknown_feature(image).await);
+
+    Ok(tests.finish())
+}
+
+async fn test_system_toggle_feature(runtime: &sim::Runtime) -> cu::Result<()> {
+    let states = run_script(
+        runtime,
+        "!system [enable-feature=mem-track-free]\n!system [disable-feature=mem-track-free]",
+    )
+    .await?
+    .states;
+    if !features_at(&states, 0)?.contains(Feature::mem_track_free) {
+        cu::bail!("expected mem-track-free to be enabled");
+    }
+    if features_at(&states, 1)?.contains(Feature::mem_track_free) {
+        cu::bail!("expected mem-track-free to be disabled");
+    }
+    Ok(())
+}
+
+async fn test_system_unknown_feature(runtime: &sim::Runtime) -> cu::Result<()> {
+    let output = run_script(runtime, "!system [enable-feature=not-a-feature]").await?;
+    if !output
+        .errors
+        .iter()
+        .any(|x| matches!(&x.error, Error::UnknownFeature(name) if name == "not-a-feature"))
+    {
+        cu::bail!("expected an UnknownFeature error");
+    }
+    let initial = runtime
+        .initial_process()
+        .context("failed to get initial process")?
+        .features();
+    if features_at(&output.states, 0)? != initial {
+        cu::bail!("expected the features to be unchanged");
+    }
+    Ok(())
+}
+
+async fn test_env_features(image: &[u8]) -> cu::Result<()> {
+    let params = sim::RuntimeInitParams {
+        enable_features: vec![
+            "mem-track-free".to_string(),
+            " limited-block-count".to_string(),
+        ],
+        // disabling is applied after enabling
+        disable_features: vec![
+            "check-stack-corruption".to_string(),
+            "limited-block-count".to_string(),
+        ],
+        ..util::test_init_params()
+    };
+    let runtime = sim::Runtime::new(exec::Spawner::new()?);
+    runtime
+        .init(image, 1, Some(&params))
+        .context("failed to initialize runtime")?;
+    let features = runtime
+        .initial_process()
+        .context("failed to get initial process")?
+        .features();
+    if !features.contains(Feature::mem_track_free) {
+        cu::bail!("expected mem-track-free to be enabled");
+    }
+    if features.contains(Feature::check_stack_corruption) {
+        cu::bail!("expected check-stack-corruption to be disabled");
+    }
+    if features.contains(Feature::limited_block_count) {
+        cu::bail!("expected limited-block-count to be disabled");
+    }
+    Ok(())
+}
+
+async fn test_env_unknown_feature(image: &[u8]) -> cu::Result<()> {
+    let params = sim::RuntimeInitParams {
+        enable_features: vec!["not-a-feature".to_string()],
+        ..util::test_init_params()
+    };
+    let runtime = sim::Runtime::new(exec::Spawner::new()?);
+    match runtime.init(image, 1, Some(&params)) {
+        Err(RuntimeInitError::UnknownFeature(name)) if name == "not-a-feature" => Ok(()),
+        Err(e) => cu::bail!("expected UnknownFeature, got: {e}"),
+        Ok(_) => cu::bail!("expected the initialization to fail"),
+    }
+}
+
+async fn run_script(runtime: &sim::Runtime, script: &str) -> cu::Result<sim::RunOutput> {
+    let resolver = StubQuotedItemResolver(AtomicBool::new(false));
+    let parsed = skybook_parser::parse(&resolver, script).await;
+    if let Some(error) = parsed.errors.iter().find(|x| !x.is_warning) {
+        cu::bail!("parse error: {}", error.error);
+    }
+    let run = sim::Run::new(Arc::new(sim::RunHandle::new()));
+    match run.run_parsed(&parsed, runtime).await {
+        MaybeAborted::Ok(output) => Ok(output),
+        MaybeAborted::Aborted => cu::bail!("run was aborted"),
+    }
+}
+
+/// Get the features of the running game after the step
+fn features_at(states: &[sim::State], step: usize) -> cu::Result<FeatureSet> {
+    match states.get(step).map(|x| &x.game) {
+        Some(sim::Game::Running(game)) => Ok(game.process.features()),
+        _ => cu::bail!("expected the game to be running after step {step}"),
+    }
+}
@@ -0,0 +1,60 @@
+//! Tests for the Korok Seeds needed for pouch upgrades from Hestu
+use skybook_parser::cir::Category;
+use skybook_runtime::sim::actions::hestu_upgrade_cost;
+
+use crate::util::{TestGroup, run_test};
+
+pub fn run() -> cu::Result<bool> {
+    cu::debug!("running hestu tests");
+    let mut tests = TestGroup::new("hestu");
+    run_test!(tests, test_first_upgrade());
+    run_test!(tests, test_specific_costs());
+    run_test!(tests, test_max_upgrade());
+    run_test!(tests, test_total_cost());
+
+    Ok(tests.finish())
+}
+
+fn test_first_upgrade() -> cu::Result<()> {
+    cu::ensure!(hestu_upgrade_cost(Category::Weapon, 8) == Some(1))?;
+    cu::ensure!(hestu_upgrade_cost(Category::Bow, 5) == Some(1))?;
+    cu::ensure!(hestu_upgrade_cost(Category::Shield, 4) == Some(1))?;
+    // fewer slots than initial is the same as no upgrades
+    cu::ensure!(hestu_upgrade_cost(Category::Weapon, 0) == Some(1))?;
+    Ok(())
+}
+
+fn test_specific_costs() -> cu::Result<()> {
+    // cost only depends on the upgrades done in the same pouch
+    cu::ensure!(hestu_upgrade_cost(Category::Weapon, 11) == Some(5))?;
+    cu::ensure!(hestu_upgrade_cost(Category::Weapon, 19) == Some(40))?;
+    cu::ensure!(hestu_upgrade_cost(Category::Bow, 12) == Some(20))?;
+    cu::ensure!(hestu_upgrade_cost(Category::Bow, 13) == Some(30))?;
+    cu::ensure!(hestu_upgrade_cost(Category::Shield, 13) == Some(10))?;
+    cu::ensure!(hestu_upgrade_cost(Category::Shield, 19) == Some(20))?;
+    Ok(())
+}
+
+fn test_max_upgrade() -> cu::Result<()> {
+    cu::ensure!(hestu_upgrade_cost(Category::Weapon, 20).is_none())?;
+    cu::ensure!(hestu_upgrade_cost(Category::Bow, 14).is_none())?;
+    cu::ensure!(hestu_upgrade_cost(Category::Shield, 20).is_none())?;
+    cu::ensure!(hestu_upgrade_cost(Category::Armor, 0).is_none())?;
+    Ok(())
+}
+
+fn test_total_cost() -> cu::Result<()> {
+    let total = |category: Category, initial: i32| {
+        (initial..)
+            .map_while(|slots| hestu_upgrade_cost(category, slots))
+            .sum::<usize>()
+    };
+    let weapon = total(Category::Weapon, 8);
+    let bow = total(Category::Bow, 5);
+    let shield = total(Category::Shield, 4);
+    cu::ensure!(weapon == 193, "weapon total: {weapon}")?;
+    cu::ensure!(bow == 93, "bow total: {bow}")?;
+    cu::ensure!(shield == 155, "shield total: {shield}")?;
+    cu::ensure!(weapon + bow + shield == 441)?;
+    Ok(())
+}
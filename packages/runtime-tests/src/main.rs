@@ -1,9 +1,17 @@
 use std::path::Path;
+use std::sync::Arc;
 
 use cu::pre::*;
 
+mod abort_tests;
+mod diff_tests;
+mod dump_tests;
+mod feature_tests;
+mod hestu_tests;
 mod linker_tests;
+mod save_tests;
 mod script_tests;
+mod search_tests;
 mod util;
 
 /// Skybook runtime testing framework
@@ -49,14 +57,42 @@ fn main(args: Args) -> cu::Result<()> {
         cu::bail!("linker tests failed, not executing further tests");
     }
     let has_only = args.only.is_some();
-    let script_test_passed = script_tests::run(runtime, args.refresh, args.only)?;
+    let script_test_passed = script_tests::run(Arc::clone(&runtime), args.refresh, args.only)?;
     if !script_test_passed {
         cu::bail!("script tests failed");
     }
     if has_only {
-        cu::info!("not collecting extra info since --only was specified");
+        cu::info!("not running differential tests since --only was specified");
         return Ok(());
     }
+    let diff_test_passed = diff_tests::run(Arc::clone(&runtime))?;
+    if !diff_test_passed {
+        cu::bail!("differential tests failed");
+    }
+    let search_test_passed = search_tests::run(Arc::clone(&runtime))?;
+    if !search_test_passed {
+        cu::bail!("search tests failed");
+    }
+    let feature_test_passed = feature_tests::run(Arc::clone(&runtime), image_file)?;
+    if !feature_test_passed {
+        cu::bail!("feature tests failed");
+    }
+    let dump_test_passed = dump_tests::run(Arc::clone(&runtime), image_file)?;
+    if !dump_test_passed {
+        cu::bail!("dump tests failed");
+    }
+    let abort_test_passed = abort_tests::run(runtime)?;
+    if !abort_test_passed {
+        cu::bail!("abort tests failed");
+    }
+    let save_test_passed = save_tests::run()?;
+    if !save_test_passed {
+        cu::bail!("save tests failed");
+    }
+    let hestu_test_passed = hestu_tests::run()?;
+    if !hestu_test_passed {
+        cu::bail!("hestu tests failed");
+    }
     if !cfg!(feature = "trace-memory") {
         cu::bail!(
             "The tests always fail when trace-memory is not enabled to ensure it's not accidentally disabled"
@@ -0,0 +1,132 @@
+//! Tests for comparing the items in a save with the current GDT
+use skybook_runtime::iv::{self, GdtItemDiff};
+use skybook_runtime::sim::view::diff_gdt_items;
+
+use crate::util::{TestGroup, run_test};
+
+pub fn run() -> cu::Result<bool> {
+    cu::debug!("running save tests");
+    let mut tests = TestGroup::new("save");
+    run_test!(tests, test_diff_same_items_in_different_slots());
+    run_test!(tests, test_diff_changed_value());
+    run_test!(tests, test_diff_added_and_removed());
+    run_test!(tests, test_diff_by_metadata());
+    run_test!(tests, test_diff_duplicates());
+
+    Ok(tests.finish())
+}
+
+fn item(idx: u32, actor_name: &str, value: i32) -> iv::GdtItem {
+    iv::GdtItem {
+        common: iv::CommonItem {
+            actor_name: actor_name.to_string(),
+            value,
+            is_equipped: false,
+        },
+        idx,
+        data: iv::GdtItemData::None,
+    }
+}
+
+fn sword(idx: u32, actor_name: &str, data_idx: u32, modifier_value: i32) -> iv::GdtItem {
+    iv::GdtItem {
+        data: iv::GdtItemData::Sword {
+            idx: data_idx,
+            info: iv::WeaponModifier {
+                flag: 1,
+                value: modifier_value,
+            },
+        },
+        ..item(idx, actor_name, 3000)
+    }
+}
+
+fn check(
+    save: &[iv::GdtItem],
+    current: &[iv::GdtItem],
+    expected_diffs: &[GdtItemDiff],
+    expected_removed: &[u32],
+) -> cu::Result<()> {
+    let (diffs, removed) = diff_gdt_items(save, current);
+    if diffs != expected_diffs {
+        cu::bail!("expected diffs {expected_diffs:?}, got {diffs:?}");
+    }
+    if removed != expected_removed {
+        cu::bail!("expected removed {expected_removed:?}, got {removed:?}");
+    }
+    Ok(())
+}
+
+/// An item that only moved to a different slot is the same
+fn test_diff_same_items_in_different_slots() -> cu::Result<()> {
+    let save = [item(0, "Item_Fruit_A", 5), item(1, "Item_Fruit_B", 3)];
+    let current = [item(0, "Item_Fruit_B", 3), item(1, "Item_Fruit_A", 5)];
+    check(
+        &save,
+        &current,
+        &[GdtItemDiff::Same, GdtItemDiff::Same],
+        &[],
+    )
+}
+
+fn test_diff_changed_value() -> cu::Result<()> {
+    let save = [item(0, "Item_Fruit_A", 5), item(1, "Item_Fruit_B", 3)];
+    let current = [item(0, "Item_Fruit_A", 5), item(1, "Item_Fruit_B", 1)];
+    check(
+        &save,
+        &current,
+        &[GdtItemDiff::Same, GdtItemDiff::Changed],
+        &[],
+    )
+}
+
+/// Removing an item in the middle doesn't make the slots after it different
+fn test_diff_added_and_removed() -> cu::Result<()> {
+    let save = [
+        item(0, "Item_Fruit_A", 5),
+        item(1, "Item_Fruit_B", 3),
+        item(2, "Item_Fruit_C", 1),
+    ];
+    let current = [
+        item(0, "Item_Fruit_A", 5),
+        item(1, "Item_Fruit_C", 1),
+        item(2, "Item_Fruit_D", 2),
+    ];
+    check(
+        &save,
+        &current,
+        &[GdtItemDiff::Same, GdtItemDiff::Added, GdtItemDiff::Same],
+        &[2],
+    )
+}
+
+/// Weapons with different modifiers are different items, but the
+/// index in the modifier arrays is only the position
+fn test_diff_by_metadata() -> cu::Result<()> {
+    let save = [
+        sword(0, "Weapon_Sword_001", 0, 10),
+        sword(1, "Weapon_Sword_001", 1, 20),
+    ];
+    let current = [
+        sword(0, "Weapon_Sword_001", 0, 20),
+        sword(1, "Weapon_Sword_001", 1, 30),
+    ];
+    check(
+        &save,
+        &current,
+        &[GdtItemDiff::Added, GdtItemDiff::Same],
+        &[1],
+    )
+}
+
+/// Duplicates are matched one to one, preferring the exact same item
+fn test_diff_duplicates() -> cu::Result<()> {
+    let save = [item(0, "Item_Fruit_A", 5), item(1, "Item_Fruit_A", 3)];
+    let current = [item(0, "Item_Fruit_A", 3)];
+    check(
+        &save,
+        &current,
+        &[GdtItemDiff::Added, GdtItemDiff::Same],
+        &[],
+    )
+}
@@ -113,7 +113,7 @@ async fn run_tests(
     Ok(passed_count)
 }
 
-struct StubQuotedItemResolver(AtomicBool);
+pub struct StubQuotedItemResolver(pub AtomicBool);
 impl cir::QuotedItemResolver for StubQuotedItemResolver {
     type Future = cu::BoxedFuture<Option<cir::ResolvedItem>>;
 
@@ -196,26 +196,40 @@ async fn run_test(
     new_snapshot += "\n}\n";
 
     let snapshot_file_path = PathBuf::from(format!("snapshots/{test_name}.snap.rs"));
-    if refresh || !snapshot_file_path.exists() {
+    if refresh {
         std::fs::write(snapshot_file_path, new_snapshot).context("failed to write snapshot")?;
         cu::info!("UPDATE {test_name}");
         return Ok(true);
     }
 
+    // a missing snapshot is a failure, otherwise a new test
+    // would pass without checking anything until the snapshot is committed
+    if !snapshot_file_path.exists() {
+        cu::error!("FAIL {test_name} - snapshot is missing, run with --refresh to create it");
+        write_wip_snapshot(test_name, &new_snapshot)?;
+        return Ok(false);
+    }
+
     let old_snapshot_content =
         std::fs::read_to_string(snapshot_file_path).context("failed to read snapshot")?;
 
     if old_snapshot_content != new_snapshot {
         cu::error!("FAIL {test_name}");
-        let wip_dir = Path::new("snapshots/wip");
-        if !wip_dir.exists() {
-            std::fs::create_dir_all(wip_dir).context("cannot create wip directory")?;
-        }
-        std::fs::write(format!("snapshots/wip/{test_name}.snap.rs"), new_snapshot)
-            .context("cannot write wip snapshot")?;
+        write_wip_snapshot(test_name, &new_snapshot)?;
         return Ok(false);
     }
 
     cu::info!("PASS {test_name}");
     Ok(true)
 }
+
+/// Write the snapshot to `snapshots/wip` for comparing with the old snapshot
+fn write_wip_snapshot(test_name: &str, snapshot: &str) -> cu::Result<()> {
+    let wip_dir = Path::new("snapshots/wip");
+    if !wip_dir.exists() {
+        std::fs::create_dir_all(wip_dir).context("cannot create wip directory")?;
+    }
+    std::fs::write(format!("snapshots/wip/{test_name}.snap.rs"), snapshot)
+        .context("cannot write wip snapshot")?;
+    Ok(())
+}
@@ -0,0 +1,144 @@
+//! Tests for the route search
+use std::sync::Arc;
+use std::sync::atomic::AtomicBool;
+
+use cu::pre::*;
+use skybook_parser::cir;
+use skybook_runtime::{MaybeAborted, iv, sim};
+
+use crate::script_tests::StubQuotedItemResolver;
+use crate::util::{TestGroup, run_test};
+
+pub fn run(runtime: Arc<sim::Runtime>) -> cu::Result<bool> {
+    cu::debug!("running search tests");
+    let passed = cu::co::run(async move { run_tests(&runtime).await })?;
+    Ok(passed)
+}
+
+async fn run_tests(runtime: &sim::Runtime) -> cu::Result<bool> {
+    let mut tests = TestGroup::new("search");
+    run_test!(tests, test_initial_reached(runtime).await);
+    run_test!(tests, test_shortest_route(runtime).await);
+    run_test!(tests, test_unreachable(runtime).await);
+    run_test!(tests, test_aborted(runtime).await);
+
+    Ok(tests.finish())
+}
+
+async fn test_initial_reached(runtime: &sim::Runtime) -> cu::Result<()> {
+    let params = sim::SearchParams {
+        candidates: parse_commands("get 1 apple").await?,
+        ..Default::default()
+    };
+    let output = search(runtime, &params, |pouch: &iv::PouchList, _: &iv::Gdt| {
+        pouch.items.is_empty()
+    })
+    .await?;
+    if output.routes.len() != 1 {
+        cu::bail!("expected 1 route");
+    }
+    if !output.routes[0].commands.is_empty() {
+        cu::bail!("expected the route to be empty");
+    }
+    Ok(())
+}
+
+async fn test_shortest_route(runtime: &sim::Runtime) -> cu::Result<()> {
+    let params = sim::SearchParams {
+        candidates: parse_commands("get 1 apple\nget 1 banana\nget 2 apple").await?,
+        max_depth: 3,
+        ..Default::default()
+    };
+    let output = search(runtime, &params, |pouch: &iv::PouchList, _: &iv::Gdt| {
+        count_item(pouch, "Item_Fruit_A") == 3
+    })
+    .await?;
+    if output.routes.len() != 1 {
+        cu::bail!("expected 1 route");
+    }
+    let route = &output.routes[0];
+    if route.commands.len() != 2 {
+        cu::bail!("expected a route of 2 steps, got:\n{}", route.to_script());
+    }
+    if output.visited <= 1 {
+        cu::bail!("expected more than 1 state to be visited");
+    }
+    Ok(())
+}
+
+async fn test_unreachable(runtime: &sim::Runtime) -> cu::Result<()> {
+    let params = sim::SearchParams {
+        candidates: parse_commands("get 1 apple").await?,
+        max_depth: 2,
+        ..Default::default()
+    };
+    let output = search(runtime, &params, |pouch: &iv::PouchList, _: &iv::Gdt| {
+        count_item(pouch, "Item_Fruit_A") == 3
+    })
+    .await?;
+    if !output.routes.is_empty() {
+        cu::bail!("expected no routes");
+    }
+    // initial, 1 apple and 2 apples
+    if output.visited != 3 {
+        cu::bail!("expected 3 states to be visited, got {}", output.visited);
+    }
+    Ok(())
+}
+
+async fn test_aborted(runtime: &sim::Runtime) -> cu::Result<()> {
+    let params = sim::SearchParams {
+        candidates: parse_commands("get 1 apple").await?,
+        ..Default::default()
+    };
+    let handle = Arc::new(sim::RunHandle::new());
+    handle.abort();
+    let ctx = sim::Context::new(handle, runtime);
+    let initial = sim::State::new(
+        runtime
+            .initial_process()
+            .context("failed to get initial process")?,
+    );
+    let goal = |_: &iv::PouchList, _: &iv::Gdt| false;
+    let output = sim::search_routes(ctx, initial, &params, &goal).await?;
+    if !matches!(output, MaybeAborted::Aborted) {
+        cu::bail!("expected the search to be aborted");
+    }
+    Ok(())
+}
+
+async fn search(
+    runtime: &sim::Runtime,
+    params: &sim::SearchParams,
+    goal: impl Fn(&iv::PouchList, &iv::Gdt) -> bool,
+) -> cu::Result<sim::SearchOutput> {
+    let ctx = sim::Context::new(Arc::new(sim::RunHandle::new()), runtime);
+    let initial = sim::State::new(
+        runtime
+            .initial_process()
+            .context("failed to get initial process")?,
+    );
+    match sim::search_routes(ctx, initial, params, &goal).await? {
+        MaybeAborted::Ok(output) => Ok(output),
+        MaybeAborted::Aborted => cu::bail!("search was aborted"),
+    }
+}
+
+/// Parse the commands in the script, one command per line
+async fn parse_commands(script: &str) -> cu::Result<Vec<cir::Command>> {
+    let resolver = StubQuotedItemResolver(AtomicBool::new(false));
+    let parsed = skybook_parser::parse(&resolver, script).await;
+    if let Some(error) = parsed.errors.iter().find(|x| !x.is_warning) {
+        cu::bail!("parse error: {}", error.error);
+    }
+    Ok(parsed.steps.iter().map(|x| x.command().clone()).collect())
+}
+
+fn count_item(pouch: &iv::PouchList, actor_name: &str) -> i32 {
+    pouch
+        .items
+        .iter()
+        .filter(|x| x.common.actor_name == actor_name)
+        .map(|x| x.common.value)
+        .sum()
+}
@@ -57,6 +57,55 @@ pub fn take_last_panic() -> PanicPayload {
         .expect("no panic payload captured")
 }
 
+/// Results of a group of tests, for logging PASS/FAIL of each test and the summary
+pub struct TestGroup {
+    name: &'static str,
+    total_count: usize,
+    passed_count: usize,
+}
+
+impl TestGroup {
+    pub fn new(name: &'static str) -> Self {
+        Self {
+            name,
+            total_count: 0,
+            passed_count: 0,
+        }
+    }
+
+    /// Record the result of a test
+    pub fn record(&mut self, test: &str, result: cu::Result<()>) {
+        self.total_count += 1;
+        match result {
+            Ok(()) => {
+                cu::info!("PASS {test}");
+                self.passed_count += 1;
+            }
+            Err(e) => cu::error!("FAIL {test} - {e}"),
+        }
+    }
+
+    /// Log the number of tests passed, and return if all tests passed
+    pub fn finish(self) -> bool {
+        cu::info!(
+            "{}/{} {} tests passed",
+            self.passed_count,
+            self.total_count,
+            self.name
+        );
+        self.passed_count == self.total_count
+    }
+}
+
+/// Call the test function and record the result in the [`TestGroup`],
+/// with the function name as the test name. Add `.await` for async tests
+macro_rules! run_test {
+    ($group:ident, $test:ident($($args:expr),*) $(.$await:tt)?) => {
+        $group.record(stringify!($test), $test($($args),*) $(.$await)?)
+    };
+}
+pub(crate) use run_test;
+
 pub fn setup_test_process(image_file: &str) -> cu::Result<Arc<sim::Runtime>> {
     cu::info!("loading {image_file}");
 
@@ -69,23 +118,28 @@ pub fn setup_test_process(image_file: &str) -> cu::Result<Arc<sim::Runtime>> {
         .clamp(2, 9)
         - 1;
     runtime
-        .init(
-            &image_bytes,
-            threads,
-            Some(&sim::RuntimeInitParams {
-                dlc: 3,
-                program_start: "".to_string(),
-                stack_start: "0x0000008888800000".to_string(),
-                stack_size: 0,
-                heap_free_size: 0,
-                pmdm_addr: "0x0000002222200000".to_string(),
-            }),
-        )
+        .init(&image_bytes, threads, Some(&test_init_params()))
         .context("failed to initialize runtime")?;
 
     Ok(Arc::new(runtime))
 }
 
+/// Parameters used to initialize the runtime for the tests
+pub fn test_init_params() -> sim::RuntimeInitParams {
+    sim::RuntimeInitParams {
+        dlc: 3,
+        program_start: "".to_string(),
+        stack_start: "0x0000008888800000".to_string(),
+        stack_size: 0,
+        heap_free_size: 0,
+        pmdm_addr: "0x0000002222200000".to_string(),
+        enable_features: Vec::new(),
+        disable_features: Vec::new(),
+        instruction_limit: 0,
+        time_limit: 0,
+    }
+}
+
 #[cfg(feature = "trace-memory")]
 pub fn collect_memory_trace(process: &Process, update_hash: bool) -> cu::Result<()> {
     cu::info!("collecting memory read trace");
@@ -4,6 +4,7 @@ use std::cell::OnceCell;
 use std::sync::Arc;
 
 use blueflame::env::GameVer;
+use blueflame::program;
 use js_sys::{Function, Promise, Uint8Array};
 use serde::{Deserialize, Serialize};
 use skybook_parser::{ParseOutput, cir};
@@ -63,6 +64,9 @@ pub async fn module_init(wasm_module_path: String, wasm_bindgen_js_path: String)
 pub struct RuntimeInitOutput {
     /// X.X.X (e.g. 1.5.0)
     pub game_version: String,
+    /// SHA-256 of the program in the image (hex), which can be used to cache the image.
+    /// None if the image is in the legacy format without a header
+    pub image_hash: Option<String>,
 }
 
 /// Initialize the simulator runtime
@@ -77,20 +81,18 @@ pub fn init_runtime(
             .get()
             .expect("init_runtime called before module_init");
         let threads = 4;
-        let result = match custom_image {
+        let custom_image = custom_image.map(|x| x.to_vec());
+        let image: &[u8] = match &custom_image {
             Some(data) => {
                 cu::info!("initializing runtime in WASM using custom image");
-                runtime.init(&data.to_vec(), threads, params.as_ref())
+                data
             }
             None => {
                 cu::info!("initializing runtime in WASM using default image");
-                runtime.init(
-                    include_bytes!("../../runtime-tests/data/program-mini.bfi"),
-                    threads,
-                    params.as_ref(),
-                )
+                include_bytes!("../../runtime-tests/data/program-mini.bfi")
             }
         };
+        let result = runtime.init(image, threads, params.as_ref());
         let env = match result {
             Err(e) => {
                 return interop::Result::Err(e);
@@ -101,8 +103,13 @@ pub fn init_runtime(
             GameVer::X150 => "1.5.0",
             GameVer::X160 => "1.6.0",
         };
+        let image_hash = program::read_header(image)
+            .ok()
+            .flatten()
+            .map(|x| x.hash_hex());
         interop::Result::Ok(RuntimeInitOutput {
             game_version: game_version.to_string(),
+            image_hash,
         })
     })
 }
@@ -354,6 +361,21 @@ pub fn get_gdt_inventory(
     run_output.get_gdt_inventory(step).into()
 }
 
+/// Query GDT flags by name or hash for the given byte position in the script
+///
+/// ## Pointer Ownership
+/// Borrows both the RunOutput and ParseOutput pointers.
+#[wasm_bindgen]
+pub fn get_gdt_flags(
+    run_output_ref: *const sim::RunOutput,
+    parse_output_ref: *const ParseOutput,
+    byte_pos: usize,
+    queries: Vec<iv::GdtFlagQuery>,
+) -> interop::Result<Vec<iv::GdtFlag>, RuntimeViewError> {
+    let (run_output, step) = deref_with_step!(run_output_ref, parse_output_ref, byte_pos);
+    run_output.get_gdt_flags(step, &queries).into()
+}
+
 /// Get the overworld items for the given byte position in the script
 ///
 /// ## Pointer Ownership
@@ -368,6 +390,20 @@ pub fn get_overworld_items(
     run_output.get_overworld_items(step).into()
 }
 
+/// Inspect the PauseMenuDataMgr as a tree of typed fields for the given byte position in the script
+///
+/// ## Pointer Ownership
+/// Borrows both the RunOutput and ParseOutput pointers.
+#[wasm_bindgen]
+pub fn get_pmdm_tree(
+    run_output_ref: *const sim::RunOutput,
+    parse_output_ref: *const ParseOutput,
+    byte_pos: usize,
+) -> interop::Result<iv::InspectNode, RuntimeViewError> {
+    let (run_output, step) = deref_with_step!(run_output_ref, parse_output_ref, byte_pos);
+    run_output.get_pmdm_tree(step).into()
+}
+
 /// Get the crash info at the given byte position, empty if no crash
 ///
 /// ## Pointer Ownership
@@ -385,6 +421,24 @@ pub fn get_crash_info(
         .unwrap_or_default()
 }
 
+/// Get the structured crash report for the given byte position in the script,
+/// including the step and command that crashed the game.
+///
+/// `is_crashed` is false in the output if the game has not crashed at or before the position
+///
+/// ## Pointer Ownership
+/// Borrows both the RunOutput and ParseOutput pointers.
+#[wasm_bindgen]
+pub fn get_crash_report(
+    run_output_ref: *const sim::RunOutput,
+    parse_output_ref: *const ParseOutput,
+    byte_pos: usize,
+) -> iv::CrashReport {
+    let (run_output, step) = deref_with_step!(run_output_ref, parse_output_ref, byte_pos);
+    let parse_output = unsafe { &*parse_output_ref };
+    run_output.get_crash_report_view(step, parse_output)
+}
+
 /// Get the list of save names for the given byte position in the script.
 /// Does not include the manual save in the output
 ///
@@ -416,3 +470,36 @@ pub fn get_save_inventory(
     let (run_output, step) = deref_with_step!(run_output_ref, parse_output_ref, byte_pos);
     run_output.get_save_inventory(step, name.as_deref()).into()
 }
+
+/// Get the inventories of the manual save and all named saves for the given byte position
+/// in the script, compared with the current GDT inventory.
+///
+/// ## Pointer Ownership
+/// Borrows both the RunOutput and ParseOutput pointers.
+#[wasm_bindgen]
+pub fn get_save_comparison(
+    run_output_ref: *const sim::RunOutput,
+    parse_output_ref: *const ParseOutput,
+    byte_pos: usize,
+) -> interop::Result<iv::SaveComparison, RuntimeViewError> {
+    let (run_output, step) = deref_with_step!(run_output_ref, parse_output_ref, byte_pos);
+    run_output.get_save_comparison(step).into()
+}
+
+/// Query GDT flags by name or hash in the save for the given byte position in the script.
+/// If name is `None`, it uses the manual save. If the given save
+/// is not found, an empty list is returned.
+///
+/// ## Pointer Ownership
+/// Borrows both the RunOutput and ParseOutput pointers.
+#[wasm_bindgen]
+pub fn get_save_gdt_flags(
+    run_output_ref: *const sim::RunOutput,
+    parse_output_ref: *const ParseOutput,
+    byte_pos: usize,
+    name: Option<String>,
+    queries: Vec<iv::GdtFlagQuery>,
+) -> Vec<iv::GdtFlag> {
+    let (run_output, step) = deref_with_step!(run_output_ref, parse_output_ref, byte_pos);
+    run_output.get_save_gdt_flags(step, name.as_deref(), &queries)
+}
@@ -5,8 +5,13 @@ import type {
     ErrorReport,
     ParserError,
     InvView_PouchList,
+    InvView_CrashReport,
     InvView_Gdt,
+    InvView_GdtFlag,
+    InvView_GdtFlagQuery,
+    InvView_InspectNode,
     InvView_Overworld,
+    InvView_SaveComparison,
     ItemSearchResult,
     MaybeAborted,
     RuntimeInitParams,
@@ -173,6 +178,17 @@ export class WasmApi implements NativeApi<number> {
         });
     }
 
+    public getGdtFlags(
+        runOutputPtr: number,
+        parseOutputPtr: number,
+        bytePos: number,
+        queries: InvView_GdtFlagQuery[],
+    ): Pwr<Result<InvView_GdtFlag[], RuntimeViewError>> {
+        return this.exec(() => {
+            return wasm_bindgen.get_gdt_flags(runOutputPtr, parseOutputPtr, bytePos, queries);
+        });
+    }
+
     public getOverworldItems(
         runOutputPtr: number,
         parseOutputPtr: number,
@@ -183,6 +199,16 @@ export class WasmApi implements NativeApi<number> {
         });
     }
 
+    public getPmdmTree(
+        runOutputPtr: number,
+        parseOutputPtr: number,
+        bytePos: number,
+    ): Pwr<Result<InvView_InspectNode, RuntimeViewError>> {
+        return this.exec(() => {
+            return wasm_bindgen.get_pmdm_tree(runOutputPtr, parseOutputPtr, bytePos);
+        });
+    }
+
     public getCrashInfo(
         runOutputPtr: number,
         parseOutputPtr: number,
@@ -193,6 +219,16 @@ export class WasmApi implements NativeApi<number> {
         });
     }
 
+    public getCrashReport(
+        runOutputPtr: number,
+        parseOutputPtr: number,
+        bytePos: number,
+    ): Pwr<InvView_CrashReport> {
+        return this.exec(() => {
+            return wasm_bindgen.get_crash_report(runOutputPtr, parseOutputPtr, bytePos);
+        });
+    }
+
     public getSaveNames(
         runOutputPtr: number,
         parseOutputPtr: number,
@@ -214,6 +250,34 @@ export class WasmApi implements NativeApi<number> {
         });
     }
 
+    public getSaveComparison(
+        runOutputPtr: number,
+        parseOutputPtr: number,
+        bytePos: number,
+    ): Pwr<Result<InvView_SaveComparison, RuntimeViewError>> {
+        return this.exec(() => {
+            return wasm_bindgen.get_save_comparison(runOutputPtr, parseOutputPtr, bytePos);
+        });
+    }
+
+    public getSaveGdtFlags(
+        runOutputPtr: number,
+        parseOutputPtr: number,
+        bytePos: number,
+        name: string | undefined,
+        queries: InvView_GdtFlagQuery[],
+    ): Pwr<InvView_GdtFlag[]> {
+        return this.exec(() => {
+            return wasm_bindgen.get_save_gdt_flags(
+                runOutputPtr,
+                parseOutputPtr,
+                bytePos,
+                name,
+                queries,
+            );
+        });
+    }
+
     public async freeNativeHandle(ptr: number): Promise<void> {
         await this.exec(() => {
             return wasm_bindgen.free_task_handle(ptr);
@@ -71,16 +71,31 @@ export const createRuntimeWorker = <TPtr>(
                 val: unwrapMaybeAborted(await runMgr.getGdtInventory(script, taskId, pos)),
             };
         },
+        getGdtFlags: async (script, taskId, pos, queries) => {
+            return {
+                val: unwrapMaybeAborted(await runMgr.getGdtFlags(script, taskId, pos, queries)),
+            };
+        },
         getOverworldItems: async (script, taskId, pos) => {
             return {
                 val: unwrapMaybeAborted(await runMgr.getOverworldItems(script, taskId, pos)),
             };
         },
+        getPmdmTree: async (script, taskId, pos) => {
+            return {
+                val: unwrapMaybeAborted(await runMgr.getPmdmTree(script, taskId, pos)),
+            };
+        },
         getCrashInfo: async (script, taskId, pos) => {
             return {
                 val: unwrapMaybeAborted(await runMgr.getCrashInfo(script, taskId, pos)),
             };
         },
+        getCrashReport: async (script, taskId, pos) => {
+            return {
+                val: unwrapMaybeAborted(await runMgr.getCrashReport(script, taskId, pos)),
+            };
+        },
         getSaveNames: async (script, taskId, pos) => {
             return {
                 val: unwrapMaybeAborted(await runMgr.getSaveNames(script, taskId, pos)),
@@ -91,6 +106,18 @@ export const createRuntimeWorker = <TPtr>(
                 val: unwrapMaybeAborted(await runMgr.getSaveInventory(script, taskId, pos, name)),
             };
         },
+        getSaveComparison: async (script, taskId, pos) => {
+            return {
+                val: unwrapMaybeAborted(await runMgr.getSaveComparison(script, taskId, pos)),
+            };
+        },
+        getSaveGdtFlags: async (script, taskId, pos, name, queries) => {
+            return {
+                val: unwrapMaybeAborted(
+                    await runMgr.getSaveGdtFlags(script, taskId, pos, name, queries),
+                ),
+            };
+        },
     };
 };
 
@@ -2,9 +2,14 @@ import { makeEmpType } from "@pistonite/pure/memory";
 import type { Result } from "@pistonite/pure/result";
 
 import type {
+    InvView_CrashReport,
     InvView_Gdt,
+    InvView_GdtFlag,
+    InvView_GdtFlagQuery,
+    InvView_InspectNode,
     InvView_Overworld,
     InvView_PouchList,
+    InvView_SaveComparison,
     ItemSearchResult,
     MaybeAborted,
     ErrorReport,
@@ -22,6 +27,8 @@ export type QuotedItemResolverFn = (query: string) => Promise<ItemSearchResult |
 export interface RuntimeInitOutput {
     /** Custom image version initialized, should be in the form of "X.X.X" */
     gameVersion: string;
+    /** SHA-256 of the program in the image, undefined if the image is in the legacy format */
+    imageHash?: string | null;
 }
 
 /** API bindings for calls into native runtime, plus mixin functions used by the worker */
@@ -110,6 +117,27 @@ export interface NativeApiFunctions<TPtr> {
         bytePos: number,
     ): Pwr<Result<InvView_Gdt, RuntimeViewError>>;
 
+    /**
+     * Get the inventories of the manual save and all named saves, compared with
+     * the current GDT inventory. Does not consume either ptr.
+     */
+    getSaveComparison(
+        runOutputPtr: TPtr,
+        parseOutputPtr: TPtr,
+        bytePos: number,
+    ): Pwr<Result<InvView_SaveComparison, RuntimeViewError>>;
+
+    /**
+     * Query GDT flags for the given byte position in the script.
+     * Does not consume either ptr.
+     */
+    getGdtFlags(
+        runOutputPtr: TPtr,
+        parseOutputPtr: TPtr,
+        bytePos: number,
+        queries: InvView_GdtFlagQuery[],
+    ): Pwr<Result<InvView_GdtFlag[], RuntimeViewError>>;
+
     /**
      * Get the overworld items for the given byte position in the script
      * Does not consume either ptr.
@@ -120,12 +148,32 @@ export interface NativeApiFunctions<TPtr> {
         bytePos: number,
     ): Pwr<Result<InvView_Overworld, RuntimeViewError>>;
 
+    /**
+     * Inspect the PauseMenuDataMgr as a tree of typed fields for the given byte
+     * position in the script. Does not consume either ptr.
+     */
+    getPmdmTree(
+        runOutputPtr: TPtr,
+        parseOutputPtr: TPtr,
+        bytePos: number,
+    ): Pwr<Result<InvView_InspectNode, RuntimeViewError>>;
+
     /**
      * Get crash info for the given byte position in the script.
      * Does not consume either ptr. Returns empty string if no crash
      */
     getCrashInfo(runOutputPtr: TPtr, parseOutputPtr: TPtr, bytePos: number): Pwr<string>;
 
+    /**
+     * Get the structured crash report for the given byte position in the script.
+     * Does not consume either ptr. `isCrashed` is false if no crash
+     */
+    getCrashReport(
+        runOutputPtr: TPtr,
+        parseOutputPtr: TPtr,
+        bytePos: number,
+    ): Pwr<InvView_CrashReport>;
+
     /** Get list of saves. Does not consume either ptr. */
     getSaveNames(runOutputPtr: TPtr, parseOutputPtr: TPtr, bytePos: number): Pwr<string[]>;
 
@@ -137,6 +185,15 @@ export interface NativeApiFunctions<TPtr> {
         name: string | undefined,
     ): Pwr<Result<InvView_Gdt, RuntimeViewError>>;
 
+    /** Query GDT flags in the save. Does not consume either ptr. Use undefined for manual save */
+    getSaveGdtFlags(
+        runOutputPtr: TPtr,
+        parseOutputPtr: TPtr,
+        bytePos: number,
+        name: string | undefined,
+        queries: InvView_GdtFlagQuery[],
+    ): Pwr<InvView_GdtFlag[]>;
+
     // === ref counting api ===
 
     freeNativeHandle(ptr: TPtr): Promise<void>;
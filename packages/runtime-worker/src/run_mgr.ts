@@ -4,9 +4,14 @@ import { scopedCapture } from "@pistonite/pure/sync";
 
 import type {
     ErrorReport,
+    InvView_CrashReport,
     InvView_Gdt,
+    InvView_GdtFlag,
+    InvView_GdtFlagQuery,
+    InvView_InspectNode,
     InvView_Overworld,
     InvView_PouchList,
+    InvView_SaveComparison,
     RuntimeError,
     RuntimeViewError,
 } from "@pistonite/skybook-api";
@@ -406,6 +411,27 @@ export class RunMgr<TPtr> {
         );
     }
 
+    public getGdtFlags(
+        script: string,
+        taskId: string,
+        bytePos: number,
+        queries: InvView_GdtFlagQuery[],
+    ): Pwr<Result<InvView_GdtFlag[], RuntimeViewError>> {
+        return this.withParseAndRunOutput(
+            script,
+            taskId,
+            bytePos,
+            (parseOutputBorrowed, runOutputBorrowed) => {
+                return this.napi.getGdtFlags(
+                    runOutputBorrowed,
+                    parseOutputBorrowed,
+                    bytePos,
+                    queries,
+                );
+            },
+        );
+    }
+
     public getOverworldItems(
         script: string,
         taskId: string,
@@ -421,6 +447,21 @@ export class RunMgr<TPtr> {
         );
     }
 
+    public getPmdmTree(
+        script: string,
+        taskId: string,
+        bytePos: number,
+    ): Pwr<Result<InvView_InspectNode, RuntimeViewError>> {
+        return this.withParseAndRunOutput(
+            script,
+            taskId,
+            bytePos,
+            (parseOutputBorrowed, runOutputBorrowed) => {
+                return this.napi.getPmdmTree(runOutputBorrowed, parseOutputBorrowed, bytePos);
+            },
+        );
+    }
+
     public getCrashInfo(script: string, taskId: string, bytePos: number): Pwr<string> {
         return this.withParseAndRunOutput(
             script,
@@ -432,6 +473,21 @@ export class RunMgr<TPtr> {
         );
     }
 
+    public getCrashReport(
+        script: string,
+        taskId: string,
+        bytePos: number,
+    ): Pwr<InvView_CrashReport> {
+        return this.withParseAndRunOutput(
+            script,
+            taskId,
+            bytePos,
+            (parseOutputBorrowed, runOutputBorrowed) => {
+                return this.napi.getCrashReport(runOutputBorrowed, parseOutputBorrowed, bytePos);
+            },
+        );
+    }
+
     public getRuntimeDiagnostics(
         script: string,
         taskId: string,
@@ -473,4 +529,42 @@ export class RunMgr<TPtr> {
             },
         );
     }
+
+    public getSaveComparison(
+        script: string,
+        taskId: string,
+        bytePos: number,
+    ): Pwr<Result<InvView_SaveComparison, RuntimeViewError>> {
+        return this.withParseAndRunOutput(
+            script,
+            taskId,
+            bytePos,
+            (parseOutputBorrowed, runOutputBorrowed) => {
+                return this.napi.getSaveComparison(runOutputBorrowed, parseOutputBorrowed, bytePos);
+            },
+        );
+    }
+
+    public getSaveGdtFlags(
+        script: string,
+        taskId: string,
+        bytePos: number,
+        name: string | undefined,
+        queries: InvView_GdtFlagQuery[],
+    ): Pwr<InvView_GdtFlag[]> {
+        return this.withParseAndRunOutput(
+            script,
+            taskId,
+            bytePos,
+            (parseOutputBorrowed, runOutputBorrowed) => {
+                return this.napi.getSaveGdtFlags(
+                    runOutputBorrowed,
+                    parseOutputBorrowed,
+                    bytePos,
+                    name,
+                    queries,
+                );
+            },
+        );
+    }
 }
@@ -1,4 +1,6 @@
+use std::sync::LazyLock;
 use std::thread::JoinHandle;
+use std::time::Instant;
 
 use crate::exec::{self, Error, JobSender, Join, Spawn};
 
@@ -29,3 +31,13 @@ impl Join for JoinHandle<()> {
             .map_err(|_| Error::Join("failed to join thread".to_string()))
     }
 }
+
+/// Clock for the time limit of the processor, using the monotonic system clock
+pub struct Clock;
+
+impl blueflame::processor::Clock for Clock {
+    fn now_ms(&self) -> u64 {
+        static START: LazyLock<Instant> = LazyLock::new(Instant::now);
+        START.elapsed().as_millis() as u64
+    }
+}
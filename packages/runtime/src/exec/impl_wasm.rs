@@ -68,3 +68,13 @@ impl Join for JoinHandle<()> {
             .map_err(|_| Error::Join("failed to join thread".to_string()))
     }
 }
+
+/// Clock for the time limit of the processor, using `Date.now()`,
+/// since `std::time` is not available in WASM
+pub struct Clock;
+
+impl blueflame::processor::Clock for Clock {
+    fn now_ms(&self) -> u64 {
+        js_sys::Date::now() as u64
+    }
+}
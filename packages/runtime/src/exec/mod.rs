@@ -38,6 +38,7 @@ mod impl_wasm;
 use impl_wasm as __impl;
 
 pub type Spawner = __impl::Spawner;
+pub type Clock = __impl::Clock;
 
 pub type Executor = executor::ExecutorImpl<Spawner>;
 static_assertions::assert_impl_all!(Executor: Send , Sync);
@@ -1,8 +1,8 @@
 use blueflame::game::{self, WeaponModifierInfo};
 use blueflame::linker;
 use blueflame::linker::events::GameEvent as _;
-use blueflame::memory::{self, Memory};
-use blueflame::processor::{self, Cpu2};
+use blueflame::memory::{self, Memory, mem};
+use blueflame::processor::{self, Cpu2, Process};
 use skybook_parser::{Span, cir};
 
 use crate::error::{ErrorReport, sim_error, sim_warning};
@@ -164,6 +164,75 @@ pub fn check_overworld_amount(item: &cir::ItemSelectSpec, errors: &mut Vec<Error
     }
 }
 
+/// Get the `itemBuyingPrice` of the actor, from the ActorInfo in the
+/// program image if available, otherwise from the static data
+pub fn buying_price(proc: &Process, actor: &str) -> Option<i32> {
+    proc.actor_info()
+        .and_then(|x| x.get(actor))
+        .and_then(|info| info.item_buying_price())
+        .or_else(|| game::get_buying_price(actor))
+}
+
+/// Get the `itemSellingPrice` of the actor, from the ActorInfo in the
+/// program image if available, otherwise from the static data
+pub fn selling_price(proc: &Process, actor: &str) -> Option<i32> {
+    proc.actor_info()
+        .and_then(|x| x.get(actor))
+        .and_then(|info| info.item_selling_price())
+        .or_else(|| game::get_selling_price(actor))
+}
+
+/// Count the amount of a material (by actor name) in the inventory.
+/// Stackable materials are counted by value
+pub fn count_material(proc: &Process, material: &str, span: Span) -> Result<usize, memory::Error> {
+    let inventory = sim::PouchScreen::open_no_exec(proc, false)?;
+    let matcher = material_matcher(material, span);
+    inventory.get_amount(&matcher, sim::CountingMethod::CanStack, proc.memory())
+}
+
+/// Take `amount` of a material (by actor name) from the inventory, the same
+/// way as selling. The amount should be checked with [`count_material`] first
+pub fn take_material(
+    ctx: &mut sim::Context<&mut Cpu2>,
+    errors: &mut Vec<ErrorReport>,
+    material: &str,
+    amount: usize,
+) -> Result<(), processor::Error> {
+    let matcher = material_matcher(material, ctx.span);
+    let mut remaining = amount;
+    while remaining > 0 {
+        if ctx.is_aborted() {
+            return Ok(());
+        }
+        let inventory = sim::PouchScreen::open_no_exec(ctx.cpu().proc, false)?;
+        let m = ctx.cpu().proc.memory();
+        let Some((tab, slot)) = inventory.select_value_at_least(&matcher, 1, m, errors)? else {
+            cu::error!("failed to find material {material} to take");
+            break;
+        };
+        let sim::ScreenItemState::Normal(item_ptr) = inventory.get(tab, slot) else {
+            break;
+        };
+        let amount = if game::can_stack(material) {
+            mem! { m: let value = *(&item_ptr->mValue); }
+            remaining.min(value.max(0) as usize)
+        } else {
+            1
+        };
+        linker::sell_item(ctx.cpu(), item_ptr, amount as i32)?;
+        remaining -= amount;
+    }
+    Ok(())
+}
+
+fn material_matcher(material: &str, span: Span) -> cir::ItemMatchSpec {
+    cir::ItemMatchSpec {
+        name: cir::ItemNameSpec::Actor(material.to_string()),
+        meta: None,
+        span,
+    }
+}
+
 /// Convert `AllBut` variant from the "but" amount to real amount
 pub fn convert_amount<F: FnOnce(&mut Vec<ErrorReport>) -> Result<usize, processor::Error>>(
     amount: cir::AmountSpec,
@@ -1,9 +1,9 @@
 use blueflame::game;
 use blueflame::linker;
-use blueflame::processor::{self, Cpu2};
+use blueflame::processor::{self, Cpu2, Process};
 use skybook_parser::cir;
 
-use crate::error::{ErrorReport, sim_error};
+use crate::error::{ErrorReport, sim_error, sim_warning};
 use crate::sim;
 
 /// Add items to pouch by eventually calling itemGet or cookItemGet
@@ -56,7 +56,7 @@ pub fn buy_items(
         }
         if !sys
             .screen
-            .transition_to_shop_buying(ctx, &mut sys.overworld, false, errors)?
+            .transition_to_shop_buying(ctx, &mut sys.overworld, None, false, errors)?
         {
             cu::error!("failed to transition to buying screen for BUY");
             return Ok(());
@@ -73,8 +73,47 @@ pub fn buy_items(
         super::predrop_items!(ctx, sys, errors, "BUY")
     };
 
+    // when talking to a known NPC, only items sold there can be bought
+    let shop = sys.screen.current_shop();
+
     for item in items {
-        get_item_internal(ctx, sys, item, errors, accurate)?;
+        let shop_item = match &shop {
+            None => None,
+            Some(shop) => match shop.item(&item.name) {
+                None => {
+                    errors.push(sim_error!(item.span, NotSoldInShop(shop.name.clone())));
+                    continue;
+                }
+                Some(shop_item) => Some((shop, shop_item)),
+            },
+        };
+        if let Some((shop, shop_item)) = shop_item
+            && let Some(remaining) = sys.shop.remaining(shop, shop_item)
+            && remaining < item.amount as u32
+        {
+            errors.push(sim_error!(item.span, OutOfStock(remaining)));
+            continue;
+        }
+        let unit_price = match super::buying_price(ctx.cpu().proc, &item.name) {
+            Some(price) => price.max(0),
+            None => {
+                errors.push(sim_warning!(item.span, UnknownPrice));
+                0
+            }
+        };
+        let price = unit_price.saturating_mul(item.amount as i32);
+        let rupees = super::low_level::get_rupees(ctx.cpu().proc)?;
+        if price > rupees {
+            errors.push(sim_error!(item.span, NotEnoughRupees(price, rupees)));
+            continue;
+        }
+        // only pay for the items actually added to the pouch
+        let count = get_item_internal(ctx, sys, item, errors, accurate)?;
+        let price = unit_price.saturating_mul(count as i32);
+        super::low_level::add_rupees(ctx.cpu().proc, -price)?;
+        if let Some((shop, shop_item)) = shop_item {
+            sys.shop.sell(shop, shop_item, count as u32);
+        }
         if ctx.is_aborted() {
             break;
         }
@@ -99,31 +138,55 @@ pub fn buy_items(
     Ok(())
 }
 
+/// Get the ingredients of a recipe for the dish from the CookData in the
+/// program image, used when the ingredients are not specified.
+///
+/// Empty if the data is not in the image
+fn default_ingredients(proc: &Process, dish: &str) -> Vec<String> {
+    let (Some(cook_data), Some(actor_info)) = (proc.game_data().cook_data(), proc.actor_info())
+    else {
+        return vec![];
+    };
+    let Some(ingredients) = cook_data.ingredients(dish) else {
+        return vec![];
+    };
+    ingredients
+        .iter()
+        .filter_map(|x| actor_info.name_by_hash(*x.first()?))
+        .map(|x| x.to_string())
+        .collect()
+}
+
+/// Get the item, and return the number of items actually added
 fn get_item_internal(
     ctx: &mut sim::Context<&mut Cpu2>,
     sys: &mut sim::GameSystems,
     item: &cir::ItemSpec,
     errors: &mut Vec<ErrorReport>,
     accurate: bool,
-) -> Result<(), processor::Error> {
+) -> Result<usize, processor::Error> {
     let amount = item.amount;
     let name = &item.name;
     let is_cook_item = name.starts_with("Item_Cook_");
     let meta = item.meta.as_ref();
     if is_cook_item {
+        let ingredients = match meta.map(|m| m.ingredients.as_slice()) {
+            Some(x) if !x.is_empty() => x.to_vec(),
+            _ => default_ingredients(ctx.cpu().proc, name),
+        };
         // cannot optimize cook items
-        for _ in 0..amount {
+        for i in 0..amount {
             if ctx.is_aborted() {
-                return Ok(());
+                return Ok(i);
             }
             if linker::cannot_get_item(ctx.cpu(), name, 1)? {
                 errors.push(sim_error!(item.span, CannotGetMore));
-                return Ok(());
+                return Ok(i);
             }
             linker::get_cook_item(
                 ctx.cpu(),
                 name,
-                meta.map(|m| m.ingredients.as_slice()).unwrap_or(&[]),
+                &ingredients,
                 meta.and_then(|m| m.life_recover_f32()),
                 meta.and_then(|m| m.effect_duration),
                 meta.and_then(|m| m.sell_price),
@@ -131,7 +194,7 @@ fn get_item_internal(
                 meta.and_then(|m| m.effect_level),
             )?;
         }
-        return Ok(());
+        return Ok(amount);
     }
 
     // getting non-cook item
@@ -149,19 +212,19 @@ fn get_item_internal(
     if can_optimize {
         if linker::cannot_get_item(ctx.cpu(), name, amount as i32)? {
             errors.push(sim_error!(item.span, CannotGetMore));
-        } else {
-            // optimize into one call with a value
-            linker::get_item(ctx.cpu(), name, Some(amount as i32), modifier)?;
+            return Ok(0);
         }
-        return Ok(());
+        // optimize into one call with a value
+        linker::get_item(ctx.cpu(), name, Some(amount as i32), modifier)?;
+        return Ok(amount);
     }
-    for _ in 0..amount {
+    for i in 0..amount {
         if ctx.is_aborted() {
-            return Ok(());
+            return Ok(i);
         }
         if linker::cannot_get_item(ctx.cpu(), name, 1)? {
             errors.push(sim_error!(item.span, CannotGetMore));
-            return Ok(());
+            return Ok(i);
         }
         // need to generate new actor because it's not already
         // on the ground
@@ -172,5 +235,5 @@ fn get_item_internal(
         };
         super::get_item_with_auto_equip(ctx.cpu(), sys, is_weapon, auto_equip_type)?;
     }
-    Ok(())
+    Ok(amount)
 }
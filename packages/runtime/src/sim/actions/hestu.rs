@@ -0,0 +1,126 @@
+use blueflame::game::gdt;
+use blueflame::memory::{self, proxy};
+use blueflame::processor::{self, Cpu2, Process};
+use skybook_parser::cir;
+
+use crate::error::{ErrorReport, sim_error};
+use crate::sim;
+
+/// Trade Korok Seeds with Hestu for more slots in the category
+///
+/// The number of seeds needed depends on how many upgrades are already done
+/// in the category. `per_use` overrides the cost of each upgrade
+pub fn hestu_upgrade(
+    ctx: &mut sim::Context<&mut Cpu2>,
+    sys: &mut sim::GameSystems,
+    errors: &mut Vec<ErrorReport>,
+    category: &cir::CategorySpec,
+    per_use: Option<i32>,
+) -> Result<(), processor::Error> {
+    // talking to Hestu is done in the overworld
+    super::switch_to_overworld_or_stop!(ctx, sys, errors, "HESTU");
+    let should_drop = super::predrop_items!(ctx, sys, errors, "HESTU");
+
+    hestu_upgrade_internal(ctx, errors, category, per_use)?;
+
+    super::handle_predrop_result(ctx, sys, errors, false, should_drop, "HESTU")
+}
+
+fn hestu_upgrade_internal(
+    ctx: &mut sim::Context<&mut Cpu2>,
+    errors: &mut Vec<ErrorReport>,
+    category: &cir::CategorySpec,
+    per_use: Option<i32>,
+) -> Result<(), processor::Error> {
+    let span = ctx.span;
+    let Some(index) = slot_flag_index(category.category) else {
+        // the parser only allows weapon, bow and shield
+        cu::error!(
+            "invalid category for HESTU command: {:?}",
+            category.category
+        );
+        return Ok(());
+    };
+    let (flag_name, initial, costs) = SLOT_FLAGS[index];
+    let max = initial + costs.len() as i32;
+
+    for _ in 0..category.amount {
+        if ctx.is_aborted() {
+            break;
+        }
+        let slots = get_slots(ctx.cpu().proc, flag_name)?;
+        if slots >= max {
+            errors.push(sim_error!(span, MaxUpgradeReached));
+            break;
+        }
+        let cost = match per_use {
+            Some(x) => x.max(0) as usize,
+            // slots below the initial number are treated as no upgrades done
+            None => costs[(slots - initial).max(0) as usize],
+        };
+        let seeds = super::count_material(ctx.cpu().proc, "Obj_KorokNuts", span)?;
+        if seeds < cost {
+            errors.push(sim_error!(
+                span,
+                NotEnoughUpgradeMaterial("Obj_KorokNuts".to_string(), cost, seeds)
+            ));
+            break;
+        }
+        super::take_material(ctx, errors, "Obj_KorokNuts", cost)?;
+        set_slots(ctx.cpu().proc, flag_name, slots + 1)?;
+    }
+
+    Ok(())
+}
+
+/// GDT flags for the number of slots, with the initial number of slots
+/// and the Korok Seeds needed for each upgrade, for weapons, bows and shields.
+///
+/// The cost of an upgrade only depends on the upgrades already done in the
+/// same category. Maxing out all categories takes 441 seeds in total
+#[rustfmt::skip]
+const SLOT_FLAGS: [(&str, i32, &[usize]); 3] = [
+    ("WeaponPorchStockNum", 8, &[1, 2, 3, 5, 7, 10, 15, 20, 25, 30, 35, 40]),
+    ("BowPorchStockNum", 5, &[1, 2, 3, 5, 7, 10, 15, 20, 30]),
+    ("ShieldPorchStockNum", 4, &[1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 12, 14, 16, 18, 20, 20]),
+];
+
+fn slot_flag_index(category: cir::Category) -> Option<usize> {
+    match category {
+        cir::Category::Weapon => Some(0),
+        cir::Category::Bow => Some(1),
+        cir::Category::Shield => Some(2),
+        _ => None,
+    }
+}
+
+/// Get the Korok Seeds Hestu asks for to upgrade the category, when it
+/// currently has `slots` slots.
+///
+/// Returns `None` if the category cannot be upgraded, or is already at max
+pub fn hestu_upgrade_cost(category: cir::Category, slots: i32) -> Option<usize> {
+    let (_, initial, costs) = SLOT_FLAGS[slot_flag_index(category)?];
+    costs.get((slots - initial).max(0) as usize).copied()
+}
+
+fn get_slots(proc: &Process, flag_name: &str) -> Result<i32, memory::Error> {
+    let gdt_ptr = gdt::trigger_param_ptr(proc.memory())?;
+    proxy! { let gdt = *gdt_ptr as trigger_param in proc };
+    match gdt.by_name::<gdt::fd!(s32)>(flag_name) {
+        Some(flag) => Ok(*flag.get()),
+        None => {
+            cu::warn!("could not find {flag_name} flag");
+            Ok(0)
+        }
+    }
+}
+
+fn set_slots(proc: &mut Process, flag_name: &str, value: i32) -> Result<(), memory::Error> {
+    let gdt_ptr = gdt::trigger_param_ptr(proc.memory())?;
+    proxy! { let mut gdt = *gdt_ptr as trigger_param in proc };
+    match gdt.by_name_mut::<gdt::fd!(s32)>(flag_name) {
+        Some(flag) => flag.set(value),
+        None => cu::warn!("could not find {flag_name} flag"),
+    }
+    Ok(())
+}
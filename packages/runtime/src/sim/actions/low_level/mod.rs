@@ -3,6 +3,8 @@ use common::*;
 
 mod add_slot;
 pub use add_slot::*;
+mod rupee;
+pub use rupee::*;
 mod set_gdt;
 pub use set_gdt::*;
 mod swap_items;
@@ -0,0 +1,36 @@
+use blueflame::game::gdt;
+use blueflame::memory::{self, proxy};
+use blueflame::processor::Process;
+
+/// Max number of rupees the player can have
+pub const MAX_RUPEES: i32 = 999999;
+
+/// Get the number of rupees the player has, from the `CurrentRupee` flag
+pub fn get_rupees(proc: &Process) -> Result<i32, memory::Error> {
+    let gdt_ptr = gdt::trigger_param_ptr(proc.memory())?;
+    proxy! { let gdt = *gdt_ptr as trigger_param in proc };
+    match gdt.by_name::<gdt::fd!(s32)>("CurrentRupee") {
+        Some(flag) => Ok(*flag.get()),
+        None => {
+            cu::warn!("could not find CurrentRupee flag");
+            Ok(0)
+        }
+    }
+}
+
+/// Add rupees to the player (or take rupees away if `amount` is negative)
+/// by updating the `CurrentRupee` flag.
+///
+/// The result is clamped to be between 0 and [`MAX_RUPEES`]
+pub fn add_rupees(proc: &mut Process, amount: i32) -> Result<(), memory::Error> {
+    let gdt_ptr = gdt::trigger_param_ptr(proc.memory())?;
+    proxy! { let mut gdt = *gdt_ptr as trigger_param in proc };
+    match gdt.by_name_mut::<gdt::fd!(s32)>("CurrentRupee") {
+        Some(flag) => {
+            let value = flag.get().saturating_add(amount).clamp(0, MAX_RUPEES);
+            flag.set(value);
+        }
+        None => cu::warn!("could not find CurrentRupee flag"),
+    }
+    Ok(())
+}
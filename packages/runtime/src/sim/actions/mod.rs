@@ -27,6 +27,12 @@ mod overload;
 pub use overload::*;
 mod spawn_items;
 pub use spawn_items::*;
+mod upgrade_armor;
+pub use upgrade_armor::*;
+mod hestu;
+pub use hestu::*;
+mod pray;
+pub use pray::*;
 
 mod force_remove;
 pub use force_remove::*;
@@ -0,0 +1,134 @@
+use blueflame::game::gdt;
+use blueflame::memory::{self, proxy};
+use blueflame::processor::{self, Cpu2, Process};
+use skybook_parser::cir;
+
+use crate::error::{ErrorReport, sim_error};
+use crate::sim;
+
+/// Number of Spirit Orbs needed for one upgrade
+const ORBS_PER_UPGRADE: usize = 4;
+/// Max health in quarter hearts (30 hearts)
+const MAX_HEARTS: i32 = 120;
+/// Max stamina (3 wheels)
+const MAX_STAMINA: f32 = 3000.0;
+/// Stamina added by one Stamina Vessel (1/5 wheel)
+const STAMINA_PER_VESSEL: f32 = 200.0;
+
+/// Trade Spirit Orbs for a Heart Container or Stamina Vessel at a Goddess Statue
+pub fn pray(
+    ctx: &mut sim::Context<&mut Cpu2>,
+    sys: &mut sim::GameSystems,
+    errors: &mut Vec<ErrorReport>,
+    target: cir::PrayTarget,
+    times: usize,
+) -> Result<(), processor::Error> {
+    // praying is done in the overworld
+    super::switch_to_overworld_or_stop!(ctx, sys, errors, "PRAY");
+    let should_drop = super::predrop_items!(ctx, sys, errors, "PRAY");
+
+    pray_internal(ctx, errors, target, times)?;
+
+    super::handle_predrop_result(ctx, sys, errors, false, should_drop, "PRAY")
+}
+
+fn pray_internal(
+    ctx: &mut sim::Context<&mut Cpu2>,
+    errors: &mut Vec<ErrorReport>,
+    target: cir::PrayTarget,
+    times: usize,
+) -> Result<(), processor::Error> {
+    let span = ctx.span;
+    for _ in 0..times {
+        if ctx.is_aborted() {
+            break;
+        }
+        let is_max = match target {
+            cir::PrayTarget::Heart => get_max_hearts(ctx.cpu().proc)? >= MAX_HEARTS,
+            cir::PrayTarget::Stamina => get_max_stamina(ctx.cpu().proc)? >= MAX_STAMINA,
+        };
+        if is_max {
+            errors.push(sim_error!(span, MaxUpgradeReached));
+            break;
+        }
+        let orbs = super::count_material(ctx.cpu().proc, "Obj_DungeonClearSeal", span)?;
+        if orbs < ORBS_PER_UPGRADE {
+            errors.push(sim_error!(
+                span,
+                NotEnoughUpgradeMaterial(
+                    "Obj_DungeonClearSeal".to_string(),
+                    ORBS_PER_UPGRADE,
+                    orbs
+                )
+            ));
+            break;
+        }
+        super::take_material(ctx, errors, "Obj_DungeonClearSeal", ORBS_PER_UPGRADE)?;
+        match target {
+            cir::PrayTarget::Heart => add_heart_container(ctx.cpu().proc)?,
+            cir::PrayTarget::Stamina => add_stamina_vessel(ctx.cpu().proc)?,
+        }
+    }
+
+    Ok(())
+}
+
+fn get_max_hearts(proc: &Process) -> Result<i32, memory::Error> {
+    let gdt_ptr = gdt::trigger_param_ptr(proc.memory())?;
+    proxy! { let gdt = *gdt_ptr as trigger_param in proc };
+    match gdt.by_name::<gdt::fd!(s32)>("MaxHart") {
+        Some(flag) => Ok(*flag.get()),
+        None => {
+            cu::warn!("could not find MaxHart flag");
+            Ok(0)
+        }
+    }
+}
+
+fn get_max_stamina(proc: &Process) -> Result<f32, memory::Error> {
+    let gdt_ptr = gdt::trigger_param_ptr(proc.memory())?;
+    proxy! { let gdt = *gdt_ptr as trigger_param in proc };
+    match gdt.by_name::<gdt::fd!(f32)>("StaminaMax") {
+        Some(flag) => Ok(*flag.get()),
+        None => {
+            cu::warn!("could not find StaminaMax flag");
+            Ok(0.0)
+        }
+    }
+}
+
+/// Add one heart (4 quarter hearts) to max health, and refill health
+fn add_heart_container(proc: &mut Process) -> Result<(), memory::Error> {
+    let gdt_ptr = gdt::trigger_param_ptr(proc.memory())?;
+    proxy! { let mut gdt = *gdt_ptr as trigger_param in proc };
+    let max = match gdt.by_name_mut::<gdt::fd!(s32)>("MaxHart") {
+        Some(flag) => {
+            let value = (*flag.get() + 4).min(MAX_HEARTS);
+            flag.set(value);
+            value
+        }
+        None => {
+            cu::warn!("could not find MaxHart flag");
+            return Ok(());
+        }
+    };
+    match gdt.by_name_mut::<gdt::fd!(s32)>("CurrentHart") {
+        Some(flag) => flag.set(max),
+        None => cu::warn!("could not find CurrentHart flag"),
+    }
+    Ok(())
+}
+
+/// Add one Stamina Vessel to max stamina
+fn add_stamina_vessel(proc: &mut Process) -> Result<(), memory::Error> {
+    let gdt_ptr = gdt::trigger_param_ptr(proc.memory())?;
+    proxy! { let mut gdt = *gdt_ptr as trigger_param in proc };
+    match gdt.by_name_mut::<gdt::fd!(f32)>("StaminaMax") {
+        Some(flag) => {
+            let value = (*flag.get() + STAMINA_PER_VESSEL).min(MAX_STAMINA);
+            flag.set(value);
+        }
+        None => cu::warn!("could not find StaminaMax flag"),
+    }
+    Ok(())
+}
@@ -113,6 +113,9 @@ pub fn regen_stage_internal(
     // X. Reset to overworld screen
     sys.screen.reset_to_overworld();
 
+    // X. Shops are restocked
+    sys.shop.restock_on_stage_regen();
+
     // 2. SaveMgr/GdtMgr (?) loads the save into GDT
     if let Some(save_gdt) = load_save {
         reload_gdt_or_stop!(ctx, errors, save_gdt);
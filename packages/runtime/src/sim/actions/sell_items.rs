@@ -105,8 +105,22 @@ pub fn sell_items(
                 1
             };
 
+            // cook items have the price stored on the item,
+            // other items use the price from actor data.
+            // orbs and koroks don't give rupees since they are traded
+            let unit_price = if !game::can_sell(&item_name) {
+                0
+            } else if item_name.starts_with("Item_Cook_") {
+                mem! { m: let price = *(&item_ptr->mSellPrice); }
+                price
+            } else {
+                super::selling_price(ctx.cpu().proc, &item_name).unwrap_or_default()
+            };
+            let rupees = unit_price.max(0).saturating_mul(sell_amount as i32);
+
             remaining.sub(sell_amount);
             linker::sell_item(ctx.cpu(), item_ptr, sell_amount as i32)?;
+            super::low_level::add_rupees(ctx.cpu().proc, rupees)?;
             shop.update(tab, slot, None, ctx.cpu().proc.memory())?;
         }
         if check_for_extra_error {
@@ -1,6 +1,7 @@
 use std::collections::BTreeMap;
 use std::sync::Arc;
 
+use blueflame::env::{Feature, FeatureSet};
 use blueflame::game::{gdt, singleton_instance};
 use blueflame::processor::{self, Cpu2};
 use skybook_parser::cir;
@@ -8,16 +9,32 @@ use skybook_parser::cir;
 use crate::error::{ErrorReport, sim_error};
 use crate::sim;
 
+/// State outside of the game process that can be changed by system commands
+#[derive(Default)]
+pub struct SysCommandState {
+    /// The named saves
+    pub saves: BTreeMap<String, Arc<gdt::TriggerParam>>,
+    pub manual_save: Option<Arc<gdt::TriggerParam>>,
+    /// The new DLC version, if changed
+    pub dlc_version: Option<u32>,
+    /// The new features of the process, if changed
+    pub features: Option<FeatureSet>,
+}
+
 // TODO: probably better ways to handle this, when we fix the scheduling
 pub fn exec_sys_commands(
     ctx: &mut sim::Context<&mut Cpu2>,
     sys: &mut sim::GameSystems,
     errors: &mut Vec<ErrorReport>,
     cmds: &[cir::SysCommand],
-    saves: &mut BTreeMap<String, Arc<gdt::TriggerParam>>,
-    manual_save: &mut Option<Arc<gdt::TriggerParam>>,
-    dlc_version: &mut Option<u32>,
+    state: &mut SysCommandState,
 ) -> Result<(), processor::Error> {
+    let SysCommandState {
+        saves,
+        manual_save,
+        dlc_version,
+        features,
+    } = state;
     for cmd in cmds {
         use cir::SysCommandData as S;
         match &cmd.data {
@@ -65,6 +82,21 @@ pub fn exec_sys_commands(
             S::LoadingScreenNoRemoveTranslucent => {
                 super::regen_stage_internal(ctx, sys, errors, false, None)?;
             }
+            S::EnableFeature(name) | S::DisableFeature(name) => {
+                let Some(feature) = Feature::parse(name) else {
+                    errors.push(sim_error!(cmd.span, UnknownFeature(name.to_string())));
+                    continue;
+                };
+                let proc = &mut ctx.cpu().proc;
+                let mut new_features = proc.features();
+                if matches!(cmd.data, S::EnableFeature(_)) {
+                    new_features.insert(feature);
+                } else {
+                    new_features.remove(feature);
+                }
+                proc.set_features(new_features);
+                *features = Some(new_features);
+            }
         }
     }
     Ok(())
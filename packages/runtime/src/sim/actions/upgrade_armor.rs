@@ -0,0 +1,96 @@
+use blueflame::linker;
+use blueflame::memory::Ptr;
+use blueflame::processor::{self, Cpu2};
+use skybook_parser::cir;
+
+use crate::error::{ErrorReport, sim_error};
+use crate::sim;
+
+/// Upgrade an armor to the next star at a Great Fairy
+///
+/// The materials and rupees are checked and taken before the armor is
+/// upgraded. If anything is missing, or the cost is unknown, nothing is taken.
+pub fn upgrade_armor(
+    ctx: &mut sim::Context<&mut Cpu2>,
+    sys: &mut sim::GameSystems,
+    errors: &mut Vec<ErrorReport>,
+    item: &cir::ItemSelectSpec,
+) -> Result<(), processor::Error> {
+    // talking to the Great Fairy is done in the overworld
+    super::switch_to_overworld_or_stop!(ctx, sys, errors, "UPGRADE");
+    let should_drop = super::predrop_items!(ctx, sys, errors, "UPGRADE");
+
+    upgrade_armor_internal(ctx, errors, item)?;
+
+    super::handle_predrop_result(ctx, sys, errors, false, should_drop, "UPGRADE")
+}
+
+fn upgrade_armor_internal(
+    ctx: &mut sim::Context<&mut Cpu2>,
+    errors: &mut Vec<ErrorReport>,
+    item: &cir::ItemSelectSpec,
+) -> Result<(), processor::Error> {
+    let matcher = &item.matcher;
+    let span = matcher.span;
+    let inventory = sim::PouchScreen::open_no_exec(ctx.cpu().proc, false)?;
+
+    // find the armor
+    let m = ctx.cpu().proc.memory();
+    let Some((tab, slot)) = inventory.select(matcher, m, errors)? else {
+        errors.push(sim_error!(span, CannotFindItem));
+        return Ok(());
+    };
+    let sim::ScreenItemState::Normal(item_ptr) = inventory.get(tab, slot) else {
+        errors.push(sim_error!(span, InvalidItemTarget));
+        return Ok(());
+    };
+    let name = Ptr!(&item_ptr->mName).cstr(m)?.load_utf8_lossy(m)?;
+    let star = match skybook_parser::get_armor_star(&name) {
+        Some(star) if star < 4 => star,
+        _ => {
+            errors.push(sim_error!(span, NotUpgradable));
+            return Ok(());
+        }
+    };
+    let next_name = skybook_parser::get_armor_with_star(&name, star + 1).into_owned();
+
+    let Some(cost) = skybook_parser::get_armor_upgrade_cost(&name, star + 1) else {
+        errors.push(sim_error!(span, UnknownUpgradeCost));
+        return Ok(());
+    };
+    let (materials, rupees) = (cost.materials, cost.rupees);
+
+    // check if everything needed is there
+    let mut has_all = true;
+    for (material, need) in materials {
+        let need = (*need).max(0) as usize;
+        let amount = super::count_material(ctx.cpu().proc, material, span)?;
+        if amount < need {
+            errors.push(sim_error!(
+                span,
+                NotEnoughUpgradeMaterial(material.to_string(), need, amount)
+            ));
+            has_all = false;
+        }
+    }
+    let current_rupees = super::low_level::get_rupees(ctx.cpu().proc)?;
+    if rupees > current_rupees {
+        errors.push(sim_error!(span, NotEnoughRupees(rupees, current_rupees)));
+        has_all = false;
+    }
+    if !has_all {
+        return Ok(());
+    }
+
+    // take the materials
+    for (material, need) in materials {
+        super::take_material(ctx, errors, material, (*need).max(0) as usize)?;
+        if ctx.is_aborted() {
+            return Ok(());
+        }
+    }
+    super::low_level::add_rupees(ctx.cpu().proc, -rupees)?;
+
+    // change the armor to the next star
+    linker::upgrade_armor(ctx.cpu(), item_ptr, &next_name)
+}
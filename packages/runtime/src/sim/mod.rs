@@ -14,8 +14,12 @@ mod runtime;
 pub use runtime::*;
 mod state;
 pub use state::*;
+mod search;
+pub use search::*;
 mod screen;
 pub use screen::*;
+mod shops;
+pub use shops::*;
 mod state_context;
 pub use state_context::*;
 mod snapshot;
@@ -1,4 +1,8 @@
-use blueflame::processor::CrashReport;
+use std::collections::BTreeMap;
+
+use blueflame::memory::WriteTrace;
+use blueflame::processor::{CrashReport, ExecTrace, Profile};
+use skybook_parser::ParseOutput;
 
 use crate::error::{ErrorReport, RuntimeViewError};
 use crate::{iv, sim};
@@ -8,6 +12,25 @@ pub struct RunOutput {
     /// State at each simulation step
     pub states: Vec<sim::State>,
     pub errors: Vec<ErrorReport>,
+    /// Writes to memory recorded at each simulation step,
+    /// empty if the run is not tracing writes
+    pub write_traces: Vec<WriteTrace>,
+    /// Instructions executed at each simulation step,
+    /// empty if the run is not profiled
+    pub profiles: Vec<StepProfile>,
+    /// Blocks executed at each simulation step,
+    /// empty if the run is not tracing the execution
+    pub exec_traces: Vec<ExecTrace>,
+    /// GDT flags watched in the run, see [`Run::with_watched_flags`](sim::Run::with_watched_flags)
+    pub watched_flags: Vec<iv::GdtFlagQuery>,
+}
+
+/// Instructions executed in one simulation step, see [`Run::with_profile`](sim::Run::with_profile)
+#[derive(Clone, Default)]
+pub struct StepProfile {
+    /// Type of the command executed in the step
+    pub command: String,
+    pub profile: Profile,
 }
 
 impl RunOutput {
@@ -63,6 +86,27 @@ impl RunOutput {
         Ok(sim::view::extract_gdt_view(&state.process)?)
     }
 
+    /// Query GDT flags by name or hash at the given step in the script
+    ///
+    /// Flags that are not found (for example, if the type is wrong) have `None` as the value
+    pub fn get_gdt_flags(
+        &self,
+        step: usize,
+        queries: &[iv::GdtFlagQuery],
+    ) -> Result<Vec<iv::GdtFlag>, RuntimeViewError> {
+        let Some(state) = self.get_state_by_step(step) else {
+            return Ok(Default::default());
+        };
+        let state = sim::view::view_game_state!(state);
+
+        Ok(sim::view::extract_gdt_flags(&state.process, queries)?)
+    }
+
+    /// Get the values of the watched GDT flags at the given step in the script
+    pub fn get_watched_gdt_flags(&self, step: usize) -> Result<Vec<iv::GdtFlag>, RuntimeViewError> {
+        self.get_gdt_flags(step, &self.watched_flags)
+    }
+
     /// Get the overworld view for the given step in the script
     pub fn get_overworld_items(&self, step: usize) -> Result<iv::Overworld, RuntimeViewError> {
         let Some(state) = self.get_state_by_step(step) else {
@@ -73,8 +117,134 @@ impl RunOutput {
         Ok(state.systems.overworld.to_iv())
     }
 
+    /// Get the changes to memory made by the given step in the script, grouped
+    /// by known structures
+    ///
+    /// If writes are not traced for the run (see [`Run::with_write_trace`](sim::Run::with_write_trace)),
+    /// an empty view is returned
+    pub fn get_memory_changes(&self, step: usize) -> Result<iv::MemoryChanges, RuntimeViewError> {
+        let Some(trace) = self.write_traces.get(step) else {
+            return Ok(Default::default());
+        };
+        let Some(mut state) = self.states.get(step) else {
+            return Ok(Default::default());
+        };
+        // the process is not kept if the game crashed in the step,
+        // so the structures are found with the state before the step
+        if let sim::Game::Crashed(_) = &state.game
+            && let Some(prev_state) = step.checked_sub(1).and_then(|x| self.states.get(x))
+        {
+            state = prev_state;
+        }
+        let state = sim::view::view_game_state!(state);
+
+        Ok(sim::view::extract_memory_changes(&state.process, trace)?)
+    }
+
+    /// Inspect the PauseMenuDataMgr at the given step in the script as a tree of typed fields,
+    /// with pointers annotated by what they point to
+    pub fn get_pmdm_tree(&self, step: usize) -> Result<iv::InspectNode, RuntimeViewError> {
+        let Some(state) = self.get_state_by_step(step) else {
+            return Ok(Default::default());
+        };
+        let state = sim::view::view_game_state!(state);
+
+        Ok(sim::view::extract_pmdm_tree(&state.process)?)
+    }
+
+    /// Get the instructions executed in the run in the folded stack format,
+    /// which can be consumed by flamegraph tools.
+    ///
+    /// The stacks are grouped by the command type of the step. If `by_step` is true,
+    /// they are further grouped by the step, otherwise the counts for the
+    /// same command type are added together.
+    ///
+    /// If the run is not profiled (see [`Run::with_profile`](sim::Run::with_profile)),
+    /// the output is empty
+    pub fn get_profile_folded(&self, by_step: bool) -> String {
+        let mut out = String::new();
+        // find a process to resolve the symbols
+        let Some(process) = self.states.iter().find_map(|x| match &x.game {
+            sim::Game::Running(game) => Some(&game.process),
+            _ => None,
+        }) else {
+            return out;
+        };
+        let main_start = process.main_start();
+        let symbols = process.symbols().map(|x| x.as_ref());
+
+        if by_step {
+            for (i, step) in self.profiles.iter().enumerate() {
+                let step_name = format!("step {i}");
+                let prefix = [step.command.as_str(), step_name.as_str()];
+                let profile = &step.profile;
+                profile.write_folded(&mut out, &prefix, main_start, symbols);
+            }
+            return out;
+        }
+
+        let mut by_command = BTreeMap::<&str, Profile>::new();
+        for step in &self.profiles {
+            by_command
+                .entry(step.command.as_str())
+                .or_default()
+                .merge(&step.profile);
+        }
+        for (command, profile) in by_command {
+            profile.write_folded(&mut out, &[command], main_start, symbols);
+        }
+        out
+    }
+
+    /// Compare the execution traces of the run against `expected` (for example,
+    /// recorded by another build), and find the first step where they are different.
+    ///
+    /// Returns the index of the step and the divergence formatted with up to `context`
+    /// events before it. Returns `None` if the traces are the same, or if the
+    /// execution is not traced for the run (see [`Run::with_exec_trace`](sim::Run::with_exec_trace))
+    pub fn find_exec_trace_divergence(
+        &self,
+        expected: &[ExecTrace],
+        context: usize,
+    ) -> Option<(usize, String)> {
+        let empty = ExecTrace::default();
+        let len = expected.len().max(self.exec_traces.len());
+        let (step, divergence) = (0..len).find_map(|i| {
+            let expected = expected.get(i).unwrap_or(&empty);
+            let actual = self.exec_traces.get(i).unwrap_or(&empty);
+            expected.first_divergence(actual, context).map(|x| (i, x))
+        })?;
+        let process = self.states.iter().find_map(|x| match &x.game {
+            sim::Game::Running(game) => Some(&game.process),
+            _ => None,
+        });
+        let main_start = process.map(|x| x.main_start()).unwrap_or_default();
+        let symbols = process.and_then(|x| x.symbols()).map(|x| x.as_ref());
+        Some((step, divergence.format(main_start, symbols)))
+    }
+
     /// Get the crash report for a step, if the game has crashed on that step
     pub fn get_crash_report(&self, step: usize) -> Option<&CrashReport> {
+        self.find_crash(step).map(|(_, report)| report)
+    }
+
+    /// Get the crash report for a step as a structured view, including the step
+    /// and command that crashed the game.
+    ///
+    /// If the game has not crashed at or before the step, a view with `is_crashed = false` is returned
+    pub fn get_crash_report_view(&self, step: usize, parsed: &ParseOutput) -> iv::CrashReport {
+        let Some((step, report)) = self.find_crash(step) else {
+            return Default::default();
+        };
+        let (command, span) = match parsed.steps.get(step) {
+            Some(x) => (sim::command_name(x.command()), (x.span().lo, x.span().hi)),
+            None => Default::default(),
+        };
+        sim::view::extract_crash_report(report, step, command, span)
+    }
+
+    /// Find the step where the game crashed, if the game has crashed at or before the step
+    fn find_crash(&self, step: usize) -> Option<(usize, &CrashReport)> {
         if self.states.is_empty() {
             return None;
         }
@@ -88,7 +258,7 @@ impl RunOutput {
                 | sim::Game::Running(_)
                 | sim::Game::Closed
                 | sim::Game::PreviousClosed => return None,
-                sim::Game::Crashed(crash_report) => return Some(crash_report),
+                sim::Game::Crashed(crash_report) => return Some((step, crash_report)),
                 sim::Game::PreviousCrash => {
                     if step == 0 {
                         // should be unreachable
@@ -129,6 +299,49 @@ impl RunOutput {
         }
     }
 
+    /// Get the GDT inventories of the manual save and all named saves in the given
+    /// step in the script, compared with the current GDT inventory, so the differences
+    /// a reload will make can be seen.
+    ///
+    /// The current GDT inventory is not available if the game is not running, but the
+    /// saves are still included
+    pub fn get_save_comparison(&self, step: usize) -> Result<iv::SaveComparison, RuntimeViewError> {
+        let Some(state) = self.get_state_by_step(step) else {
+            return Ok(Default::default());
+        };
+        let current = match &state.game {
+            sim::Game::Running(game) => Some(sim::view::extract_gdt_view(&game.process)?),
+            _ => None,
+        };
+        Ok(sim::view::extract_save_comparison(
+            current,
+            state.manual_save.as_deref(),
+            state.saves(),
+        )?)
+    }
+
+    /// Query GDT flags by name or hash in the save in the given step in the script
+    ///
+    /// If name is `None`, it uses the manual save.
+    /// If the named save doesn't exist, the result is empty
+    pub fn get_save_gdt_flags(
+        &self,
+        step: usize,
+        name: Option<&str>,
+        queries: &[iv::GdtFlagQuery],
+    ) -> Vec<iv::GdtFlag> {
+        match self
+            .get_state_by_step(step)
+            .and_then(|x| x.save_by_name(name))
+        {
+            None => vec![],
+            Some(save) => queries
+                .iter()
+                .map(|query| sim::view::query_gdt_flag(save.as_ref(), query))
+                .collect(),
+        }
+    }
+
     fn get_state_by_step(&self, step: usize) -> Option<&sim::State> {
         if self.states.is_empty() {
             return None;
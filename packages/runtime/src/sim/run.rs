@@ -1,16 +1,23 @@
 use std::sync::Arc;
 use std::sync::atomic::AtomicBool;
 
-use skybook_parser::ParseOutput;
+use blueflame::processor::AbortSignal;
+use skybook_parser::{ParseOutput, cir};
 
 use crate::error::MaybeAborted;
-use crate::sim;
+use crate::{iv, sim};
 
 pub struct Run {
     /// Handle for the running task
     handle: Arc<RunHandle>,
     /// Data produced by the run
     output: sim::RunOutput,
+    /// Record writes to memory in each step
+    trace_writes: bool,
+    /// Count the instructions executed in each step
+    profile: bool,
+    /// Record the blocks executed in each step
+    trace_exec: bool,
 }
 
 impl Run {
@@ -18,9 +25,52 @@ impl Run {
         Run {
             handle,
             output: Default::default(),
+            trace_writes: false,
+            profile: false,
+            trace_exec: false,
         }
     }
 
+    /// Record the writes to memory in each step, so the changes can be viewed
+    /// with [`RunOutput::get_memory_changes`](sim::RunOutput::get_memory_changes).
+    ///
+    /// The state cache of the runtime is not used when tracing, since the writes
+    /// are only recorded when the step is executed. Writes in a step
+    /// where the game is not running at the start (for example, when the
+    /// game is booted by the step) are not recorded
+    pub fn with_write_trace(mut self) -> Self {
+        self.trace_writes = true;
+        self
+    }
+
+    /// Count the instructions executed in each function in each step, so the
+    /// profile can be viewed with [`RunOutput::get_profile_folded`](sim::RunOutput::get_profile_folded).
+    ///
+    /// Like [`with_write_trace`](Self::with_write_trace), the state cache is not used,
+    /// and steps where the game is not running at the start are not profiled
+    pub fn with_profile(mut self) -> Self {
+        self.profile = true;
+        self
+    }
+
+    /// Record the blocks and hooks executed in each step, along with the registers
+    /// changed and memory written, so the traces in [`RunOutput::exec_traces`](sim::RunOutput::exec_traces)
+    /// can be compared against another build of the simulator.
+    ///
+    /// Like [`with_write_trace`](Self::with_write_trace), the state cache is not used,
+    /// and steps where the game is not running at the start are not traced
+    pub fn with_exec_trace(mut self) -> Self {
+        self.trace_exec = true;
+        self
+    }
+
+    /// Watch the GDT flags, so their values at each step can be viewed
+    /// with [`RunOutput::get_watched_gdt_flags`](sim::RunOutput::get_watched_gdt_flags)
+    pub fn with_watched_flags(mut self, queries: Vec<iv::GdtFlagQuery>) -> Self {
+        self.output.watched_flags = queries;
+        self
+    }
+
     /// Execute the parsed simulation script
     ///
     /// All errors that happened, including internal (e.g. game crash) or
@@ -42,17 +92,15 @@ impl Run {
     ///
     /// The notification will not be sent after the last step
     pub async fn run_parsed_with_notify<TFuture, F>(
-        mut self,
+        self,
         parsed: &ParseOutput,
         runtime: &sim::Runtime,
-        mut notify_fn: F,
+        notify_fn: F,
     ) -> MaybeAborted<sim::RunOutput>
     where
         F: FnMut(usize, &sim::RunOutput) -> TFuture,
         TFuture: std::future::Future,
     {
-        self.output.states.reserve(parsed.steps.len());
-
         let process = match runtime.initial_process() {
             Ok(x) => x,
             Err(e) => {
@@ -61,7 +109,40 @@ impl Run {
             }
         };
 
-        let mut state = sim::State::new(process);
+        self.run_internal(parsed, runtime, sim::State::new(process), true, notify_fn)
+            .await
+    }
+
+    /// Execute the parsed simulation script, starting from the given state
+    /// instead of a new state from the initial process (for example,
+    /// a state created with [`sim::State::from_pmdm_dump`]).
+    ///
+    /// The state cache of the runtime is not used, since it's keyed only by the commands
+    pub async fn run_parsed_from_state(
+        self,
+        parsed: &ParseOutput,
+        runtime: &sim::Runtime,
+        state: sim::State,
+    ) -> MaybeAborted<sim::RunOutput> {
+        self.run_internal(parsed, runtime, state, false, |_, _| async {})
+            .await
+    }
+
+    async fn run_internal<TFuture, F>(
+        mut self,
+        parsed: &ParseOutput,
+        runtime: &sim::Runtime,
+        mut state: sim::State,
+        use_cache: bool,
+        mut notify_fn: F,
+    ) -> MaybeAborted<sim::RunOutput>
+    where
+        F: FnMut(usize, &sim::RunOutput) -> TFuture,
+        TFuture: std::future::Future,
+    {
+        self.output.states.reserve(parsed.steps.len());
+        let use_cache = use_cache && !self.trace_writes && !self.profile && !self.trace_exec;
+
         let mut commands = Vec::with_capacity(parsed.steps.len());
         let mut ctx = sim::Context::new(self.handle, runtime);
 
@@ -89,10 +170,35 @@ impl Run {
 
             commands.push(step.command.clone());
 
-            let report = match runtime.find_cached(&commands) {
+            let cached = if use_cache {
+                runtime.find_cached(&commands)
+            } else {
+                None
+            };
+            let mut report = match cached {
                 Some(report) => report,
                 None => {
                     ctx.span = step.span();
+                    // the limits are for the whole step, which can
+                    // run the game multiple times
+                    if let sim::Game::Running(game) = &mut state.game {
+                        game.process.reset_watchdog();
+                    }
+                    if self.trace_writes
+                        && let sim::Game::Running(game) = &mut state.game
+                    {
+                        game.process.memory_mut().start_write_trace();
+                    }
+                    if self.profile
+                        && let sim::Game::Running(game) = &mut state.game
+                    {
+                        game.process.start_profile();
+                    }
+                    if self.trace_exec
+                        && let sim::Game::Running(game) = &mut state.game
+                    {
+                        game.process.start_exec_trace();
+                    }
 
                     let report = match state.execute_step(ctx.clone(), step).await {
                         Err(e) => {
@@ -101,8 +207,9 @@ impl Run {
                         }
                         Ok(report) => report,
                     };
-                    // check if the run is aborted
-                    if ctx.is_aborted() {
+                    // check if the run is aborted, either between
+                    // steps or in the middle of the step
+                    if ctx.is_aborted() || report.value.game.is_aborted() {
                         return MaybeAborted::Aborted;
                     }
 
@@ -110,12 +217,58 @@ impl Run {
                     // note we must only update the cache if the run
                     // is not aborted, since it could abort
                     // in the middle of a step (i.e. partially executed)
-                    runtime.set_cache(&commands, &report);
+                    if use_cache {
+                        runtime.set_cache(&commands, &report);
+                    }
 
                     report
                 }
             };
 
+            // stop the execution trace first, since it may own the write trace
+            if self.trace_exec {
+                let trace = match &mut report.value.game {
+                    sim::Game::Running(game) => game.process.stop_exec_trace(),
+                    // keep the blocks executed up to the crash
+                    sim::Game::Crashed(crash_report) => crash_report
+                        .exec_trace
+                        .take()
+                        .map(|x| *x)
+                        .unwrap_or_default(),
+                    _ => Default::default(),
+                };
+                self.output.exec_traces.push(trace);
+            }
+
+            if self.trace_writes {
+                let trace = match &mut report.value.game {
+                    sim::Game::Running(game) => game.process.memory_mut().stop_write_trace(),
+                    // keep the writes up to the crash
+                    sim::Game::Crashed(crash_report) => crash_report
+                        .write_trace
+                        .take()
+                        .map(|x| *x)
+                        .unwrap_or_default(),
+                    _ => Default::default(),
+                };
+                self.output.write_traces.push(trace);
+            }
+
+            if self.profile {
+                let profile = match &mut report.value.game {
+                    sim::Game::Running(game) => game.process.stop_profile(),
+                    // keep the instructions counted up to the crash
+                    sim::Game::Crashed(crash_report) => {
+                        crash_report.profile.take().map(|x| *x).unwrap_or_default()
+                    }
+                    _ => Default::default(),
+                };
+                self.output.profiles.push(sim::StepProfile {
+                    command: command_name(step.command()),
+                    profile,
+                });
+            }
+
             self.output.states.push(report.value.clone());
             self.output.errors.extend(report.errors);
             state = report.value;
@@ -125,6 +278,70 @@ impl Run {
     }
 }
 
+/// Get the name of the command type, for grouping profiles
+pub(crate) fn command_name(command: &cir::Command) -> String {
+    use cir::Command as X;
+    let name = match command {
+        X::Multi(..) => "Multi",
+        X::CoAccuratelySimulate => "CoAccuratelySimulate",
+        X::Get(..) => "Get",
+        X::PickUp(..) => "PickUp",
+        X::CoPauseDuring => "CoPauseDuring",
+        X::OpenInv => "OpenInv",
+        X::CloseInv => "CloseInv",
+        X::CoSmug => "CoSmug",
+        X::Hold(..) => "Hold",
+        X::Unhold => "Unhold",
+        X::CoOverworld => "CoOverworld",
+        X::Drop(..) => "Drop",
+        X::Dnp(..) => "Dnp",
+        X::Eat(..) => "Eat",
+        X::CookHeld => "CookHeld",
+        X::Cook(..) => "Cook",
+        X::Entangle(..) => "Entangle",
+        X::CoTargeting(..) => "CoTargeting",
+        X::Sort(..) => "Sort",
+        X::Overload(..) => "Overload",
+        X::Spawn(..) => "Spawn",
+        X::CoDpad => "CoDpad",
+        X::Equip(..) => "Equip",
+        X::Unequip(..) => "Unequip",
+        X::Use(..) => "Use",
+        X::CoPerUse(..) => "CoPerUse",
+        X::CoNonBreaking => "CoNonBreaking",
+        X::CoBreaking => "CoBreaking",
+        X::ThrowWeapon => "ThrowWeapon",
+        X::Display(..) => "Display",
+        X::OpenShop(..) => "OpenShop",
+        X::CloseShop => "CloseShop",
+        X::Buy(..) => "Buy",
+        X::Sell(..) => "Sell",
+        X::CoSameDialog => "CoSameDialog",
+        X::Upgrade(..) => "Upgrade",
+        X::Hestu(..) => "Hestu",
+        X::Pray(..) => "Pray",
+        X::Save(..) => "Save",
+        X::Reload(..) => "Reload",
+        X::CloseGame => "CloseGame",
+        X::NewGame => "NewGame",
+        X::SuBreak(..) => "SuBreak",
+        X::SuInit(..) => "SuInit",
+        X::SuAddSlot(..) => "SuAddSlot",
+        X::SuSwap(..) => "SuSwap",
+        X::SuWrite(..) => "SuWrite",
+        X::SuRemove(..) => "SuRemove",
+        X::SuSetGdt(..) => "SuSetGdt",
+        X::SuArrowlessSmuggle => "SuArrowlessSmuggle",
+        X::SuSystem(..) => "SuSystem",
+        X::SuTrialStart => "SuTrialStart",
+        X::SuTrialEnd => "SuTrialEnd",
+        X::Roast(..) => "Roast",
+        X::Boil(..) => "Boil",
+        X::Freeze(..) => "Freeze",
+    };
+    name.to_string()
+}
+
 /// Handle for a running simulation task.
 ///
 /// See [`Run`] for more information.
@@ -176,3 +393,11 @@ impl RunHandle {
         x
     }
 }
+
+/// Stop the processor in the middle of a step when the run is aborted,
+/// so long calls into the game can be interrupted
+impl AbortSignal for RunHandle {
+    fn is_aborted(&self) -> bool {
+        RunHandle::is_aborted(self)
+    }
+}
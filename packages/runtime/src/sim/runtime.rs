@@ -1,6 +1,6 @@
-use std::sync::Mutex;
+use std::sync::{Arc, Mutex};
 
-use blueflame::env::{DlcVer, Environment, GameVer};
+use blueflame::env::{DlcVer, Environment, Feature, GameVer};
 use blueflame::processor::{Cpu1, Process};
 use blueflame::{linker, program};
 use hashlink::LruCache;
@@ -13,6 +13,9 @@ use crate::sim;
 #[doc(inline)]
 pub use skybook_api::runtime::sim::RuntimeInitParams;
 
+/// Default maximum number of instructions executed in one step
+pub const DEFAULT_INSTRUCTION_LIMIT: u64 = 1_000_000_000;
+
 pub struct Runtime {
     executor: Executor,
     initial_process: Mutex<Option<Process>>,
@@ -60,7 +63,7 @@ impl Runtime {
         let program = match program::unpack_zc(image, &mut program_bytes) {
             Err(e) => {
                 cu::error!("failed to unpack blueflame image: {e}");
-                return Err(RuntimeInitError::BadImage);
+                return Err(RuntimeInitError::BadImage(e.to_string()));
             }
             Ok(program) => program,
         };
@@ -139,9 +142,32 @@ impl Runtime {
             .take_if(|x| *x != 0)
             .unwrap_or(0x4000);
 
-        let process = match linker::init_process(
+        let mut features = Feature::default_const();
+        if let Some(params) = params {
+            for name in &params.enable_features {
+                let Some(feature) = Feature::parse(name.trim()) else {
+                    return Err(RuntimeInitError::UnknownFeature(name.to_string()));
+                };
+                features.insert(feature);
+            }
+            for name in &params.disable_features {
+                let Some(feature) = Feature::parse(name.trim()) else {
+                    return Err(RuntimeInitError::UnknownFeature(name.to_string()));
+                };
+                features.remove(feature);
+            }
+        }
+        cu::debug!("features: {features:?}");
+
+        let instruction_limit = params
+            .map(|x| x.instruction_limit)
+            .take_if(|x| *x != 0)
+            .unwrap_or(DEFAULT_INSTRUCTION_LIMIT);
+
+        let mut process = match linker::init_process(
             program,
             env.dlc_ver,
+            features,
             stack_start,
             stack_size,
             pmdm_addr,
@@ -153,6 +179,9 @@ impl Runtime {
             }
             Ok(x) => x,
         };
+        process.set_instruction_limit(Some(instruction_limit));
+        let time_limit = params.map(|x| x.time_limit).take_if(|x| *x != 0);
+        process.set_time_limit(Arc::new(exec::Clock), time_limit);
 
         {
             let mut p = self
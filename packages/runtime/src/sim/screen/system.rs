@@ -42,6 +42,10 @@ pub struct ScreenSystem {
     ///
     /// While holding, you can only hold and unhold in pouch
     pub holding_in_inventory: bool,
+
+    /// The shop of the NPC in the current shop dialog, if it's
+    /// a known shop
+    shop: Option<Arc<sim::ShopDef>>,
 }
 
 /// Type of the screen and the data they hold
@@ -68,6 +72,16 @@ impl ScreenSystem {
         Arc::make_mut(&mut self.screen)
     }
 
+    /// Get the shop of the NPC being talked to, if the current
+    /// screen is a shop screen and the shop is known
+    pub fn current_shop(&self) -> Option<Arc<sim::ShopDef>> {
+        if self.screen.is_shop() {
+            self.shop.clone()
+        } else {
+            None
+        }
+    }
+
     pub fn set_remove_held_after_dialog(&mut self) {
         self.remove_held_item_after_dialog = true;
     }
@@ -110,10 +124,13 @@ impl ScreenSystem {
         Ok(true)
     }
 
+    /// Transition to the shop buying screen. If talking to a new NPC
+    /// from the overworld, `shop` is the shop of the NPC
     pub fn transition_to_shop_buying(
         &mut self,
         ctx: &mut sim::Context<&mut Cpu2>,
         overworld: &mut sim::OverworldSystem,
+        shop: Option<Arc<sim::ShopDef>>,
         manual: bool,
         errors: &mut Vec<ErrorReport>,
     ) -> Result<bool, processor::Error> {
@@ -162,6 +179,7 @@ impl ScreenSystem {
         if manual {
             self.is_manually_switched = true;
         }
+        self.shop = shop;
         *self.current_screen_mut() = Screen::Shop(sim::ShopScreen::Buy);
 
         Ok(true)
@@ -213,6 +231,7 @@ impl ScreenSystem {
         if manual {
             self.is_manually_switched = true;
         }
+        self.shop = None;
         *self.current_screen_mut() = Screen::Shop(sim::ShopScreen::open_sell(ctx.cpu())?);
 
         Ok(true)
@@ -238,6 +257,7 @@ impl ScreenSystem {
         }
         // after returning to overworld, allow automatic screen switch again
         self.is_manually_switched = false;
+        self.shop = None;
         let screen = Arc::make_mut(&mut self.screen);
         let drop_items = self.remove_held_item_after_dialog;
         self.remove_held_item_after_dialog = false;
@@ -256,6 +276,7 @@ impl ScreenSystem {
         self.remove_held_item_after_dialog = false;
         self.equipped_items_to_remove_after_dialog.clear();
         self.holding_in_inventory = false;
+        self.shop = None;
         self.screen = Arc::new(Screen::Overworld);
     }
 }
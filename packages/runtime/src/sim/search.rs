@@ -0,0 +1,246 @@
+use std::collections::HashSet;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::sync::Arc;
+
+use skybook_parser::{Span, cir};
+
+use crate::error::MaybeAborted;
+use crate::{exec, iv, sim};
+
+/// Goal for [`search_routes`]
+pub trait SearchGoal {
+    /// Check if the goal is reached with the inventory state
+    fn is_reached(&self, pouch: &iv::PouchList, gdt: &iv::Gdt) -> bool;
+
+    /// Heuristic score of a state, higher is closer to the goal.
+    ///
+    /// This is only used to rank states when the search is limited
+    /// by a beam width.
+    fn score(&self, _pouch: &iv::PouchList, _gdt: &iv::Gdt) -> i64 {
+        0
+    }
+}
+
+impl<F> SearchGoal for F
+where
+    F: Fn(&iv::PouchList, &iv::Gdt) -> bool,
+{
+    fn is_reached(&self, pouch: &iv::PouchList, gdt: &iv::Gdt) -> bool {
+        self(pouch, gdt)
+    }
+}
+
+/// Parameters for [`search_routes`]
+#[derive(Debug, Clone)]
+pub struct SearchParams {
+    /// Commands that can be used for each step of the route
+    pub candidates: Vec<cir::Command>,
+    /// Max number of steps in a route
+    pub max_depth: usize,
+    /// If set, only keep the best N states (by [`SearchGoal::score`])
+    /// of each depth to expand (i.e. beam search). Otherwise, all states
+    /// are expanded (i.e. BFS)
+    pub beam_width: Option<usize>,
+    /// Max number of routes to return
+    pub max_results: usize,
+}
+
+impl Default for SearchParams {
+    fn default() -> Self {
+        Self {
+            candidates: vec![],
+            max_depth: 4,
+            beam_width: None,
+            max_results: 1,
+        }
+    }
+}
+
+/// A route found by [`search_routes`]
+#[derive(Debug, Clone)]
+pub struct Route {
+    /// Commands to execute from the initial state to reach the goal
+    pub commands: Vec<cir::Command>,
+}
+
+impl Route {
+    /// Convert the route to a script, one command per line
+    pub fn to_script(&self) -> String {
+        let mut out = String::new();
+        for command in &self.commands {
+            command.to_script(&mut out);
+            out.push('\n');
+        }
+        out
+    }
+}
+
+/// Output of [`search_routes`]
+#[derive(Debug, Clone, Default)]
+pub struct SearchOutput {
+    /// Shortest routes found, all with the same length.
+    /// Empty if the goal is not reachable within the max depth
+    pub routes: Vec<Route>,
+    /// Number of unique states visited
+    pub visited: usize,
+}
+
+/// Search for the shortest routes from the initial state that reach the goal,
+/// by executing the candidate commands.
+///
+/// States are deduplicated by the pouch view, so routes that reach
+/// the same pouch state only get expanded once. A step that errors
+/// (not including warnings), crashes the game or leaves the inventory in a state
+/// that cannot be viewed is not expanded further.
+///
+/// Returns an error if a step cannot be executed by the runtime (i.e. an error
+/// in the executor, not in the simulation), which is different from the search being aborted
+pub async fn search_routes(
+    ctx: sim::Context<&sim::Runtime>,
+    initial: sim::State,
+    params: &SearchParams,
+    goal: &impl SearchGoal,
+) -> Result<MaybeAborted<SearchOutput>, exec::Error> {
+    let mut output = SearchOutput::default();
+    let mut seen = HashSet::new();
+
+    let Some((pouch, gdt)) = view_search_state(&initial) else {
+        return Ok(MaybeAborted::Ok(output));
+    };
+    seen.insert(pouch_hash(&pouch));
+    output.visited += 1;
+    if goal.is_reached(&pouch, &gdt) {
+        output.routes.push(Route { commands: vec![] });
+        return Ok(MaybeAborted::Ok(output));
+    }
+
+    let notes: Arc<str> = Arc::from("");
+    let mut layer = vec![(initial, vec![])];
+
+    for depth in 0..params.max_depth {
+        cu::debug!("route search: depth {}, {} states", depth, layer.len());
+        let mut next_layer = vec![];
+        for (state, route) in &layer {
+            for command in &params.candidates {
+                if ctx.is_aborted() {
+                    return Ok(MaybeAborted::Aborted);
+                }
+                let step = cir::Step::new(Span::new(0, 0), command.clone(), Arc::clone(&notes));
+                let mut state = state.clone();
+                if let sim::Game::Running(game) = &mut state.game {
+                    game.process.reset_watchdog();
+                }
+                let report = state.execute_step(ctx.clone(), &step).await?;
+                if report.value.game.is_aborted() {
+                    return Ok(MaybeAborted::Aborted);
+                }
+                if report.errors.iter().any(|x| !x.is_warning) {
+                    continue;
+                }
+                let Some((pouch, gdt)) = view_search_state(&report.value) else {
+                    continue;
+                };
+                if !seen.insert(pouch_hash(&pouch)) {
+                    continue;
+                }
+                output.visited += 1;
+
+                let mut route = route.clone();
+                route.push(command.clone());
+                if goal.is_reached(&pouch, &gdt) {
+                    output.routes.push(Route { commands: route });
+                    if output.routes.len() >= params.max_results {
+                        return Ok(MaybeAborted::Ok(output));
+                    }
+                    continue;
+                }
+                let score = goal.score(&pouch, &gdt);
+                next_layer.push((score, report.value, route));
+            }
+        }
+        // only return the shortest routes
+        if !output.routes.is_empty() || next_layer.is_empty() {
+            break;
+        }
+        if let Some(beam_width) = params.beam_width {
+            // stable sort to keep the order of the candidates for same scores
+            next_layer.sort_by(|a, b| b.0.cmp(&a.0));
+            next_layer.truncate(beam_width);
+        }
+        layer = next_layer
+            .into_iter()
+            .map(|(_, state, route)| (state, route))
+            .collect();
+    }
+
+    Ok(MaybeAborted::Ok(output))
+}
+
+/// Get the inventory views of a state for checking the goal.
+/// Returns `None` if the game is crashed/closed or the inventory cannot be viewed
+fn view_search_state(state: &sim::State) -> Option<(iv::PouchList, iv::Gdt)> {
+    let game = match &state.game {
+        sim::Game::Uninit => return Some(Default::default()),
+        sim::Game::Running(game) => game,
+        _ => return None,
+    };
+    let pouch = sim::view::extract_pouch_view(&game.process, &game.systems).ok()?;
+    let gdt = sim::view::extract_gdt_view(&game.process).ok()?;
+    Some((pouch, gdt))
+}
+
+/// Hash the pouch view for deduplicating states.
+///
+/// The view contains floats, so it cannot derive `Hash`. The floats are hashed
+/// by their bits, which is fine since the values are only compared for equality
+fn pouch_hash(pouch: &iv::PouchList) -> u64 {
+    let mut h = DefaultHasher::new();
+    pouch.count.hash(&mut h);
+    pouch.are_tabs_valid.hash(&mut h);
+    pouch.num_tabs.hash(&mut h);
+    for tab in &pouch.tabs {
+        tab.item_idx.hash(&mut h);
+        tab.tab_type.hash(&mut h);
+    }
+    pouch.entangled_tab.hash(&mut h);
+    pouch.entangled_slot.hash(&mut h);
+    (pouch.screen as u8).hash(&mut h);
+    pouch.is_holding_in_inventory.hash(&mut h);
+    pouch.is_arrowless_smuggle.hash(&mut h);
+    pouch.is_trial_mode.hash(&mut h);
+    pouch.is_menu_overloaded.hash(&mut h);
+    pouch.items.len().hash(&mut h);
+    for item in &pouch.items {
+        hash_pouch_item(item, &mut h);
+    }
+    h.finish()
+}
+
+fn hash_pouch_item(item: &iv::PouchItem, h: &mut DefaultHasher) {
+    item.common.actor_name.hash(h);
+    item.common.value.hash(h);
+    item.common.is_equipped.hash(h);
+    item.item_type.hash(h);
+    item.item_use.hash(h);
+    item.is_in_inventory.hash(h);
+    item.is_no_icon.hash(h);
+    item.data.effect_value.hash(h);
+    item.data.effect_duration.hash(h);
+    item.data.sell_price.hash(h);
+    item.data.effect_id.to_bits().hash(h);
+    item.data.effect_level.to_bits().hash(h);
+    item.ingredients.hash(h);
+    item.holding_count.hash(h);
+    item.prompt_entangled.hash(h);
+    item.node_addr.as_u64().hash(h);
+    item.node_valid.hash(h);
+    item.node_pos.hash(h);
+    item.node_prev.as_u64().hash(h);
+    item.node_next.as_u64().hash(h);
+    item.allocated_idx.hash(h);
+    item.unallocated_idx.hash(h);
+    item.tab_idx.hash(h);
+    item.tab_slot.hash(h);
+    item.accessible.hash(h);
+    item.dpad_accessible.hash(h);
+}
@@ -0,0 +1,186 @@
+use std::collections::BTreeMap;
+
+use blueflame::program::GameData;
+
+/// Definition of a shop, i.e. what an NPC sells
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShopDef {
+    /// Display name of the shop
+    pub name: String,
+    /// Items sold in the shop
+    pub items: Vec<ShopItemDef>,
+}
+
+/// Definition of an item sold in a shop.
+///
+/// Items are sold at the buying price in the actor data
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShopItemDef {
+    /// Actor name of the item
+    pub actor: String,
+    /// Number of items in stock. `None` if the shop never runs out
+    pub stock: Option<u32>,
+    /// When the stock is refilled
+    pub restock: Restock,
+}
+
+/// Restock behavior of an item in a shop
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Restock {
+    /// The stock is never refilled once it's sold out
+    Never,
+    /// The stock is refilled when the stage is regenerated
+    /// (i.e. on reload or a loading screen)
+    OnStageRegen,
+}
+
+/// An NPC with a shop that can be selected with `talk-to`
+#[derive(Debug, PartialEq, Eq)]
+pub struct ShopNpc {
+    /// Names that can be used to select the NPC with `talk-to`.
+    /// The first one is the display name
+    pub names: &'static [&'static str],
+    /// Actor name of the NPC, used to find the items sold in the game data
+    pub actor: &'static str,
+    /// Items with limited stock. This is also the list of items sold if
+    /// the shop data is not in the program image
+    pub stock: &'static [ShopStockDef],
+}
+
+/// Stock of an item in a shop, which is not in the game data
+#[derive(Debug, PartialEq, Eq)]
+pub struct ShopStockDef {
+    /// Actor name of the item
+    pub actor: &'static str,
+    /// Number of items in stock
+    pub stock: u32,
+    /// When the stock is refilled
+    pub restock: Restock,
+}
+
+/// Known shop NPCs.
+///
+/// Other NPCs can be selected by their actor name if the shop data is in the program image.
+/// Talking to an NPC that is not known opens a shop that sells everything without stock limit
+pub static SHOP_NPCS: &[ShopNpc] = &[ShopNpc {
+    names: &["beedle"],
+    actor: "Npc_TripMaster_00",
+    stock: &[
+        ShopStockDef {
+            actor: "NormalArrow",
+            stock: 20,
+            restock: Restock::OnStageRegen,
+        },
+        ShopStockDef {
+            actor: "FireArrow",
+            stock: 10,
+            restock: Restock::OnStageRegen,
+        },
+        ShopStockDef {
+            actor: "IceArrow",
+            stock: 10,
+            restock: Restock::OnStageRegen,
+        },
+        ShopStockDef {
+            actor: "ElectricArrow",
+            stock: 10,
+            restock: Restock::OnStageRegen,
+        },
+    ],
+}];
+
+impl ShopNpc {
+    /// Find the NPC by the name used in `talk-to` (case-insensitive)
+    pub fn find(name: &str) -> Option<&'static ShopNpc> {
+        SHOP_NPCS
+            .iter()
+            .find(|npc| npc.names.iter().any(|x| x.eq_ignore_ascii_case(name)))
+    }
+}
+
+impl ShopDef {
+    /// Load the shop of the NPC used in `talk-to`.
+    ///
+    /// The items sold are read from the shop data in the program image. The NPC
+    /// can be a known name in [`SHOP_NPCS`], or the actor name of the NPC.
+    /// Only the known NPCs have limited stock, items in other shops never run out.
+    /// Returns `None` if the shop is not known
+    pub fn load(npc: &str, game_data: &GameData) -> Option<Self> {
+        let known = ShopNpc::find(npc);
+        let (name, actor) = match known {
+            Some(known) => (
+                known.names.first().copied().unwrap_or_default(),
+                known.actor,
+            ),
+            None => (npc, npc),
+        };
+        let stock = known.map(|x| x.stock).unwrap_or_default();
+        let actors: Vec<&str> = match game_data.shop_data().and_then(|x| x.get(actor)) {
+            Some(items) => items.iter().map(|x| x.as_str()).collect(),
+            None => {
+                if stock.is_empty() {
+                    return None;
+                }
+                stock.iter().map(|x| x.actor).collect()
+            }
+        };
+        let items = actors
+            .into_iter()
+            .map(|actor| {
+                let stock = stock.iter().find(|x| x.actor == actor);
+                ShopItemDef {
+                    actor: actor.to_string(),
+                    stock: stock.map(|x| x.stock),
+                    restock: stock.map(|x| x.restock).unwrap_or(Restock::Never),
+                }
+            })
+            .collect();
+        Some(Self {
+            name: name.to_string(),
+            items,
+        })
+    }
+
+    /// Get the item sold in the shop by actor name
+    pub fn item(&self, actor: &str) -> Option<&ShopItemDef> {
+        self.items.iter().find(|x| x.actor == actor)
+    }
+}
+
+/// Simulation of the remaining stock in shops
+#[derive(Debug, Default, Clone)]
+pub struct ShopSystem {
+    /// Number of items sold since the last restock, by (shop name, actor name)
+    sold: BTreeMap<(String, String), (u32, Restock)>,
+}
+
+impl ShopSystem {
+    /// Get the number of the item remaining in the shop, `None` if there is no limit
+    pub fn remaining(&self, shop: &ShopDef, item: &ShopItemDef) -> Option<u32> {
+        let stock = item.stock?;
+        let sold = self
+            .sold
+            .get(&(shop.name.clone(), item.actor.clone()))
+            .map(|(sold, _)| *sold)
+            .unwrap_or_default();
+        Some(stock.saturating_sub(sold))
+    }
+
+    /// Record that `amount` of the item is bought from the shop
+    pub fn sell(&mut self, shop: &ShopDef, item: &ShopItemDef, amount: u32) {
+        if item.stock.is_none() {
+            return;
+        }
+        let (sold, _) = self
+            .sold
+            .entry((shop.name.clone(), item.actor.clone()))
+            .or_insert((0, item.restock));
+        *sold = sold.saturating_add(amount);
+    }
+
+    /// Refill the stock that is restocked when the stage is regenerated
+    pub fn restock_on_stage_regen(&mut self) {
+        self.sold
+            .retain(|_, (_, restock)| *restock == Restock::Never);
+    }
+}
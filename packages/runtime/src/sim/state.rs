@@ -2,6 +2,7 @@ use std::collections::BTreeMap;
 use std::sync::Arc;
 
 use blueflame::game::{gdt, singleton_instance};
+use blueflame::linker;
 use blueflame::processor::{CrashReport, Process};
 use skybook_parser::{Span, cir};
 
@@ -36,6 +37,23 @@ impl State {
             manual_save: None,
         }
     }
+
+    /// Create a state where the game is already running, with PMDM
+    /// loaded from a memory dump. See [`linker::load_pmdm_dump`].
+    ///
+    /// Starting a new game from this state will use the initial process,
+    /// which does not have the dump
+    pub fn from_pmdm_dump(
+        initial_process: Process,
+        dump: &linker::PmdmDump,
+    ) -> Result<Self, linker::Error> {
+        let mut process = initial_process.clone();
+        linker::load_pmdm_dump(&mut process, dump)?;
+        let mut state = Self::new(initial_process);
+        state.game = Game::Running(Box::new(GameState::new(process)));
+        Ok(state)
+    }
+
     /// Get names of all saves
     pub fn save_names(&self) -> Vec<String> {
         self.saves
@@ -44,6 +62,10 @@ impl State {
             .map(|(n, _)| n.to_string())
             .collect()
     }
+    /// Get all named saves, in the order they were first saved
+    pub fn saves(&self) -> &[(String, Arc<gdt::TriggerParam>)] {
+        &self.saves
+    }
     /// Get a manual save (if name is `None`) or a named save
     pub fn save_by_name(&self, name: Option<&str>) -> Option<Arc<gdt::TriggerParam>> {
         match name {
@@ -139,6 +161,8 @@ pub struct GameSystems {
     pub screen: sim::ScreenSystem,
     /// Simulation of the overworld
     pub overworld: sim::OverworldSystem,
+    /// Simulation of the stock in shops
+    pub shop: sim::ShopSystem,
 }
 
 impl GameSystems {
@@ -265,10 +289,13 @@ impl State {
             }
             X::Use(item, times) => self.handle_use(ctx, item, *times, args.as_deref()).await,
 
-            X::OpenShop => self.handle_open_shop(ctx).await,
+            X::OpenShop(npc) => self.handle_open_shop(ctx, npc.as_deref()).await,
             X::CloseShop => self.handle_close_shop(ctx).await,
             X::Sell(items) => self.handle_sell(ctx, items).await,
             X::Buy(items) => self.handle_buy(ctx, items, args.as_deref()).await,
+            X::Upgrade(item) => self.handle_upgrade(ctx, item).await,
+            X::Hestu(category) => self.handle_hestu(ctx, category, args.as_deref()).await,
+            X::Pray(target, times) => self.handle_pray(ctx, *target, *times).await,
 
             X::Save(name) => self.handle_save(ctx, name.as_deref()).await,
             X::Reload(name) => self.handle_reload(ctx, name.as_deref(), false).await,
@@ -513,10 +540,24 @@ impl State {
     async fn handle_open_shop(
         self,
         rt: sim::Context<&sim::Runtime>,
+        npc: Option<&str>,
     ) -> Result<Report<Self>, exec::Error> {
         cu::debug!("handling OPEN-SHOP");
+        let npc = npc.map(|x| x.to_string());
         execute_command!(self, rt, cpu, sys, errors => {
-            sys.screen.transition_to_shop_buying(&mut cpu, &mut sys.overworld, true, errors)?;
+            // talking to an NPC again ends the current dialog first,
+            // so the shop is reset for the new NPC
+            if npc.is_some() && sys.screen.current_screen().is_shop() {
+                if !sys.screen.transition_to_overworld(&mut cpu, &mut sys.overworld, false, errors)? {
+                    return Ok(());
+                }
+                sys.overworld.despawn_items();
+            }
+            let shop = npc
+                .as_deref()
+                .and_then(|npc| sim::ShopDef::load(npc, cpu.cpu().proc.game_data()))
+                .map(Arc::new);
+            sys.screen.transition_to_shop_buying(&mut cpu, &mut sys.overworld, shop, true, errors)?;
             Ok(())
         })
     }
@@ -554,6 +595,44 @@ impl State {
         })
     }
 
+    async fn handle_upgrade(
+        self,
+        rt: sim::Context<&sim::Runtime>,
+        item: &cir::ItemSelectSpec,
+    ) -> Result<Report<Self>, exec::Error> {
+        cu::debug!("handling UPGRADE");
+        let item = item.clone();
+        execute_command!(self, rt, cpu, sys, errors => {
+            sim::actions::upgrade_armor(&mut cpu, sys, errors, &item)
+        })
+    }
+
+    async fn handle_hestu(
+        self,
+        rt: sim::Context<&sim::Runtime>,
+        category: &cir::CategorySpec,
+        args: Option<&StateArgs>,
+    ) -> Result<Report<Self>, exec::Error> {
+        cu::debug!("handling HESTU");
+        let per_use = args.and_then(|x| x.per_use);
+        let category = category.clone();
+        execute_command!(self, rt, cpu, sys, errors => {
+            sim::actions::hestu_upgrade(&mut cpu, sys, errors, &category, per_use)
+        })
+    }
+
+    async fn handle_pray(
+        self,
+        rt: sim::Context<&sim::Runtime>,
+        target: cir::PrayTarget,
+        times: usize,
+    ) -> Result<Report<Self>, exec::Error> {
+        cu::debug!("handling PRAY");
+        execute_command!(self, rt, cpu, sys, errors => {
+            sim::actions::pray(&mut cpu, sys, errors, target, times)
+        })
+    }
+
     async fn handle_buy(
         self,
         rt: sim::Context<&sim::Runtime>,
@@ -769,14 +848,16 @@ impl State {
         for (name, data) in self.saves.iter() {
             saves.insert(name.clone(), Arc::clone(data));
         }
-        let manual_save = self.manual_save.clone();
+        let sys_state = sim::actions::system::SysCommandState {
+            saves,
+            manual_save: self.manual_save.clone(),
+            ..Default::default()
+        };
 
         let new_state = execute_command!(self, rt, cpu, sys, errors => {
-            let mut manual_save = manual_save;
-            let mut dlc_version = None;
-            sim::actions::system::exec_sys_commands(&mut cpu, sys,
-                errors, &cmds, &mut saves, &mut manual_save, &mut dlc_version)?;
-            if send.send((saves, manual_save, dlc_version)).is_err() {
+            let mut sys_state = sys_state;
+            sim::actions::system::exec_sys_commands(&mut cpu, sys, errors, &cmds, &mut sys_state)?;
+            if send.send(sys_state).is_err() {
                 cu::error!("failed to send system command output to runtime main thread");
             }
             Ok(())
@@ -788,7 +869,13 @@ impl State {
             .ok();
 
         Ok(new_state.map(|mut state| {
-            if let Some((mut out_saves, out_manual_save, dlc_version)) = data {
+            if let Some(sim::actions::system::SysCommandState {
+                saves: mut out_saves,
+                manual_save: out_manual_save,
+                dlc_version,
+                features,
+            }) = data
+            {
                 let saves = Arc::make_mut(&mut state.saves);
                 saves.retain_mut(|(name, data)| match out_saves.remove(name) {
                     Some(out_data) => {
@@ -809,6 +896,10 @@ impl State {
                         }
                     }
                 }
+                if let Some(features) = features {
+                    // same for features
+                    state.initial_process.set_features(features);
+                }
             }
             state
         }))
@@ -166,6 +166,13 @@ impl sim::Game {
             },
         }
     }
+
+    /// Check if the game is stopped in the middle of the step because
+    /// the run is aborted. The state is only partially executed, so it
+    /// should be discarded instead of being used as the result of the step
+    pub fn is_aborted(&self) -> bool {
+        matches!(self, sim::Game::Crashed(x) if x.error == processor::Error::Aborted)
+    }
 }
 
 #[derive(Clone)]
@@ -227,15 +234,19 @@ impl Context<&mut Cpu1> {
         let span = self.span;
         let handle = self.handle;
         let cpu1 = self.inner;
+        state.process.set_abort_signal(Some(handle.clone()));
         let mut cpu2 = Cpu2::new(cpu1, &mut state.process);
-        cpu2.with_crash_report(|cpu2| {
+        let result = cpu2.with_crash_report(|cpu2| {
             let ctx = Context {
                 span,
                 handle,
                 inner: cpu2,
             };
             f(ctx, &mut state.systems)
-        })?;
+        });
+        // don't keep the handle in the state after the step
+        state.process.set_abort_signal(None);
+        result?;
         Ok(state)
     }
 
@@ -255,16 +266,20 @@ impl Context<&mut Cpu1> {
         let span = self.span;
         let handle = self.handle;
         let cpu1 = self.inner;
+        state.process.set_abort_signal(Some(handle.clone()));
         let mut cpu2 = Cpu2::new(cpu1, &mut state.process);
         let mut errors = vec![];
-        cpu2.with_crash_report(|cpu2| {
+        let result = cpu2.with_crash_report(|cpu2| {
             let ctx = Context {
                 span,
                 handle,
                 inner: cpu2,
             };
             f(ctx, &mut state.systems, &mut errors)
-        })?;
+        });
+        // don't keep the handle in the state after the step
+        state.process.set_abort_signal(None);
+        result?;
         Ok(Report::with_errors(state, errors))
     }
 }
@@ -284,7 +299,8 @@ impl IntoGameReport for Result<sim::GameState, CrashReport> {
         match self {
             Ok(game_state) => Report::new(sim::Game::Running(Box::new(game_state))),
             Err(crash_report) => {
-                Report::error(sim::Game::Crashed(crash_report), sim_error!(span, Crash))
+                let error = crash_error(span, &crash_report);
+                Report::error(sim::Game::Crashed(crash_report), error)
             }
         }
     }
@@ -295,7 +311,8 @@ impl IntoGameReport for Result<Report<sim::GameState>, CrashReport> {
         match self {
             Ok(report) => report.map(|x| sim::Game::Running(Box::new(x))),
             Err(crash_report) => {
-                Report::error(sim::Game::Crashed(crash_report), sim_error!(span, Crash))
+                let error = crash_error(span, &crash_report);
+                Report::error(sim::Game::Crashed(crash_report), error)
             }
         }
     }
@@ -306,3 +323,44 @@ impl IntoGameReport for Report<sim::Game> {
         self
     }
 }
+
+/// Get the error to report for the crash
+fn crash_error(span: Span, crash_report: &CrashReport) -> ErrorReport {
+    match crash_report.error {
+        processor::Error::InstructionLimitReached {
+            limit,
+            hot_start,
+            hot_end,
+        } => {
+            let range = format_code_range(crash_report, hot_start, hot_end);
+            sim_error!(span, InstructionLimitReached(limit, range))
+        }
+        processor::Error::TimeLimitReached {
+            limit_ms,
+            hot_start,
+            hot_end,
+        } => {
+            let range = format_code_range(crash_report, hot_start, hot_end);
+            sim_error!(span, TimeLimitReached(limit_ms, range))
+        }
+        // the result of an aborted run is discarded, so this is not shown
+        _ => sim_error!(span, Crash),
+    }
+}
+
+/// Format the range of code as `main+0xSTART-main+0xEND <function>`
+fn format_code_range(crash_report: &CrashReport, start: u64, end: u64) -> String {
+    let main_start = crash_report.main_start;
+    let (Some(start), Some(end)) = (start.checked_sub(main_start), end.checked_sub(main_start))
+    else {
+        return format!("0x{start:016x}-0x{end:016x}");
+    };
+    let range = format!("main+0x{start:08x}-main+0x{end:08x}");
+    let function = u32::try_from(start)
+        .ok()
+        .and_then(|x| crash_report.symbols.as_ref()?.format_main_offset(x));
+    match function {
+        Some(function) => format!("{range} <{function}>"),
+        None => range,
+    }
+}
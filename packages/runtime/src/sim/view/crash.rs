@@ -0,0 +1,105 @@
+use blueflame::processor::{CrashReport, FrameType, reg};
+
+use crate::iv;
+
+/// Convert the crash report to the structured view.
+///
+/// `step`, `command` and `span` are of the step that crashed the game
+pub fn extract_crash_report(
+    report: &CrashReport,
+    step: usize,
+    command: String,
+    span: (usize, usize),
+) -> iv::CrashReport {
+    let main_start = report.main_start;
+    let symbols = report.symbols.as_deref();
+    let resolve = |addr: u64| {
+        addr.checked_sub(main_start)
+            .and_then(|x| u32::try_from(x).ok())
+            .and_then(|x| symbols?.format_main_offset(x))
+            .unwrap_or_default()
+    };
+
+    let cpu = &report.cpu;
+    let x: Vec<iv::Pointer> = (0..31)
+        .map(|i| cpu.read::<u64>(reg!(x[i])).into())
+        .collect();
+    let v: Vec<iv::Pointer> = (0..32)
+        .map(|i| cpu.read::<u64>(reg!(d[i])).into())
+        .collect();
+    let q: Vec<String> = (0..32)
+        .map(|i| {
+            let (low, high) = cpu.read::<(u64, u64)>(reg!(q[i]));
+            format!("0x{high:016x}{low:016x}")
+        })
+        .collect();
+    let flags = iv::CrashFlags {
+        n: cpu.flags.n,
+        z: cpu.flags.z,
+        c: cpu.flags.c,
+        v: cpu.flags.v,
+    };
+
+    let fault = report.fault.as_ref().map(|fault| iv::CrashFault {
+        addr: fault.addr.into(),
+        size: fault.size,
+        access: fault.flags.to_string(),
+        section: fault.section.map(|x| x.to_string()).unwrap_or_default(),
+        location: fault.location.trim_end().to_string(),
+    });
+
+    let pc = cpu.pc;
+    let disassembly = report
+        .disassembly
+        .iter()
+        .map(|line| iv::CrashDisasmLine {
+            addr: line.addr.into(),
+            bits: line.bits,
+            text: line.text.clone(),
+            is_pc: line.addr == pc & !3,
+        })
+        .collect();
+
+    let stack_trace = cpu
+        .stack_trace
+        .frames
+        .iter()
+        .rev()
+        .map(|frame| {
+            let from = match frame.jump_type {
+                FrameType::Bl(from) | FrameType::Blr(from, _) => from,
+                FrameType::Native => 0,
+            };
+            iv::CrashStackFrame {
+                from: from.into(),
+                target: frame.jump_target.into(),
+                target_function: resolve(frame.jump_target),
+                text: frame
+                    .format_with_symbols(main_start, symbols)
+                    .trim()
+                    .to_string(),
+            }
+        })
+        .collect();
+
+    iv::CrashReport {
+        is_crashed: true,
+        step,
+        command,
+        span,
+        cause: report.error.to_string(),
+        main_start: main_start.into(),
+        pc: pc.into(),
+        pc_function: resolve(pc),
+        lr: cpu.read::<u64>(reg!(lr)).into(),
+        sp: cpu.read::<u64>(reg!(sp)).into(),
+        x,
+        v,
+        q,
+        flags,
+        fault,
+        disassembly,
+        stack_trace,
+        text: format!("{report:?}"),
+    }
+}
@@ -0,0 +1,84 @@
+use blueflame::game::gdt;
+use blueflame::processor::Process;
+
+use crate::iv;
+
+use super::{Error, try_mem};
+
+/// Query GDT flags by name or hash from the process memory
+pub fn extract_gdt_flags(
+    proc: &Process,
+    queries: &[iv::GdtFlagQuery],
+) -> Result<Vec<iv::GdtFlag>, Error> {
+    let gdt_ptr = try_mem!(
+        gdt::trigger_param_ptr(proc.memory()),
+        e,
+        "failed to load gdt pointer: {e}"
+    );
+    // we can't use the proxy! macro since the return error is ViewError
+    let guard = proc.proxies().trigger_param.read(proc.memory());
+    let gdt = try_mem!(
+        guard.get(gdt_ptr),
+        e,
+        "failed to load gdt trigger param: {e}"
+    );
+
+    Ok(queries
+        .iter()
+        .map(|query| query_gdt_flag(gdt, query))
+        .collect())
+}
+
+/// Query one GDT flag by name or hash in a TriggerParam instance (could be in memory or in save)
+///
+/// If the flag is not found with the type in the query, the value is `None`
+pub fn query_gdt_flag(gdt: &gdt::TriggerParam, query: &iv::GdtFlagQuery) -> iv::GdtFlag {
+    let hash = if query.name.is_empty() {
+        query.hash
+    } else {
+        gdt::get_hash(&query.name)
+    };
+
+    macro_rules! get {
+        (($($fd:tt)*), $x:ident => $value:expr) => {
+            gdt.by_hash::<gdt::fd!($($fd)*)>(hash).map(|flag| {
+                let $x = flag.get();
+                $value
+            })
+        };
+    }
+    use iv::GdtFlagType as T;
+    use iv::GdtFlagValue as V;
+    let value = match query.ty {
+        T::Bool => get!((bool), x => V::Bool(*x)),
+        T::S32 => get!((s32), x => V::S32(*x)),
+        T::F32 => get!((f32), x => V::F32(*x)),
+        T::String32 => get!((str32), x => V::String(x.to_string())),
+        T::String64 => get!((str64), x => V::String(x.to_string())),
+        T::String256 => get!((str256), x => V::String(x.to_string())),
+        T::Vector2f => get!((vec2f), x => V::Vector2f([x.0, x.1])),
+        T::Vector3f => get!((vec3f), x => V::Vector3f([x.0, x.1, x.2])),
+        T::Vector4f => get!((vec4f), x => V::Vector4f([x.0, x.1, x.2, x.3])),
+        T::BoolArray => get!((bool[]), x => V::BoolArray(x.to_vec())),
+        T::S32Array => get!((s32[]), x => V::S32Array(x.to_vec())),
+        T::F32Array => get!((f32[]), x => V::F32Array(x.to_vec())),
+        T::String64Array => {
+            get!((str64[]), x => V::StringArray(x.iter().map(|s| s.to_string()).collect()))
+        }
+        T::String256Array => {
+            get!((str256[]), x => V::StringArray(x.iter().map(|s| s.to_string()).collect()))
+        }
+        T::Vector2fArray => {
+            get!((vec2f[]), x => V::Vector2fArray(x.iter().map(|v| [v.0, v.1]).collect()))
+        }
+        T::Vector3fArray => {
+            get!((vec3f[]), x => V::Vector3fArray(x.iter().map(|v| [v.0, v.1, v.2]).collect()))
+        }
+    };
+
+    iv::GdtFlag {
+        query: query.clone(),
+        hash,
+        value,
+    }
+}
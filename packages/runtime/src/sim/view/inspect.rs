@@ -0,0 +1,154 @@
+use std::collections::BTreeMap;
+
+use blueflame::game::{
+    CookEffectId, OffsetList, PauseMenuDataMgr, PouchItem, PouchItemType, PouchItemUse,
+    singleton_instance,
+};
+use blueflame::memory::{self, InspectNode, InspectValue, MemInspect, MemObject, Memory, Ptr, mem};
+use blueflame::processor::Process;
+
+use crate::iv;
+
+use super::{Error, try_mem};
+
+/// Inspect the whole PauseMenuDataMgr structure as a tree of typed fields.
+///
+/// Pointers are annotated with the field they point to, and additionally
+/// for items in the item buffer, which list the item is in. Pointers outside
+/// of PauseMenuDataMgr are annotated as out of bounds
+pub fn extract_pmdm_tree(proc: &Process) -> Result<iv::InspectNode, Error> {
+    let memory = proc.memory();
+    let pmdm_ptr = try_mem!(
+        singleton_instance!(pmdm(memory)),
+        e,
+        "failed to read pmdm instance: {e}"
+    );
+    let item_buffer_start = Ptr!(&pmdm_ptr->mItemBuffer).to_raw();
+
+    let mut lists = BTreeMap::new();
+    let list1 = Ptr!(&pmdm_ptr->mList1);
+    let list2 = Ptr!(&pmdm_ptr->mList2);
+    try_mem!(
+        walk_list(memory, pmdm_ptr, list1, "mList1", &mut lists),
+        e,
+        "failed to walk mList1: {e}"
+    );
+    try_mem!(
+        walk_list(memory, pmdm_ptr, list2, "mList2", &mut lists),
+        e,
+        "failed to walk mList2: {e}"
+    );
+    let item_status = |i: u64| match lists.get(&(i as i32)) {
+        Some(("mList2", pos)) => format!("mList2[{pos}], unallocated"),
+        Some((list, pos)) => format!("{list}[{pos}]"),
+        None => "not in any list".to_string(),
+    };
+
+    let mut tree = try_mem!(
+        PauseMenuDataMgr::inspect("pmdm", pmdm_ptr.to_raw(), memory),
+        e,
+        "failed to inspect pmdm: {e}"
+    );
+    tree.annotate_internal_pointers();
+    tree.visit_mut(&mut |path, node| {
+        if let InspectValue::Pointer(ptr, _) = &node.value {
+            if node.annotation.is_empty() {
+                // pointers outside of the PMDM are fine, as long as they point to mapped memory
+                if *ptr == 0 {
+                    node.annotation = "nullptr".to_string();
+                } else if memory.section_flags(*ptr).is_none() {
+                    node.annotation = "unmapped".to_string();
+                } else {
+                    node.annotation = memory.format_addr(*ptr).trim_end().to_string();
+                }
+                return;
+            }
+            let Some(off) = ptr.checked_sub(item_buffer_start) else {
+                return;
+            };
+            let i = off / PouchItem::SIZE as u64;
+            if i < 420 {
+                node.annotation = format!("{} ({})", node.annotation, item_status(i));
+            }
+            return;
+        }
+        if let Some(i) = path.strip_prefix("mItemBuffer[")
+            && let Some(i) = i.strip_suffix(']')
+            && let Ok(i) = i.parse::<u64>()
+        {
+            node.annotation = item_status(i);
+            return;
+        }
+        if path.starts_with("mTabsType[") {
+            if let InspectValue::Int(x) = node.value {
+                node.annotation = PouchItemType::describe(x as i32);
+            }
+            return;
+        }
+        if !path.starts_with("mItemBuffer[") {
+            return;
+        }
+        match (node.name.as_str(), &node.value) {
+            ("mType", InspectValue::Int(x)) => {
+                node.annotation = PouchItemType::describe(*x as i32);
+            }
+            ("mItemUse", InspectValue::Int(x)) => {
+                node.annotation = PouchItemUse::describe(*x as i32);
+            }
+            ("mEffectId", InspectValue::F32(x)) => {
+                node.annotation = CookEffectId::describe(*x);
+            }
+            _ => {}
+        }
+    });
+
+    Ok(convert_node(tree))
+}
+
+/// Walk a list in the pmdm and record the position of each item in the list.
+///
+/// The walk stops if a node is not in the item buffer (e.g. corrupted) or if it loops
+fn walk_list(
+    memory: &Memory,
+    pmdm_ptr: Ptr![PauseMenuDataMgr],
+    list: Ptr![OffsetList],
+    name: &'static str,
+    out: &mut BTreeMap<i32, (&'static str, usize)>,
+) -> Result<(), memory::Error> {
+    let start_end = Ptr!(&list->mStartEnd);
+    mem! { memory:
+        let offset = *(&list->mOffset);
+        let mut curr = *(&list->mStartEnd.mNext);
+    }
+    for pos in 0..420 {
+        if curr == start_end {
+            break;
+        }
+        let item: Ptr![PouchItem] = curr.to_raw().wrapping_sub(offset as u64).into();
+        let Some(i) = pmdm_ptr.get_item_buffer_idx(item) else {
+            break;
+        };
+        if i >= 420 || out.contains_key(&i) {
+            break;
+        }
+        out.insert(i, (name, pos));
+        mem! { memory: curr = *(&curr->mNext); }
+    }
+    Ok(())
+}
+
+fn convert_node(node: InspectNode) -> iv::InspectNode {
+    let value = match &node.value {
+        InspectValue::None => String::new(),
+        x => x.to_string(),
+    };
+    iv::InspectNode {
+        name: node.name,
+        ty: node.type_name,
+        addr: node.addr.into(),
+        size: node.size,
+        value,
+        annotation: node.annotation,
+        children: node.children.into_iter().map(convert_node).collect(),
+    }
+}
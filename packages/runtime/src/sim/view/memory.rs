@@ -0,0 +1,175 @@
+use blueflame::game::{PauseMenuDataMgr, PouchItem, gdt, singleton_instance};
+use blueflame::memory::{MemObject, ProxyObject, Ptr, WriteTrace};
+use blueflame::processor::Process;
+
+use crate::iv;
+
+use super::{Error, try_mem};
+
+/// Known fields of PouchItem, for naming the fields written to
+const POUCH_ITEM_FIELDS: &[(u32, &str)] = &[
+    (0x0, "vtable"),
+    (0x8, "mListNode.mPrev"),
+    (0x10, "mListNode.mNext"),
+    (0x18, "mType"),
+    (0x1c, "mItemUse"),
+    (0x20, "mValue"),
+    (0x24, "mEquipped"),
+    (0x25, "mInInventory"),
+    (0x28, "mName"),
+    (0x80, "mHealthRecover"),
+    (0x84, "mEffectDuration"),
+    (0x88, "mSellPrice"),
+    (0x8c, "mEffectId"),
+    (0x90, "mEffectLevel"),
+    (0x98, "mIngredients"),
+];
+
+/// Known fields of PauseMenuDataMgr, other than the item buffer and tabs
+const PMDM_FIELDS: &[(u32, &str)] = &[
+    (0x0, "vtable"),
+    (0x68, "mList1"),
+    (0x80, "mList2"),
+    (0x441f8, "mListHeads"),
+    (0x44488, "mLastAddedItem"),
+    (0x44490, "mLastAddedItemTab"),
+    (0x44494, "mLastAddedItemSlot"),
+    (0x44498, "mNumTabs"),
+    (0x444a0, "mGrabbedItems"),
+    (0x44518, "mRitoSoulItem"),
+    (0x44520, "mGoronSoulItem"),
+    (0x44528, "mZoraSoulItem"),
+    (0x44530, "mGerudoSoulItem"),
+    (0x44540, "mNewlyAddedItem"),
+    (0x447d8, "mIsPouchForQuest"),
+    (0x447e0, "mEquippedWeapons"),
+    (0x44800, "mCategoryToSort"),
+];
+
+/// Group the writes recorded by the write tracer by the structures they are in.
+///
+/// The process should be the one the writes are recorded in (or one with the same
+/// singletons, such as the one before the writes). Writes that don't change the value are skipped
+pub fn extract_memory_changes(
+    proc: &Process,
+    trace: &WriteTrace,
+) -> Result<iv::MemoryChanges, Error> {
+    let memory = proc.memory();
+    let pmdm = try_mem!(
+        singleton_instance!(pmdm(memory)),
+        e,
+        "failed to read pmdm instance: {e}"
+    );
+    let item_buffer_start = Ptr!(&pmdm->mItemBuffer).to_raw();
+    let tabs_start = Ptr!(&pmdm->mTabs).to_raw();
+    let tabs_type_start = Ptr!(&pmdm->mTabsType).to_raw();
+
+    let gdt_ptr = try_mem!(
+        gdt::trigger_param_ptr(memory),
+        e,
+        "failed to load gdt pointer: {e}"
+    );
+    let gdt_size = {
+        let guard = proc.proxies().trigger_param.read(memory);
+        let gdt = try_mem!(
+            guard.get(gdt_ptr),
+            e,
+            "failed to load gdt trigger param: {e}"
+        );
+        gdt.mem_size() as u64
+    };
+
+    let main_start = proc.main_start();
+    let symbols = proc.symbols();
+
+    let format_function = |function: u64| {
+        function
+            .checked_sub(main_start)
+            .and_then(|x| u32::try_from(x).ok())
+            .and_then(|x| symbols?.format_main_offset(x))
+            .unwrap_or_default()
+    };
+
+    let mut changes = Vec::with_capacity(trace.writes.len());
+    for record in &trace.writes {
+        if record.old == record.new {
+            continue;
+        }
+        let addr = record.addr;
+        let location = if let Some(off) = offset_in(addr, pmdm.to_raw(), PauseMenuDataMgr::SIZE) {
+            if let Some(off) = offset_in(addr, item_buffer_start, PouchItem::SIZE * 420) {
+                iv::MemoryLocation::PouchItem {
+                    index: (off / PouchItem::SIZE) as i32,
+                    field: field_name(POUCH_ITEM_FIELDS, off % PouchItem::SIZE),
+                }
+            } else if let Some(off) = offset_in(addr, tabs_start, 8 * 50) {
+                iv::MemoryLocation::Tabs {
+                    index: (off / 8) as i32,
+                }
+            } else if let Some(off) = offset_in(addr, tabs_type_start, 4 * 50) {
+                iv::MemoryLocation::TabsType {
+                    index: (off / 4) as i32,
+                }
+            } else {
+                iv::MemoryLocation::Pmdm {
+                    field: field_name(PMDM_FIELDS, off),
+                }
+            }
+        } else if addr >= gdt_ptr && addr - gdt_ptr < gdt_size {
+            iv::MemoryLocation::Gdt
+        } else {
+            iv::MemoryLocation::Other
+        };
+        changes.push(iv::MemoryChange {
+            location,
+            addr: record.addr.into(),
+            size: record.size,
+            old: record.old.into(),
+            new: record.new.into(),
+            pc: record.pc.into(),
+            function: record.function.into(),
+            function_name: format_function(record.function),
+        });
+    }
+
+    let gdt_changes = trace
+        .gdt_writes
+        .iter()
+        .filter(|record| record.old != record.new)
+        .map(|record| iv::GdtFlagChange {
+            flag: record.flag.clone(),
+            array_idx: record.array_idx,
+            old: record.old.clone(),
+            new: record.new.clone(),
+            pc: record.pc.into(),
+            function: record.function.into(),
+            function_name: format_function(record.function),
+        })
+        .collect();
+
+    Ok(iv::MemoryChanges {
+        is_traced: true,
+        changes,
+        gdt_changes,
+    })
+}
+
+/// Get the offset of the address in the range, if the address is in the range
+fn offset_in(addr: u64, start: u64, size: u32) -> Option<u32> {
+    if addr < start || addr - start >= size as u64 {
+        return None;
+    }
+    Some((addr - start) as u32)
+}
+
+/// Name the field at the offset with the known fields. If the offset
+/// is in the middle of a field, the offset into the field is appended
+fn field_name(fields: &[(u32, &str)], off: u32) -> String {
+    let Some((field_off, name)) = fields.iter().rev().find(|(x, _)| *x <= off) else {
+        return format!("+0x{off:x}");
+    };
+    match off - field_off {
+        0 => name.to_string(),
+        rel => format!("{name}+0x{rel:x}"),
+    }
+}
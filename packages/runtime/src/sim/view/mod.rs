@@ -2,6 +2,16 @@ mod pouch;
 pub use pouch::*;
 mod gdt;
 pub use gdt::*;
+mod gdt_flag;
+pub use gdt_flag::*;
+mod memory;
+pub use memory::*;
+mod inspect;
+pub use inspect::*;
+mod crash;
+pub use crash::*;
+mod save;
+pub use save::*;
 
 #[derive(Debug, PartialEq, thiserror::Error)]
 pub enum Error {
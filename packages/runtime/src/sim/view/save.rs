@@ -0,0 +1,142 @@
+use std::sync::Arc;
+
+use blueflame::game::gdt;
+
+use crate::iv;
+
+use super::{Error, extract_gdt_from_trigger_param};
+
+/// Extract the inventories in the saves and compare them with the current GDT inventory.
+///
+/// `current` is `None` if the game is not running, in which case the differences are not computed
+pub fn extract_save_comparison(
+    current: Option<iv::Gdt>,
+    manual_save: Option<&gdt::TriggerParam>,
+    saves: &[(String, Arc<gdt::TriggerParam>)],
+) -> Result<iv::SaveComparison, Error> {
+    let manual_save = manual_save
+        .map(|save| extract_save_inventory(String::new(), save, current.as_ref()))
+        .transpose()?;
+    let saves = saves
+        .iter()
+        .map(|(name, save)| extract_save_inventory(name.clone(), save, current.as_ref()))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(iv::SaveComparison {
+        current,
+        manual_save,
+        saves,
+    })
+}
+
+fn extract_save_inventory(
+    name: String,
+    save: &gdt::TriggerParam,
+    current: Option<&iv::Gdt>,
+) -> Result<iv::SaveInventory, Error> {
+    let gdt = extract_gdt_from_trigger_param(save)?;
+    let Some(current) = current else {
+        return Ok(iv::SaveInventory {
+            name,
+            gdt,
+            ..Default::default()
+        });
+    };
+    let (item_diffs, removed_items) = diff_gdt_items(&gdt.items, &current.items);
+    let master_sword_differs = gdt.master_sword != current.master_sword;
+    let info_differs = gdt.info != current.info;
+    Ok(iv::SaveInventory {
+        name,
+        gdt,
+        item_diffs,
+        removed_items,
+        master_sword_differs,
+        info_differs,
+    })
+}
+
+/// Compare the items in the save with the current items.
+///
+/// Items are matched by identity (the actor name and the metadata, like weapon modifiers
+/// or food effects) instead of the slot, since the same items can be in different slots
+/// in the save and the current GDT. Returns the difference of each item in the save,
+/// and the `idx` of the items in the current GDT that are not in the save
+pub fn diff_gdt_items(
+    save: &[iv::GdtItem],
+    current: &[iv::GdtItem],
+) -> (Vec<iv::GdtItemDiff>, Vec<u32>) {
+    let save_keys: Vec<_> = save.iter().map(ItemKey::new).collect();
+    let current_keys: Vec<_> = current.iter().map(ItemKey::new).collect();
+    let mut matched = vec![false; current.len()];
+    let mut diffs = vec![iv::GdtItemDiff::Added; save.len()];
+
+    // match the items that are exactly the same first, so an item that
+    // only changed doesn't take the place of one that is the same
+    for (key, diff) in save_keys.iter().zip(&mut diffs) {
+        if let Some(j) = find_unmatched(&current_keys, &matched, |x| x == key) {
+            matched[j] = true;
+            *diff = iv::GdtItemDiff::Same;
+        }
+    }
+    for (key, diff) in save_keys.iter().zip(&mut diffs) {
+        if *diff == iv::GdtItemDiff::Added
+            && let Some(j) = find_unmatched(&current_keys, &matched, |x| x.is_same_item(key))
+        {
+            matched[j] = true;
+            *diff = iv::GdtItemDiff::Changed;
+        }
+    }
+
+    let removed = current
+        .iter()
+        .zip(matched)
+        .filter(|(_, matched)| !matched)
+        .map(|(item, _)| item.idx)
+        .collect();
+    (diffs, removed)
+}
+
+fn find_unmatched<'a>(
+    keys: &[ItemKey<'a>],
+    matched: &[bool],
+    f: impl Fn(&ItemKey<'a>) -> bool,
+) -> Option<usize> {
+    keys.iter()
+        .zip(matched)
+        .position(|(key, matched)| !matched && f(key))
+}
+
+/// An item in the GDT, without the position in the GDT
+#[derive(PartialEq)]
+struct ItemKey<'a> {
+    actor_name: &'a str,
+    /// Extra data, with the index in the data arrays cleared
+    data: iv::GdtItemData,
+    value: i32,
+    is_equipped: bool,
+}
+
+impl<'a> ItemKey<'a> {
+    fn new(item: &'a iv::GdtItem) -> Self {
+        let mut data = item.data.clone();
+        match &mut data {
+            iv::GdtItemData::None => {}
+            iv::GdtItemData::Sword { idx, .. }
+            | iv::GdtItemData::Bow { idx, .. }
+            | iv::GdtItemData::Shield { idx, .. }
+            | iv::GdtItemData::Food { idx, .. } => *idx = 0,
+        }
+        Self {
+            actor_name: &item.common.actor_name,
+            data,
+            value: item.common.value,
+            is_equipped: item.common.is_equipped,
+        }
+    }
+
+    /// Check if the item has the same identity, but could have
+    /// a different value or equipped state
+    fn is_same_item(&self, other: &Self) -> bool {
+        self.actor_name == other.actor_name && self.data == other.data
+    }
+}
@@ -6,6 +6,7 @@ import {
     parseEnvImage,
     parseRegionSize,
     parseEnvDlcVersion,
+    parseEnvFeatureList,
 } from "./env_parser.ts";
 
 describe("parseEnvFromScript", () => {
@@ -20,6 +21,10 @@ describe("parseEnvFromScript", () => {
                     stackSize: 0,
                     heapFreeSize: 0,
                     pmdmAddr: "",
+                    enableFeatures: [],
+                    disableFeatures: [],
+                    instructionLimit: 0,
+                    timeLimit: 0,
                 },
                 errors: [],
             });
@@ -35,10 +40,12 @@ stack-start = 34500000
 stack-size = 0x345000
 heap-free-size = 0x345000
 pmdm-addr = 0x34500000
+enable-features = mem-track-free, limited-block-count
+disable-features = check-stack-corruption
 '''
 `),
         ).toEqual({
-            lines: [2, 9],
+            lines: [2, 11],
             image: "1.5.0",
             params: {
                 dlc: 3,
@@ -47,6 +54,10 @@ pmdm-addr = 0x34500000
                 stackSize: 0x345000,
                 heapFreeSize: 0x345000,
                 pmdmAddr: "0x0000000034500000",
+                enableFeatures: ["mem-track-free", "limited-block-count"],
+                disableFeatures: ["check-stack-corruption"],
+                instructionLimit: 0,
+                timeLimit: 0,
             },
             errors: [],
         });
@@ -93,6 +104,14 @@ describe("parseEnvDlcVersion", () => {
     });
 });
 
+describe("parseEnvFeatureList", () => {
+    it("parses comma-separated names", () => {
+        expect(parseEnvFeatureList("")).toEqual([]);
+        expect(parseEnvFeatureList(" mem-track-free ")).toEqual(["mem-track-free"]);
+        expect(parseEnvFeatureList("a, b,,c")).toEqual(["a", "b", "c"]);
+    });
+});
+
 describe("parseAbsAddrString", () => {
     it.each(["", "0", "0x0", "0x00"])("parses empty", (input) => {
         expect(parseRegionStart(input)).toEqual({
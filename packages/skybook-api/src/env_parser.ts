@@ -14,6 +14,10 @@ export const parseEnvFromScript = (script: string): ScriptEnv => {
         stackSize: 0,
         heapFreeSize: 0,
         pmdmAddr: "",
+        enableFeatures: [],
+        disableFeatures: [],
+        instructionLimit: 0,
+        timeLimit: 0,
     };
     const lines = script.split("\n");
     let i = 0;
@@ -134,6 +138,14 @@ export const parseEnvFromScript = (script: string): ScriptEnv => {
                 }
                 break;
             }
+            case "enable-features": {
+                params.enableFeatures.push(...parseEnvFeatureList(val));
+                break;
+            }
+            case "disable-features": {
+                params.disableFeatures.push(...parseEnvFeatureList(val));
+                break;
+            }
             default: {
                 errors.push({
                     type: "UnknownKey",
@@ -210,6 +222,14 @@ export const parseEnvDlcVersion = (dlc: string): number => {
             return 3;
     }
 };
+/** Parse comma-separated feature names. The names are validated when the runtime initializes */
+export const parseEnvFeatureList = (features: string): string[] => {
+    return features
+        .split(",")
+        .map((x) => x.trim())
+        .filter(Boolean);
+};
+
 /** Input is hex string with optional 0x prefix */
 export const parseRegionStart = (addr: string): Result<string, AbsAddrError> => {
     return parseAbsAddrStringInternal(addr, true);
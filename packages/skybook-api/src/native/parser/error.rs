@@ -79,6 +79,8 @@ pub enum ParserError {
     InvalidEquipmentSlotNum(cir::Category, i32),
     #[error("`{0}={1}` is not a valid system command")]
     InvalidSystemCommand(String, String),
+    #[error("`{0}` is not a valid thing to pray for, valid values are `heart` and `stamina`")]
+    InvalidPrayTarget(String),
     //////////////////////////////////
     // Add new errors below
     // The translation files needs to be updated accordingly!!!
@@ -35,8 +35,8 @@ pub enum RuntimeInitError {
     BadDlcVersion(u32),
     #[error("the game version is not supported")]
     UnsupportedVersion,
-    #[error("the image file is invalid")]
-    BadImage,
+    #[error("the image file is invalid: {0}")]
+    BadImage(String),
     #[error("stack-start param is invalid")]
     InvalidStackStart,
     #[error("pmdm-addr param is invalid")]
@@ -49,6 +49,8 @@ pub enum RuntimeInitError {
     HeapTooBig,
     #[error("failed to initialize the process")]
     InitializeProcess,
+    #[error("unknown feature: {0}")]
+    UnknownFeature(String),
 }
 
 /// Error type for the runtime
@@ -103,6 +105,10 @@ pub enum RuntimeError {
     Executor,
     #[error("the `all but` syntax did not achieve the desired result")]
     InaccurateAllBut,
+    #[error(
+        "the step executed more than `{0}` instructions and is stopped, the game was executing `{1}`"
+    )]
+    InstructionLimitReached(u64, String),
     #[error("this type of item is not accessible through quick menu")]
     InvalidDpadType,
     #[error("array index `{2}` for the flag `{0}` with type `{1}` might be invalid")]
@@ -123,6 +129,8 @@ pub enum RuntimeError {
         "the item in the inventory in this position is `{0}`, which does not match the input category `{1:?}`"
     )]
     ItemMismatchCategory(String, crate::parser::cir::Category),
+    #[error("this is already fully upgraded")]
+    MaxUpgradeReached,
     #[error("cannot find arrow to shoot")]
     NoArrowsToShoot,
     #[error("no manual save has been made")]
@@ -133,6 +141,10 @@ pub enum RuntimeError {
     NotEatable,
     #[error("this requires `{0}` items, but only `{1}` items found")]
     NotEnoughForAllBut(usize, usize),
+    #[error("this costs `{0}` rupees, but only `{1}` rupees are available")]
+    NotEnoughRupees(i32, i32),
+    #[error("this requires `{1}` of `{0}`, but only `{2}` found")]
+    NotEnoughUpgradeMaterial(String, usize, usize),
     #[error("the item is not an equipment")]
     NotEquipment,
     #[error("the item is not equipped in the overworld")]
@@ -145,8 +157,14 @@ pub enum RuntimeError {
     NotRightScreen,
     #[error("the item `{0}` is not sellable")]
     NotSellable(String),
+    #[error("this item is not sold by `{0}`")]
+    NotSoldInShop(String),
+    #[error("this item cannot be upgraded")]
+    NotUpgradable,
     #[error("this operation cannot be completed due to previous errors")]
     OperationNotComplete,
+    #[error("the shop only has `{0}` of this item left in stock")]
+    OutOfStock(u32),
     #[error("cannot specify item position here")]
     PositionSpecNotAllowed,
     #[error(
@@ -161,6 +179,8 @@ pub enum RuntimeError {
     ReloadFail,
     #[error("cannot find the named save `{0}`")]
     SaveNotFound(String),
+    #[error("the step ran for more than `{0}` ms and is stopped, the game was executing `{1}`")]
+    TimeLimitReached(u64, String),
     #[error("the runtime iteration limit is reached")]
     TooManyIterations,
     #[error("the runtime has not been initialized yet, you need to call `Runtime::init`")]
@@ -169,6 +189,12 @@ pub enum RuntimeError {
         "this command or syntax is not implemented yet, please track the development on GitHub"
     )]
     Unimplemented,
+    #[error("unknown feature `{0}`")]
+    UnknownFeature(String),
+    #[error("the price of this item is unknown, so no rupees are taken")]
+    UnknownPrice,
+    #[error("the cost of this upgrade is unknown, so the armor is not upgraded")]
+    UnknownUpgradeCost,
     #[error("amount can only be 1 when targeting equipped items in the overworld")]
     UselessAmountForOverworldEquipment,
     #[error("meta properties are ignored when targeting equipped items in the overworld")]
@@ -0,0 +1,131 @@
+//! Structured crash report of the emulated game
+
+mod __impl {
+    use serde::Serialize;
+
+    use crate::runtime::iv;
+
+    /// Crash report for the step where the game crashed, with the CPU state
+    /// and the code around where it happened
+    #[derive(Debug, Default, PartialEq, Clone, Serialize)]
+    #[cfg_attr(feature = "__ts-binding", derive(ts_rs::TS))]
+    #[cfg_attr(feature = "__ts-binding", ts(export))]
+    #[cfg_attr(feature = "wasm", derive(tsify::Tsify))]
+    #[cfg_attr(feature = "wasm", tsify(into_wasm_abi))]
+    #[serde(rename_all = "camelCase")]
+    #[allow(non_camel_case_types)]
+    pub struct InvView_CrashReport {
+        /// If the game has crashed at or before the step. If false, the other fields are empty
+        pub is_crashed: bool,
+        /// Index of the step that crashed the game
+        pub step: usize,
+        /// Type of the command in the step that crashed the game
+        pub command: String,
+        /// Span of the step in the script (in byte positions. The extension API
+        /// converts it to character positions)
+        pub span: (usize, usize),
+        /// Description of the error that caused the crash
+        pub cause: String,
+        /// Start of the main module
+        pub main_start: iv::Pointer,
+        pub pc: iv::Pointer,
+        /// Function PC is in, if it can be resolved by the symbols, otherwise empty
+        pub pc_function: String,
+        pub lr: iv::Pointer,
+        pub sp: iv::Pointer,
+        /// Values of X0-X30
+        pub x: Vec<iv::Pointer>,
+        /// Values of V0-V31 (low 64 bits, i.e. D0-D31)
+        pub v: Vec<iv::Pointer>,
+        /// Values of V0-V31 (full 128 bits, i.e. Q0-Q31), as hex strings like `0x0123...`,
+        /// since 128-bit values cannot be passed to JS as numbers
+        pub q: Vec<String>,
+        pub flags: InvView_CrashFlags,
+        /// The memory access that caused the crash, if the crash is from a memory error
+        pub fault: Option<InvView_CrashFault>,
+        /// Instructions around PC, empty if the code is not in the program
+        pub disassembly: Vec<InvView_CrashDisasmLine>,
+        /// Stack frames, with the most recent first
+        pub stack_trace: Vec<InvView_CrashStackFrame>,
+        /// The whole report as text, for copying
+        pub text: String,
+    }
+
+    /// NZCV flags of the CPU
+    #[derive(Debug, Default, PartialEq, Clone, Serialize)]
+    #[cfg_attr(feature = "__ts-binding", derive(ts_rs::TS))]
+    #[cfg_attr(feature = "__ts-binding", ts(export))]
+    #[cfg_attr(feature = "wasm", derive(tsify::Tsify))]
+    #[cfg_attr(feature = "wasm", tsify(into_wasm_abi))]
+    #[serde(rename_all = "camelCase")]
+    #[allow(non_camel_case_types)]
+    pub struct InvView_CrashFlags {
+        pub n: bool,
+        pub z: bool,
+        pub c: bool,
+        pub v: bool,
+    }
+
+    /// A memory access that caused a crash
+    #[derive(Debug, Default, PartialEq, Clone, Serialize)]
+    #[cfg_attr(feature = "__ts-binding", derive(ts_rs::TS))]
+    #[cfg_attr(feature = "__ts-binding", ts(export))]
+    #[cfg_attr(feature = "wasm", derive(tsify::Tsify))]
+    #[cfg_attr(feature = "wasm", tsify(into_wasm_abi))]
+    #[serde(rename_all = "camelCase")]
+    #[allow(non_camel_case_types)]
+    pub struct InvView_CrashFault {
+        /// Address being accessed
+        pub addr: iv::Pointer,
+        /// Number of bytes accessed, if it can be decoded from the instruction
+        pub size: Option<u32>,
+        /// Permissions and regions of the access, for example `w,data+stack+heap`
+        pub access: String,
+        /// Permissions and region of the section the address is in,
+        /// empty if the address is not in any section
+        pub section: String,
+        /// The address relative to the section it's in, for example `heap+0x00001000`
+        pub location: String,
+    }
+
+    /// One instruction in the disassembly of a crash report
+    #[derive(Debug, Default, PartialEq, Clone, Serialize)]
+    #[cfg_attr(feature = "__ts-binding", derive(ts_rs::TS))]
+    #[cfg_attr(feature = "__ts-binding", ts(export))]
+    #[cfg_attr(feature = "wasm", derive(tsify::Tsify))]
+    #[cfg_attr(feature = "wasm", tsify(into_wasm_abi))]
+    #[serde(rename_all = "camelCase")]
+    #[allow(non_camel_case_types)]
+    pub struct InvView_CrashDisasmLine {
+        pub addr: iv::Pointer,
+        pub bits: u32,
+        /// Disassembled instruction, empty if it cannot be decoded
+        pub text: String,
+        /// If this is the instruction at PC
+        pub is_pc: bool,
+    }
+
+    /// One frame in the stack trace of a crash report
+    #[derive(Debug, Default, PartialEq, Clone, Serialize)]
+    #[cfg_attr(feature = "__ts-binding", derive(ts_rs::TS))]
+    #[cfg_attr(feature = "__ts-binding", ts(export))]
+    #[cfg_attr(feature = "wasm", derive(tsify::Tsify))]
+    #[cfg_attr(feature = "wasm", tsify(into_wasm_abi))]
+    #[serde(rename_all = "camelCase")]
+    #[allow(non_camel_case_types)]
+    pub struct InvView_CrashStackFrame {
+        /// Address of the call instruction, 0 if called from native code
+        pub from: iv::Pointer,
+        /// Address of the function called
+        pub target: iv::Pointer,
+        /// Name of the function called, if it can be resolved by the symbols, otherwise empty
+        pub target_function: String,
+        /// The frame formatted as text
+        pub text: String,
+    }
+}
+pub use __impl::InvView_CrashDisasmLine as CrashDisasmLine;
+pub use __impl::InvView_CrashFault as CrashFault;
+pub use __impl::InvView_CrashFlags as CrashFlags;
+pub use __impl::InvView_CrashReport as CrashReport;
+pub use __impl::InvView_CrashStackFrame as CrashStackFrame;
@@ -0,0 +1,202 @@
+//! Generic GameData (GDT) flag query types
+
+mod __impl {
+    use serde::{Deserialize, Serialize};
+
+    /// Query for one GDT flag by name or hash
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+    #[cfg_attr(feature = "__ts-binding", derive(ts_rs::TS))]
+    #[cfg_attr(feature = "__ts-binding", ts(export))]
+    #[cfg_attr(feature = "wasm", derive(tsify::Tsify))]
+    #[cfg_attr(feature = "wasm", tsify(into_wasm_abi, from_wasm_abi))]
+    #[serde(rename_all = "camelCase")]
+    #[allow(non_camel_case_types)]
+    pub struct InvView_GdtFlagQuery {
+        /// Name of the flag. If empty, `hash` is used to find the flag
+        #[serde(default)]
+        pub name: String,
+        /// CRC32 hash of the flag name. Only used if `name` is empty
+        #[serde(default)]
+        pub hash: i32,
+        /// Type of the flag
+        pub ty: InvView_GdtFlagType,
+    }
+
+    /// Type of a GDT flag
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+    #[cfg_attr(feature = "__ts-binding", derive(ts_rs::TS))]
+    #[cfg_attr(feature = "__ts-binding", ts(export))]
+    #[cfg_attr(feature = "wasm", derive(tsify::Tsify))]
+    #[cfg_attr(feature = "wasm", tsify(into_wasm_abi, from_wasm_abi))]
+    #[allow(non_camel_case_types)]
+    pub enum InvView_GdtFlagType {
+        #[serde(rename = "bool")]
+        Bool,
+        #[serde(rename = "s32")]
+        S32,
+        #[serde(rename = "f32")]
+        F32,
+        #[serde(rename = "str32")]
+        String32,
+        #[serde(rename = "str64")]
+        String64,
+        #[serde(rename = "str256")]
+        String256,
+        #[serde(rename = "vec2f")]
+        Vector2f,
+        #[serde(rename = "vec3f")]
+        Vector3f,
+        #[serde(rename = "vec4f")]
+        Vector4f,
+        #[serde(rename = "bool[]")]
+        BoolArray,
+        #[serde(rename = "s32[]")]
+        S32Array,
+        #[serde(rename = "f32[]")]
+        F32Array,
+        #[serde(rename = "str64[]")]
+        String64Array,
+        #[serde(rename = "str256[]")]
+        String256Array,
+        #[serde(rename = "vec2f[]")]
+        Vector2fArray,
+        #[serde(rename = "vec3f[]")]
+        Vector3fArray,
+    }
+
+    impl InvView_GdtFlagType {
+        /// All flag types
+        pub const ALL: [Self; 16] = [
+            Self::Bool,
+            Self::S32,
+            Self::F32,
+            Self::String32,
+            Self::String64,
+            Self::String256,
+            Self::Vector2f,
+            Self::Vector3f,
+            Self::Vector4f,
+            Self::BoolArray,
+            Self::S32Array,
+            Self::F32Array,
+            Self::String64Array,
+            Self::String256Array,
+            Self::Vector2fArray,
+            Self::Vector3fArray,
+        ];
+
+        /// Get the short name of the type, the same as the one used in `!set-gdt`
+        /// (with `[]` appended for arrays)
+        pub fn as_str(self) -> &'static str {
+            match self {
+                Self::Bool => "bool",
+                Self::S32 => "s32",
+                Self::F32 => "f32",
+                Self::String32 => "str32",
+                Self::String64 => "str64",
+                Self::String256 => "str256",
+                Self::Vector2f => "vec2f",
+                Self::Vector3f => "vec3f",
+                Self::Vector4f => "vec4f",
+                Self::BoolArray => "bool[]",
+                Self::S32Array => "s32[]",
+                Self::F32Array => "f32[]",
+                Self::String64Array => "str64[]",
+                Self::String256Array => "str256[]",
+                Self::Vector2fArray => "vec2f[]",
+                Self::Vector3fArray => "vec3f[]",
+            }
+        }
+
+        /// Parse the type from the short name (see [`as_str`](Self::as_str))
+        pub fn from_name(name: &str) -> Option<Self> {
+            Self::ALL.into_iter().find(|x| x.as_str() == name)
+        }
+    }
+
+    impl std::fmt::Display for InvView_GdtFlagType {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            f.write_str(self.as_str())
+        }
+    }
+
+    /// Result of querying one GDT flag
+    #[derive(Debug, Clone, PartialEq, Serialize)]
+    #[cfg_attr(feature = "__ts-binding", derive(ts_rs::TS))]
+    #[cfg_attr(feature = "__ts-binding", ts(export))]
+    #[cfg_attr(feature = "wasm", derive(tsify::Tsify))]
+    #[cfg_attr(feature = "wasm", tsify(into_wasm_abi))]
+    #[serde(rename_all = "camelCase")]
+    #[allow(non_camel_case_types)]
+    pub struct InvView_GdtFlag {
+        /// The query used to find the flag
+        pub query: InvView_GdtFlagQuery,
+        /// CRC32 hash of the flag name
+        pub hash: i32,
+        /// Value of the flag, `None` if the flag is not found
+        pub value: Option<InvView_GdtFlagValue>,
+    }
+
+    /// Value of a GDT flag
+    #[derive(Debug, Clone, PartialEq, Serialize)]
+    #[cfg_attr(feature = "__ts-binding", derive(ts_rs::TS))]
+    #[cfg_attr(feature = "__ts-binding", ts(export))]
+    #[cfg_attr(feature = "wasm", derive(tsify::Tsify))]
+    #[cfg_attr(feature = "wasm", tsify(into_wasm_abi))]
+    #[serde(tag = "type", content = "value")]
+    #[allow(non_camel_case_types)]
+    pub enum InvView_GdtFlagValue {
+        #[serde(rename = "bool")]
+        Bool(bool),
+        #[serde(rename = "s32")]
+        S32(i32),
+        #[serde(rename = "f32")]
+        F32(f32),
+        /// Value of `str32`, `str64` or `str256` flags
+        #[serde(rename = "str")]
+        String(String),
+        #[serde(rename = "vec2f")]
+        Vector2f([f32; 2]),
+        #[serde(rename = "vec3f")]
+        Vector3f([f32; 3]),
+        #[serde(rename = "vec4f")]
+        Vector4f([f32; 4]),
+        #[serde(rename = "bool[]")]
+        BoolArray(Vec<bool>),
+        #[serde(rename = "s32[]")]
+        S32Array(Vec<i32>),
+        #[serde(rename = "f32[]")]
+        F32Array(Vec<f32>),
+        /// Value of `str64[]` or `str256[]` flags
+        #[serde(rename = "str[]")]
+        StringArray(Vec<String>),
+        #[serde(rename = "vec2f[]")]
+        Vector2fArray(Vec<[f32; 2]>),
+        #[serde(rename = "vec3f[]")]
+        Vector3fArray(Vec<[f32; 3]>),
+    }
+
+    impl std::fmt::Display for InvView_GdtFlagValue {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            match self {
+                Self::Bool(x) => write!(f, "{x}"),
+                Self::S32(x) => write!(f, "{x}"),
+                Self::F32(x) => write!(f, "{x}"),
+                Self::String(x) => write!(f, "{x:?}"),
+                Self::Vector2f(x) => write!(f, "{x:?}"),
+                Self::Vector3f(x) => write!(f, "{x:?}"),
+                Self::Vector4f(x) => write!(f, "{x:?}"),
+                Self::BoolArray(x) => write!(f, "{x:?}"),
+                Self::S32Array(x) => write!(f, "{x:?}"),
+                Self::F32Array(x) => write!(f, "{x:?}"),
+                Self::StringArray(x) => write!(f, "{x:?}"),
+                Self::Vector2fArray(x) => write!(f, "{x:?}"),
+                Self::Vector3fArray(x) => write!(f, "{x:?}"),
+            }
+        }
+    }
+}
+pub use __impl::InvView_GdtFlag as GdtFlag;
+pub use __impl::InvView_GdtFlagQuery as GdtFlagQuery;
+pub use __impl::InvView_GdtFlagType as GdtFlagType;
+pub use __impl::InvView_GdtFlagValue as GdtFlagValue;
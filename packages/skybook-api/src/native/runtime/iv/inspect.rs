@@ -0,0 +1,34 @@
+//! Typed inspector views of structures in memory
+
+mod __impl {
+    use serde::Serialize;
+
+    use crate::runtime::iv;
+
+    /// One field of a structure in memory, as a tree
+    #[derive(Debug, Default, PartialEq, Clone, Serialize)]
+    #[cfg_attr(feature = "__ts-binding", derive(ts_rs::TS))]
+    #[cfg_attr(feature = "__ts-binding", ts(export))]
+    #[cfg_attr(feature = "wasm", derive(tsify::Tsify))]
+    #[cfg_attr(feature = "wasm", tsify(into_wasm_abi))]
+    #[serde(rename_all = "camelCase")]
+    #[allow(non_camel_case_types)]
+    pub struct InvView_InspectNode {
+        /// Name of the field, or `[i]` for array elements
+        pub name: String,
+        /// Type of the field
+        pub ty: String,
+        /// Physical address of the field
+        pub addr: iv::Pointer,
+        /// Size of the field in memory
+        pub size: u32,
+        /// Formatted value of the field, empty for structs and arrays
+        pub value: String,
+        /// Extra information about the field, for example what a pointer points to,
+        /// or which list an item is in
+        pub annotation: String,
+        /// Fields of a struct or elements of an array
+        pub children: Vec<InvView_InspectNode>,
+    }
+}
+pub use __impl::InvView_InspectNode as InspectNode;
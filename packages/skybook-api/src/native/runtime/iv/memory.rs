@@ -0,0 +1,112 @@
+//! Changes to memory made by a simulation step
+
+mod __impl {
+    use serde::Serialize;
+
+    use crate::runtime::iv;
+
+    /// View of the writes to memory in a step, grouped by known structures
+    #[derive(Debug, Default, PartialEq, Clone, Serialize)]
+    #[cfg_attr(feature = "__ts-binding", derive(ts_rs::TS))]
+    #[cfg_attr(feature = "__ts-binding", ts(export))]
+    #[cfg_attr(feature = "wasm", derive(tsify::Tsify))]
+    #[cfg_attr(feature = "wasm", tsify(into_wasm_abi))]
+    #[serde(rename_all = "camelCase")]
+    #[allow(non_camel_case_types)]
+    pub struct InvView_MemoryChanges {
+        /// If the writes are traced for the step. If false, `changes` is always empty
+        pub is_traced: bool,
+        /// Writes that changed the value in memory, in the order they happened.
+        ///
+        /// Writes of the same value are not included
+        pub changes: Vec<InvView_MemoryChange>,
+        /// Changes to GDT flags, in the order they happened.
+        ///
+        /// The flag values are not stored in the memory, so they are
+        /// recorded separately from `changes`
+        pub gdt_changes: Vec<InvView_GdtFlagChange>,
+    }
+
+    /// One write to memory that changed the value
+    #[derive(Debug, PartialEq, Clone, Serialize)]
+    #[cfg_attr(feature = "__ts-binding", derive(ts_rs::TS))]
+    #[cfg_attr(feature = "__ts-binding", ts(export))]
+    #[cfg_attr(feature = "wasm", derive(tsify::Tsify))]
+    #[cfg_attr(feature = "wasm", tsify(into_wasm_abi))]
+    #[serde(rename_all = "camelCase")]
+    #[allow(non_camel_case_types)]
+    pub struct InvView_MemoryChange {
+        /// The structure the address is in
+        pub location: InvView_MemoryLocation,
+        /// Physical address written to
+        pub addr: iv::Pointer,
+        /// Number of bytes written
+        pub size: u32,
+        /// Value before the write
+        pub old: iv::Pointer,
+        /// Value after the write
+        pub new: iv::Pointer,
+        /// PC of the instruction that did the write
+        pub pc: iv::Pointer,
+        /// Start of the function that did the write
+        pub function: iv::Pointer,
+        /// Name of the function that did the write, if symbols are available,
+        /// otherwise empty
+        pub function_name: String,
+    }
+
+    /// One change to a GDT flag
+    #[derive(Debug, PartialEq, Clone, Serialize)]
+    #[cfg_attr(feature = "__ts-binding", derive(ts_rs::TS))]
+    #[cfg_attr(feature = "__ts-binding", ts(export))]
+    #[cfg_attr(feature = "wasm", derive(tsify::Tsify))]
+    #[cfg_attr(feature = "wasm", tsify(into_wasm_abi))]
+    #[serde(rename_all = "camelCase")]
+    #[allow(non_camel_case_types)]
+    pub struct InvView_GdtFlagChange {
+        /// Name of the flag if the game accessed it by name,
+        /// otherwise the hash of the name in hex
+        pub flag: String,
+        /// Index of the element, if the flag is an array
+        pub array_idx: Option<i32>,
+        /// Value before the change, formatted for display
+        pub old: String,
+        /// Value after the change, formatted for display
+        pub new: String,
+        /// PC of the last instruction executed before the change
+        pub pc: iv::Pointer,
+        /// Start of the function that changed the flag
+        pub function: iv::Pointer,
+        /// Name of the function that changed the flag, if symbols are available,
+        /// otherwise empty
+        pub function_name: String,
+    }
+
+    /// Known structure where a write is in
+    #[derive(Debug, Clone, PartialEq, Serialize)]
+    #[cfg_attr(feature = "__ts-binding", derive(ts_rs::TS))]
+    #[cfg_attr(feature = "__ts-binding", ts(export))]
+    #[cfg_attr(feature = "wasm", derive(tsify::Tsify))]
+    #[cfg_attr(feature = "wasm", tsify(into_wasm_abi))]
+    #[serde(rename_all = "kebab-case", tag = "type")]
+    #[allow(non_camel_case_types)]
+    pub enum InvView_MemoryLocation {
+        /// A field of the item at `index` in `mItemBuffer` of PauseMenuDataMgr (i.e. list node)
+        PouchItem { index: i32, field: String },
+        /// `mTabs[index]` of PauseMenuDataMgr
+        Tabs { index: i32 },
+        /// `mTabsType[index]` of PauseMenuDataMgr
+        TabsType { index: i32 },
+        /// Other fields of PauseMenuDataMgr
+        Pmdm { field: String },
+        /// Memory of the GDT TriggerParam proxy object. This is not
+        /// where the flag values are stored, see `gdt_changes` for the flags changed
+        Gdt,
+        /// Not in a known structure
+        Other,
+    }
+}
+pub use __impl::InvView_GdtFlagChange as GdtFlagChange;
+pub use __impl::InvView_MemoryChange as MemoryChange;
+pub use __impl::InvView_MemoryChanges as MemoryChanges;
+pub use __impl::InvView_MemoryLocation as MemoryLocation;
@@ -6,5 +6,15 @@ mod overworld;
 pub use overworld::*;
 mod gdt;
 pub use gdt::*;
+mod gdt_flag;
+pub use gdt_flag::*;
 mod pointer;
 pub use pointer::*;
+mod memory;
+pub use memory::*;
+mod inspect;
+pub use inspect::*;
+mod crash;
+pub use crash::*;
+mod save;
+pub use save::*;
@@ -0,0 +1,77 @@
+//! Comparison of the inventories in the saves with the current GDT inventory
+
+mod __impl {
+    use serde::Serialize;
+
+    use crate::runtime::iv;
+
+    /// GDT inventories of all saves side by side with the current GDT inventory
+    #[derive(Debug, Default, PartialEq, Serialize)]
+    #[cfg_attr(feature = "__ts-binding", derive(ts_rs::TS))]
+    #[cfg_attr(feature = "__ts-binding", ts(export))]
+    #[cfg_attr(feature = "wasm", derive(tsify::Tsify))]
+    #[cfg_attr(feature = "wasm", tsify(into_wasm_abi))]
+    #[serde(rename_all = "camelCase")]
+    #[allow(non_camel_case_types)]
+    pub struct InvView_SaveComparison {
+        /// The current GDT inventory, `None` if the game is not running
+        pub current: Option<iv::Gdt>,
+        /// The manual save, if exists
+        pub manual_save: Option<InvView_SaveInventory>,
+        /// The named saves, in the order they were first saved
+        pub saves: Vec<InvView_SaveInventory>,
+    }
+
+    /// GDT inventory in one save, with the differences from the current GDT inventory
+    #[derive(Debug, Default, PartialEq, Serialize)]
+    #[cfg_attr(feature = "__ts-binding", derive(ts_rs::TS))]
+    #[cfg_attr(feature = "__ts-binding", ts(export))]
+    #[cfg_attr(feature = "wasm", derive(tsify::Tsify))]
+    #[cfg_attr(feature = "wasm", tsify(into_wasm_abi))]
+    #[serde(rename_all = "camelCase")]
+    #[allow(non_camel_case_types)]
+    pub struct InvView_SaveInventory {
+        /// Name of the save, empty for the manual save
+        pub name: String,
+        /// The GDT inventory in the save
+        pub gdt: iv::Gdt,
+        /// Difference of each item in the save, with the same index as `gdt.items`.
+        /// Items are matched with the current GDT by the actor name and the metadata,
+        /// not by the slot.
+        ///
+        /// Empty if the game is not running
+        pub item_diffs: Vec<InvView_GdtItemDiff>,
+        /// `idx` of the items in the current GDT that are not in the save,
+        /// i.e. the items that reloading the save will remove
+        ///
+        /// Empty if the game is not running
+        pub removed_items: Vec<u32>,
+        /// If the Master Sword flags are different from the current GDT
+        pub master_sword_differs: bool,
+        /// If the other inventory flags are different from the current GDT
+        pub info_differs: bool,
+    }
+
+    /// Difference of an item in a save, compared to the current GDT,
+    /// i.e. what reloading the save will do to the item
+    #[derive(Debug, Default, PartialEq, Eq, Clone, Copy, Serialize)]
+    #[cfg_attr(feature = "__ts-binding", derive(ts_rs::TS))]
+    #[cfg_attr(feature = "__ts-binding", ts(export))]
+    #[cfg_attr(feature = "wasm", derive(tsify::Tsify))]
+    #[cfg_attr(feature = "wasm", tsify(into_wasm_abi))]
+    #[serde(rename_all = "camelCase")]
+    #[allow(non_camel_case_types)]
+    pub enum InvView_GdtItemDiff {
+        /// The same item is in the current GDT
+        #[default]
+        Same,
+        /// The item is not in the current GDT
+        Added,
+        /// The item is in the current GDT, but with a different
+        /// value (count or durability) or equipped state
+        Changed,
+    }
+}
+pub use __impl::InvView_GdtItemDiff as GdtItemDiff;
+pub use __impl::InvView_SaveComparison as SaveComparison;
+pub use __impl::InvView_SaveInventory as SaveInventory;
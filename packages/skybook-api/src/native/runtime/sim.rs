@@ -49,4 +49,29 @@ pub struct RuntimeInitParams {
     /// Unspecified (empty string) means using the internal default
     #[serde(default)]
     pub pmdm_addr: String,
+
+    /// BlueFlame features to enable, in addition to the default features.
+    /// Feature names are in kebab-case, for example `mem-track-free`
+    #[serde(default)]
+    pub enable_features: Vec<String>,
+
+    /// BlueFlame features to disable. This is applied after `enable_features`
+    #[serde(default)]
+    pub disable_features: Vec<String>,
+
+    /// Maximum number of instructions the game can execute in one step.
+    /// If exceeded, the step stops with an error instead of hanging
+    /// (for example, when the game is stuck in an infinite loop)
+    ///
+    /// Unspecified, or 0, means using the internal default
+    #[serde(default)]
+    pub instruction_limit: u64,
+
+    /// Maximum time in milliseconds the game can execute in one step.
+    /// If exceeded, the step stops with an error instead of hanging.
+    /// Unlike `instruction_limit`, this depends on how fast the host is
+    ///
+    /// Unspecified, or 0, means no time limit
+    #[serde(default)]
+    pub time_limit: u64,
 }
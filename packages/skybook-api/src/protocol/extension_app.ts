@@ -3,9 +3,11 @@ import type { WxPromise } from "@pistonite/workex";
 
 import type { Diagnostic, ItemSearchResult, ItemDragData } from "#types";
 import type {
+    InvView_CrashReport,
     InvView_Gdt,
     InvView_Overworld,
     InvView_PouchList,
+    InvView_SaveComparison,
     MaybeAborted,
     RuntimeViewError,
 } from "#native";
@@ -133,6 +135,21 @@ export interface ExtensionApp {
         charPos: number | undefined,
     ): WxPromise<MaybeAborted<Result<InvView_Gdt, RuntimeViewError>>>;
 
+    /**
+     * Get the GDT inventories of the manual save and all named saves at the character position
+     * `charPos` in the script, side by side with the current GDT inventory. Each save has the
+     * differences from the current GDT inventory, i.e. what reloading the save will change.
+     *
+     * Pass in `undefined` for `script` and/or `charPos` to use the current state of the application.
+     * However, if `script` is not `undefined` and `charPos` is `undefined`,
+     * `charPos` defaults to 0
+     */
+    getSaveComparison(
+        taskId: string,
+        script: string | undefined,
+        charPos: number | undefined,
+    ): WxPromise<MaybeAborted<Result<InvView_SaveComparison, RuntimeViewError>>>;
+
     /**
      * Get the overworld state at the character position `charPos` in the script
      *
@@ -159,6 +176,21 @@ export interface ExtensionApp {
         charPos: number | undefined,
     ): WxPromise<MaybeAborted<string>>;
 
+    /**
+     * Get the structured crash report at the character position `charPos` in the script.
+     * `isCrashed` is false in the report if the game has not crashed at or before the position.
+     * The `span` of the step in the report is in character positions
+     *
+     * Pass in `undefined` for `script` and/or `charPos` to use the current state of the application.
+     * However, if `script` is not `undefined` and `charPos` is `undefined`,
+     * `charPos` defaults to 0
+     */
+    getCrashReport(
+        taskId: string,
+        script: string | undefined,
+        charPos: number | undefined,
+    ): WxPromise<MaybeAborted<InvView_CrashReport>>;
+
     /**
      * Get the list of save names at the character position `charPos` in the script.
      * Manual save is not included in the list.
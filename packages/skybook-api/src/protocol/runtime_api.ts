@@ -3,9 +3,14 @@ import type { Result } from "@pistonite/pure/result";
 
 import type {
     ErrorReport,
+    InvView_CrashReport,
     InvView_Gdt,
+    InvView_GdtFlag,
+    InvView_GdtFlagQuery,
+    InvView_InspectNode,
     InvView_Overworld,
     InvView_PouchList,
+    InvView_SaveComparison,
     MaybeAborted,
     ParserError,
     RuntimeError,
@@ -107,6 +112,33 @@ export interface Runtime {
         pos: number,
     ): WxPromise<MaybeAborted<Result<InvView_Gdt, RuntimeViewError>>>;
 
+    /**
+     * Execute the script if not up-to-date, and return the inventories of the manual save
+     * and all named saves at the byte offset `pos` in the script, side by side with the
+     * current GDT inventory. Each save has the differences from the current GDT inventory,
+     * i.e. what reloading the save will change. `current` is undefined if the game is not running.
+     *
+     * The taskId should be a UUID, and can be passed into abortTask() to abort this run
+     */
+    getSaveComparison(
+        script: string,
+        taskId: string,
+        pos: number,
+    ): WxPromise<MaybeAborted<Result<InvView_SaveComparison, RuntimeViewError>>>;
+
+    /**
+     * Execute the script if not up-to-date, and query the GDT flags by name or hash
+     * at the byte offset `pos` in the script. Flags that are not found have no value.
+     *
+     * The taskId should be a UUID, and can be passed into abortTask() to abort this run
+     */
+    getGdtFlags(
+        script: string,
+        taskId: string,
+        pos: number,
+        queries: InvView_GdtFlagQuery[],
+    ): WxPromise<MaybeAborted<Result<InvView_GdtFlag[], RuntimeViewError>>>;
+
     /**
      * Execute the script if not up-to-date, and return the overworld item view
      * at the byte offset `pos` in the script.
@@ -119,6 +151,19 @@ export interface Runtime {
         pos: number,
     ): WxPromise<MaybeAborted<Result<InvView_Overworld, RuntimeViewError>>>;
 
+    /**
+     * Execute the script if not up-to-date, and inspect the PauseMenuDataMgr
+     * at the byte offset `pos` in the script as a tree of typed fields.
+     * Pointers are annotated with what they point to.
+     *
+     * The taskId should be a UUID, and can be passed into abortTask() to abort this run
+     */
+    getPmdmTree(
+        script: string,
+        taskId: string,
+        pos: number,
+    ): WxPromise<MaybeAborted<Result<InvView_InspectNode, RuntimeViewError>>>;
+
     /**
      * Execute the script if not up-to-date. If at the byte offset `pos` in the script,
      * the game crashed, return the rendered crash report as a string. Otherwise return
@@ -128,6 +173,20 @@ export interface Runtime {
      */
     getCrashInfo(script: string, taskId: string, pos: number): WxPromise<MaybeAborted<string>>;
 
+    /**
+     * Execute the script if not up-to-date, and return the crash report at the byte offset
+     * `pos` in the script with the registers, faulting memory access, disassembly around PC,
+     * stack trace, and the step that crashed the game. `isCrashed` is false if the game
+     * has not crashed at or before `pos`.
+     *
+     * The taskId should be a UUID, and can be passed into abortTask() to abort this run
+     */
+    getCrashReport(
+        script: string,
+        taskId: string,
+        pos: number,
+    ): WxPromise<MaybeAborted<InvView_CrashReport>>;
+
     /**
      * Execute the script if not up-to-date, and return the list of save names at the
      * byte offset `pos` in the script. The list does not include the manual save.
@@ -148,4 +207,20 @@ export interface Runtime {
         pos: number,
         name: string | undefined,
     ): WxPromise<MaybeAborted<Result<InvView_Gdt, RuntimeViewError>>>;
+
+    /**
+     * Execute the script if not up-to-date, and query the GDT flags by name or hash
+     * in the manual or named save at the byte offset `pos` in the script
+     * (use undefined to refer to the manual save). If the save doesn't exist,
+     * the result is empty
+     *
+     * The taskId should be a UUID, and can be passed into abortTask() to abort this run
+     */
+    getSaveGdtFlags(
+        script: string,
+        taskId: string,
+        pos: number,
+        name: string | undefined,
+        queries: InvView_GdtFlagQuery[],
+    ): WxPromise<MaybeAborted<InvView_GdtFlag[]>>;
 }